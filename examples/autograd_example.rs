@@ -7,7 +7,7 @@ fn main() {
 
     println!("1. Simple gradient computation:");
     
-    let options = Options::new().requires_grad(true);
+    let _options = Options::new().requires_grad(true);
     let x = Tensor::from_array_1d(vec![2.0f32, 3.0]);
     let y = Tensor::from_array_1d(vec![1.0f32, 4.0]);
     
@@ -2,6 +2,7 @@
 use rusted_torch::*;
 use rusted_torch::autograd::function;
 use rusted_torch::functions::{mse_loss, nll_loss, dropout, LossReduction};
+use rusted_torch::nn::{Linear, Module, Sequential};
 
 fn main() {
     println!("=== Neural Network Basics Example ===\n");
@@ -113,5 +114,19 @@ fn main() {
     println!("Raw output: {:?}", output.to_list::<f32>());
     println!("Probabilities: {:?}", probabilities.to_list::<f32>());
 
+    println!("\n6. Sequential model:");
+
+    let model = Sequential::new()
+        .with_module(Linear::new(3, 4, true))
+        .with_module(Linear::new(4, 3, true));
+    let logits = model.forward(&input_data);
+    let probabilities = function::function::softmax(&logits, 1);
+
+    for (name, parameter) in model.named_parameters() {
+        println!("{}: {:?}", name, parameter.shape());
+    }
+    println!("Model output: {:?}", logits.to_list::<f32>());
+    println!("Probabilities: {:?}", probabilities.to_list::<f32>());
+
     println!("\n=== Example completed successfully! ===");
 }
@@ -1,9 +1,12 @@
+use crate::autograd::Node;
 use crate::tensor::Tensor;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct AutogradMeta {
     pub grad: Option<Tensor>,
     requires_grad: bool,
+    grad_fn: Option<Rc<Node>>,
 }
 
 impl AutogradMeta {
@@ -11,6 +14,7 @@ impl AutogradMeta {
         Self {
             grad: None,
             requires_grad: false,
+            grad_fn: None,
         }
     }
 
@@ -45,8 +49,16 @@ impl AutogradMeta {
         self.requires_grad = requires_grad;
     }
 
+    pub fn grad_fn(&self) -> Option<Rc<Node>> {
+        self.grad_fn.clone()
+    }
+
+    pub fn set_grad_fn(&mut self, grad_fn: Option<Rc<Node>>) {
+        self.grad_fn = grad_fn;
+    }
+
     pub fn is_leaf(&self) -> bool {
-        true
+        self.grad_fn.is_none()
     }
 
     pub fn backward(&mut self, grad: &Tensor) {
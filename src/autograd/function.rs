@@ -1,8 +1,18 @@
-use crate::tensor::Tensor;
+use crate::autograd::no_grad;
+use crate::tensor::{BinaryOp, Tensor, UnaryOp};
 
-pub trait Function {
+pub trait Function: Sized + 'static {
     fn forward(&self, inputs: &[Tensor]) -> Tensor;
-    fn backward(&self, grad_output: &Tensor) -> Vec<Tensor>;
+    fn backward(&self, inputs: &[Tensor], grad_output: &Tensor) -> Vec<Tensor>;
+
+    fn apply(self, inputs: &[Tensor]) -> Tensor {
+        let output = no_grad(|| self.forward(inputs)).detach();
+        let saved: Vec<Tensor> = inputs.iter().map(Tensor::detach).collect();
+        let recorded: Vec<&Tensor> = inputs.iter().collect();
+        output.with_grad_fn(std::any::type_name::<Self>(), &recorded, move |grad| {
+            self.backward(&saved, grad)
+        })
+    }
 }
 
 pub struct AddFunction;
@@ -15,7 +25,7 @@ impl Function for AddFunction {
         &inputs[0] + &inputs[1]
     }
 
-    fn backward(&self, grad_output: &Tensor) -> Vec<Tensor> {
+    fn backward(&self, _inputs: &[Tensor], grad_output: &Tensor) -> Vec<Tensor> {
         vec![grad_output.clone(), grad_output.clone()]
     }
 }
@@ -30,24 +40,12 @@ impl Function for MulFunction {
         &inputs[0] * &inputs[1]
     }
 
-    fn backward(&self, grad_output: &Tensor) -> Vec<Tensor> {
-        if let Some(inputs) = self.get_saved_inputs() {
-            vec![
-                &*grad_output * &inputs[1],
-                &*grad_output * &inputs[0],
-            ]
-        } else {
-            vec![grad_output.clone(), grad_output.clone()]
-        }
-    }
-}
-
-impl MulFunction {
-    fn get_saved_inputs(&self) -> Option<Vec<Tensor>> {
-        None
+    fn backward(&self, inputs: &[Tensor], grad_output: &Tensor) -> Vec<Tensor> {
+        vec![grad_output * &inputs[1], grad_output * &inputs[0]]
     }
 }
 
+#[allow(clippy::module_inception)]
 pub mod function {
     use super::*;
 
@@ -56,25 +54,7 @@ pub mod function {
     }
 
     pub fn sub(a: &Tensor, b: &Tensor) -> Tensor {
-        if !a.defined() || !b.defined() {
-            return Tensor::new();
-        }
-
-        let a_data = a.to_list::<f32>();
-        let b_data = b.to_list::<f32>();
-        
-        let result_data: Vec<f32> = a_data.iter().zip(b_data.iter())
-            .map(|(&x, &y)| x - y)
-            .collect();
-
-        let shape = a.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        a.apply_binary(b, BinaryOp::Sub)
     }
 
     pub fn mul(a: &Tensor, b: &Tensor) -> Tensor {
@@ -82,61 +62,15 @@ pub mod function {
     }
 
     pub fn div(a: &Tensor, b: &Tensor) -> Tensor {
-        if !a.defined() || !b.defined() {
-            return Tensor::new();
-        }
-
-        let a_data = a.to_list::<f32>();
-        let b_data = b.to_list::<f32>();
-        
-        let result_data: Vec<f32> = a_data.iter().zip(b_data.iter())
-            .map(|(&x, &y)| x / y)
-            .collect();
-
-        let shape = a.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        a.apply_binary(b, BinaryOp::Div)
     }
 
     pub fn sin(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| val.sin()).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::Sin)
     }
 
     pub fn cos(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| val.cos()).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::Cos)
     }
 
     pub fn pow(base: &Tensor, exponent: &Tensor) -> Tensor {
@@ -148,61 +82,19 @@ pub mod function {
     }
 
     pub fn relu(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| val.max(0.0)).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        let input = x.detach();
+        x.apply_unary(UnaryOp::Relu)
+            .with_grad_fn("ReluBackward0", &[x], move |grad| {
+                vec![grad * &input.gt(&Tensor::scalar(0.0f32))]
+            })
     }
 
     pub fn gelu(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| {
-            0.5 * val * (1.0 + (val * 0.7978845608 * (1.0 + 0.044715 * val * val)).tanh())
-        }).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::Gelu)
     }
 
     pub fn silu(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| {
-            val / (1.0 + (-val).exp())
-        }).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::Silu)
     }
 
     pub fn softmax(x: &Tensor, _dim: i64) -> Tensor {
@@ -253,80 +145,26 @@ pub mod function {
     }
 
     pub fn tanh(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| val.tanh()).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        let result = x.apply_unary(UnaryOp::Tanh);
+        let output = result.detach();
+        result.with_grad_fn("TanhBackward0", &[x], move |grad| {
+            vec![grad * &(&Tensor::scalar(1.0f32) - &(&output * &output))]
+        })
     }
 
     pub fn sigmoid(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| {
-            1.0 / (1.0 + (-val).exp())
-        }).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        let result = x.apply_unary(UnaryOp::Sigmoid);
+        let output = result.detach();
+        result.with_grad_fn("SigmoidBackward0", &[x], move |grad| {
+            vec![grad * &(&output * &(&Tensor::scalar(1.0f32) - &output))]
+        })
     }
 
     pub fn leaky_relu(x: &Tensor, negative_slope: f32) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| {
-            if val > 0.0 { val } else { negative_slope * val }
-        }).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::LeakyRelu { negative_slope })
     }
 
     pub fn swish(x: &Tensor) -> Tensor {
-        if !x.defined() {
-            return Tensor::new();
-        }
-
-        let data = x.to_list::<f32>();
-        let result_data: Vec<f32> = data.iter().map(|&val| {
-            val / (1.0 + (-val).exp())
-        }).collect();
-
-        let shape = x.shape();
-        let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-        match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(std::rc::Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        x.apply_unary(UnaryOp::Silu)
     }
 }
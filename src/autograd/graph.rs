@@ -0,0 +1,169 @@
+use crate::autograd::AutogradMeta;
+use crate::tensor::Tensor;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+type BackwardFn = Box<dyn Fn(&Tensor) -> Vec<Tensor>>;
+
+pub struct Node {
+    name: &'static str,
+    inputs: Vec<Tensor>,
+    backward: BackwardFn,
+}
+
+impl Node {
+    pub fn new(
+        name: &'static str,
+        inputs: Vec<Tensor>,
+        backward: impl Fn(&Tensor) -> Vec<Tensor> + 'static,
+    ) -> Self {
+        Self {
+            name,
+            inputs,
+            backward: Box::new(backward),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn inputs(&self) -> &[Tensor] {
+        &self.inputs
+    }
+
+    pub fn apply(&self, grad_output: &Tensor) -> Vec<Tensor> {
+        (self.backward)(grad_output)
+    }
+}
+
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("name", &self.name)
+            .field("inputs", &self.inputs.len())
+            .finish()
+    }
+}
+
+thread_local! {
+    static GRAD_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+pub fn is_grad_enabled() -> bool {
+    GRAD_ENABLED.with(|enabled| enabled.get())
+}
+
+pub fn set_grad_enabled(enabled: bool) {
+    GRAD_ENABLED.with(|current| current.set(enabled));
+}
+
+pub struct NoGradGuard {
+    previous: bool,
+}
+
+impl NoGradGuard {
+    pub fn new() -> Self {
+        let previous = is_grad_enabled();
+        set_grad_enabled(false);
+        Self { previous }
+    }
+}
+
+impl Default for NoGradGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NoGradGuard {
+    fn drop(&mut self) {
+        set_grad_enabled(self.previous);
+    }
+}
+
+pub fn no_grad<R>(f: impl FnOnce() -> R) -> R {
+    let _guard = NoGradGuard::new();
+    f()
+}
+
+type MetaRef = Rc<RefCell<AutogradMeta>>;
+
+fn autograd_meta(tensor: &Tensor) -> Option<&MetaRef> {
+    tensor.impl_.as_ref().and_then(|impl_| impl_.autograd_meta.as_ref())
+}
+
+fn requires_grad(tensor: &Tensor) -> bool {
+    autograd_meta(tensor).is_some_and(|meta| meta.borrow().requires_grad())
+}
+
+pub(crate) fn run_backward(root: &Tensor, grad: &Tensor) {
+    let root_meta = match autograd_meta(root) {
+        Some(meta) => Rc::clone(meta),
+        None => return,
+    };
+
+    let mut order: Vec<MetaRef> = Vec::new();
+    let mut visited: HashSet<*const RefCell<AutogradMeta>> = HashSet::new();
+    let mut stack: Vec<(MetaRef, bool)> = vec![(root_meta, false)];
+    while let Some((meta, expanded)) = stack.pop() {
+        if expanded {
+            order.push(meta);
+            continue;
+        }
+        if !visited.insert(Rc::as_ptr(&meta)) {
+            continue;
+        }
+        stack.push((Rc::clone(&meta), true));
+        if let Some(node) = meta.borrow().grad_fn() {
+            for input in node.inputs() {
+                if let Some(input_meta) = autograd_meta(input) {
+                    if requires_grad(input) && !visited.contains(&Rc::as_ptr(input_meta)) {
+                        stack.push((Rc::clone(input_meta), false));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut grads: HashMap<*const RefCell<AutogradMeta>, Tensor> = HashMap::new();
+    grads.insert(Rc::as_ptr(&order[order.len() - 1]), Clone::clone(grad));
+
+    no_grad(|| {
+        for meta in order.iter().rev() {
+            let grad = match grads.remove(&Rc::as_ptr(meta)) {
+                Some(grad) => grad,
+                None => continue,
+            };
+
+            let node = meta.borrow().grad_fn();
+            let node = match node {
+                Some(node) => node,
+                None => {
+                    meta.borrow_mut().backward(&grad);
+                    continue;
+                }
+            };
+
+            let input_grads = node.apply(&grad);
+            for (input, input_grad) in node.inputs().iter().zip(input_grads) {
+                if !input_grad.defined() || !requires_grad(input) {
+                    continue;
+                }
+                let input_meta = match autograd_meta(input) {
+                    Some(input_meta) => input_meta,
+                    None => continue,
+                };
+                let input_grad = input_grad.sum_to_size(&input.shape());
+                let key = Rc::as_ptr(input_meta);
+                let accumulated = match grads.remove(&key) {
+                    Some(existing) => &existing + &input_grad,
+                    None => input_grad,
+                };
+                grads.insert(key, accumulated);
+            }
+        }
+    });
+}
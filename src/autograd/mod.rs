@@ -1,8 +1,10 @@
 pub mod autograd_meta;
 pub mod function;
+pub mod graph;
 
 pub use autograd_meta::*;
 pub use function::*;
+pub use graph::*;
 
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,7 @@
 use crate::data::Dataset;
-use crate::tensor::{Tensor, TensorImpl, Options, DType};
+use crate::tensor::{Tensor, TensorImpl, Options, DType, Generator, with_generator};
 use std::rc::Rc;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 pub struct DataLoader<D: Dataset> {
     dataset: D,
@@ -11,6 +10,7 @@ pub struct DataLoader<D: Dataset> {
     drop_last: bool,
     indices: Vec<usize>,
     current_batch: usize,
+    generator: Option<Generator>,
 }
 
 impl<D: Dataset> DataLoader<D> {
@@ -25,13 +25,23 @@ impl<D: Dataset> DataLoader<D> {
             drop_last: false,
             indices,
             current_batch: 0,
+            generator: None,
         }
     }
     
     pub fn shuffle(mut self, shuffle: bool) -> Self {
         self.shuffle = shuffle;
         if shuffle {
-            self.indices.shuffle(&mut thread_rng());
+            self.shuffle_indices();
+        }
+        self
+    }
+    
+    pub fn generator(mut self, generator: Generator) -> Self {
+        self.generator = Some(generator);
+        if self.shuffle {
+            self.indices = (0..self.dataset.len()).collect();
+            self.shuffle_indices();
         }
         self
     }
@@ -56,10 +66,15 @@ impl<D: Dataset> DataLoader<D> {
     pub fn reset(&mut self) {
         self.current_batch = 0;
         if self.shuffle {
-            self.indices.shuffle(&mut thread_rng());
+            self.shuffle_indices();
         }
     }
     
+    fn shuffle_indices(&mut self) {
+        let indices = &mut self.indices;
+        with_generator(self.generator.as_mut(), |rng| indices.shuffle(rng));
+    }
+    
     pub fn next_batch(&mut self) -> Option<(Tensor, Tensor)> {
         if self.current_batch >= self.len() {
             return None;
@@ -58,7 +58,7 @@ impl Dataset for TensorDataset {
         let features_shape = self.features.shape();
         let targets_shape = self.targets.shape();
         
-        if features_shape.len() < 2 || targets_shape.len() < 1 {
+        if features_shape.len() < 2 || targets_shape.is_empty() {
             return None;
         }
         
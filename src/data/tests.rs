@@ -1,3 +1,5 @@
+#![allow(unused_imports, unused_variables, dead_code, clippy::module_inception, clippy::excessive_precision)]
+
 use super::*;
 use crate::tensor::Tensor;
 
@@ -162,4 +164,24 @@ mod tests {
         let _batch2 = dataloader.next_batch().unwrap();
         assert!(dataloader.next_batch().is_none());
     }
+
+    #[test]
+    fn test_dataloader_seeded_shuffle() {
+        let make_loader = |seed: u64| {
+            let features = Tensor::from_array_2d((0..8).map(|i| vec![i as f32]).collect());
+            let targets = Tensor::from_array_1d((0..8).map(|i| i as f32).collect());
+            let dataset = TensorDataset::new(features, targets).unwrap();
+            DataLoader::new(dataset, 8)
+                .shuffle(true)
+                .generator(crate::tensor::Generator::with_seed(seed))
+        };
+
+        let (_, first) = make_loader(3).next_batch().unwrap();
+        let (_, second) = make_loader(3).next_batch().unwrap();
+        assert_eq!(first.to_list::<f32>(), second.to_list::<f32>());
+
+        let mut sorted = first.to_list::<f32>();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(sorted, (0..8).map(|i| i as f32).collect::<Vec<_>>());
+    }
 }
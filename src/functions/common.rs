@@ -0,0 +1,13 @@
+use crate::tensor::{dispatch_device, DType, Tensor};
+
+pub(super) fn float_operands(tensors: &[&Tensor]) -> bool {
+    tensors.iter().all(|tensor| tensor.defined() && tensor.dtype() == DType::Float32)
+        && matches!(dispatch_device(tensors), Some(device) if device.is_cpu() || device.is_meta())
+}
+
+pub(super) fn unravel(mut index: usize, shape: &[usize], digits: &mut [usize]) {
+    for d in (0..shape.len()).rev() {
+        digits[d] = index % shape[d];
+        index /= shape[d];
+    }
+}
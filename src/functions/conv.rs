@@ -1,263 +1,552 @@
-use crate::tensor::{Tensor, DType, Options, TensorImpl};
+use crate::functions::common::{float_operands, unravel};
+use crate::tensor::{meta_shape_rule, Tensor, DType, Options, TensorImpl};
 use std::rc::Rc;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding<T> {
+    Explicit(T),
+    Same,
+    Valid,
+}
+
+impl<T> From<T> for Padding<T> {
+    fn from(padding: T) -> Self {
+        Padding::Explicit(padding)
+    }
+}
+
+impl<T> Padding<T> {
+    pub(crate) fn into_vec(self, dims: impl FnOnce(T) -> Vec<i64>) -> Padding<Vec<i64>> {
+        match self {
+            Padding::Explicit(padding) => Padding::Explicit(dims(padding)),
+            Padding::Same => Padding::Same,
+            Padding::Valid => Padding::Valid,
+        }
+    }
+}
+
+pub fn conv1d(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: i64,
+    padding: impl Into<Padding<i64>>,
+    dilation: i64,
+    groups: i64,
+) -> Tensor {
+    let padding = padding.into().into_vec(|p| vec![p]);
+    convolution(input, weight, bias, &[stride], padding, &[dilation], groups)
+}
+
 pub fn conv2d(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: (i64, i64),
+    padding: impl Into<Padding<(i64, i64)>>,
+    dilation: (i64, i64),
+    groups: i64,
+) -> Tensor {
+    let padding = padding.into().into_vec(|(h, w)| vec![h, w]);
+    convolution(input, weight, bias, &[stride.0, stride.1], padding, &[dilation.0, dilation.1], groups)
+}
+
+pub fn conv3d(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: (i64, i64, i64),
+    padding: impl Into<Padding<(i64, i64, i64)>>,
+    dilation: (i64, i64, i64),
+    groups: i64,
+) -> Tensor {
+    let padding = padding.into().into_vec(|(d, h, w)| vec![d, h, w]);
+    let stride = [stride.0, stride.1, stride.2];
+    convolution(input, weight, bias, &stride, padding, &[dilation.0, dilation.1, dilation.2], groups)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn conv_transpose1d(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: i64,
+    padding: i64,
+    output_padding: i64,
+    groups: i64,
+    dilation: i64,
+) -> Tensor {
+    conv_transpose(input, weight, bias, &[stride], &[padding], &[output_padding], groups, &[dilation])
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn conv_transpose2d(
     input: &Tensor,
     weight: &Tensor,
     bias: Option<&Tensor>,
     stride: (i64, i64),
     padding: (i64, i64),
+    output_padding: (i64, i64),
+    groups: i64,
     dilation: (i64, i64),
 ) -> Tensor {
-    if !input.defined() || !weight.defined() {
+    conv_transpose(
+        input,
+        weight,
+        bias,
+        &[stride.0, stride.1],
+        &[padding.0, padding.1],
+        &[output_padding.0, output_padding.1],
+        groups,
+        &[dilation.0, dilation.1],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn conv_transpose3d(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: (i64, i64, i64),
+    padding: (i64, i64, i64),
+    output_padding: (i64, i64, i64),
+    groups: i64,
+    dilation: (i64, i64, i64),
+) -> Tensor {
+    conv_transpose(
+        input,
+        weight,
+        bias,
+        &[stride.0, stride.1, stride.2],
+        &[padding.0, padding.1, padding.2],
+        &[output_padding.0, output_padding.1, output_padding.2],
+        groups,
+        &[dilation.0, dilation.1, dilation.2],
+    )
+}
+
+pub fn convolution(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: &[i64],
+    padding: Padding<Vec<i64>>,
+    dilation: &[i64],
+    groups: i64,
+) -> Tensor {
+    let operands: Vec<&Tensor> = [input, weight].into_iter().chain(bias).collect();
+    if !float_operands(&operands) || !valid_bias(bias, weight.shape().first().copied()) {
         return Tensor::new();
     }
+    let geometry = match ConvGeometry::new(&input.shape(), &weight.shape(), stride, padding, dilation, groups) {
+        Some(geometry) => geometry,
+        None => return Tensor::new(),
+    };
+    if let Some(output) = meta_shape_rule(&[input, weight], &geometry.output_shape(), input.dtype()) {
+        return output;
+    }
+
+    let input_data = input.to_list::<f32>();
+    let weight_data = weight.to_list::<f32>();
+    let bias_data = bias.map(|bias| bias.to_list::<f32>());
+    let mut output = geometry.forward(&input_data, &weight_data);
+    if let Some(bias) = &bias_data {
+        add_channel_bias(&mut output, bias, geometry.output.iter().product());
+    }
+    let output = tensor_from(&output, &geometry.output_shape());
 
+    let (input_grad, weight_grad, has_bias) = (input.requires_grad(), weight.requires_grad(), bias.is_some());
+    let backward = move |grad: &Tensor| {
+        let grad = grad.to_list::<f32>();
+        let mut grads = vec![
+            if input_grad {
+                tensor_from(&geometry.grad_input(&grad, &weight_data), &geometry.input_shape())
+            } else {
+                Tensor::new()
+            },
+            if weight_grad {
+                tensor_from(&geometry.grad_weight(&grad, &input_data), &geometry.weight_shape())
+            } else {
+                Tensor::new()
+            },
+        ];
+        if has_bias {
+            let sums = channel_sums(&grad, geometry.out_channels, geometry.output.iter().product());
+            grads.push(tensor_from(&sums, &[geometry.out_channels as i64]));
+        }
+        grads
+    };
+    match bias {
+        Some(bias) => output.with_grad_fn("ConvolutionBackward0", &[input, weight, bias], backward),
+        None => output.with_grad_fn("ConvolutionBackward0", &[input, weight], backward),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn conv_transpose(
+    input: &Tensor,
+    weight: &Tensor,
+    bias: Option<&Tensor>,
+    stride: &[i64],
+    padding: &[i64],
+    output_padding: &[i64],
+    groups: i64,
+    dilation: &[i64],
+) -> Tensor {
+    let operands: Vec<&Tensor> = [input, weight].into_iter().chain(bias).collect();
+    if !float_operands(&operands) || groups <= 0 {
+        return Tensor::new();
+    }
+
+    let spatial = stride.len();
     let input_shape = input.shape();
     let weight_shape = weight.shape();
-    
-    if input_shape.len() != 4 || weight_shape.len() != 4 {
+    if input_shape.len() != spatial + 2
+        || weight_shape.len() != spatial + 2
+        || [padding.len(), output_padding.len(), dilation.len()].iter().any(|&len| len != spatial)
+        || weight_shape[0] != input_shape[1]
+    {
+        return Tensor::new();
+    }
+    let out_channels = weight_shape[1] * groups;
+    if !valid_bias(bias, Some(out_channels)) {
         return Tensor::new();
     }
+    for d in 0..spatial {
+        let op = output_padding[d];
+        if op < 0 || (op >= stride[d] && op >= dilation[d]) {
+            return Tensor::new();
+        }
+    }
 
-    let batch_size = input_shape[0];
-    let in_channels = input_shape[1];
-    let input_height = input_shape[2];
-    let input_width = input_shape[3];
-    
-    let out_channels = weight_shape[0];
-    let kernel_height = weight_shape[2];
-    let kernel_width = weight_shape[3];
-    
-    if weight_shape[1] != in_channels {
+    let mut output_shape = vec![input_shape[0], out_channels];
+    for d in 0..spatial {
+        output_shape.push(
+            (input_shape[d + 2] - 1) * stride[d] - 2 * padding[d]
+                + dilation[d] * (weight_shape[d + 2] - 1)
+                + output_padding[d]
+                + 1,
+        );
+    }
+    if output_shape[2..].iter().any(|&size| size < 0) {
         return Tensor::new();
     }
+    let padding = Padding::Explicit(padding.to_vec());
+    let geometry = match ConvGeometry::new(&output_shape, &weight_shape, stride, padding, dilation, groups) {
+        Some(geometry) if geometry.output_shape() == input_shape => geometry,
+        _ => return Tensor::new(),
+    };
+    if let Some(output) = meta_shape_rule(&[input, weight], &output_shape, input.dtype()) {
+        return output;
+    }
 
-    let output_height = (input_height + 2 * padding.0 - dilation.0 * (kernel_height - 1) - 1) / stride.0 + 1;
-    let output_width = (input_width + 2 * padding.1 - dilation.1 * (kernel_width - 1) - 1) / stride.1 + 1;
-    
-    let output_shape = vec![batch_size, out_channels, output_height, output_width];
-    let output_size = output_shape.iter().product::<i64>() as usize;
-    
     let input_data = input.to_list::<f32>();
     let weight_data = weight.to_list::<f32>();
-    let bias_data = bias.map(|b| b.to_list::<f32>());
-    
-    let mut output_data = vec![0.0f32; output_size];
-    
-    for batch in 0..batch_size as usize {
-        for out_ch in 0..out_channels as usize {
-            for out_h in 0..output_height as usize {
-                for out_w in 0..output_width as usize {
-                    let mut sum = 0.0f32;
-                    
-                    for in_ch in 0..in_channels as usize {
-                        for kh in 0..kernel_height as usize {
-                            for kw in 0..kernel_width as usize {
-                                let in_h = out_h as i64 * stride.0 - padding.0 + kh as i64 * dilation.0;
-                                let in_w = out_w as i64 * stride.1 - padding.1 + kw as i64 * dilation.1;
-                                
-                                if in_h >= 0 && in_h < input_height && in_w >= 0 && in_w < input_width {
-                                    let input_idx = batch * (in_channels as usize) * (input_height as usize) * (input_width as usize) +
-                                                  in_ch * (input_height as usize) * (input_width as usize) +
-                                                  (in_h as usize) * (input_width as usize) +
-                                                  (in_w as usize);
-                                    
-                                    let weight_idx = out_ch * (in_channels as usize) * (kernel_height as usize) * (kernel_width as usize) +
-                                                   in_ch * (kernel_height as usize) * (kernel_width as usize) +
-                                                   kh * (kernel_width as usize) +
-                                                   kw;
-                                    
-                                    sum += input_data[input_idx] * weight_data[weight_idx];
-                                }
-                            }
-                        }
-                    }
-                    
-                    if let Some(ref bias_vec) = bias_data {
-                        sum += bias_vec[out_ch];
-                    }
-                    
-                    let output_idx = batch * (out_channels as usize) * (output_height as usize) * (output_width as usize) +
-                                   out_ch * (output_height as usize) * (output_width as usize) +
-                                   out_h * (output_width as usize) +
-                                   out_w;
-                    
-                    output_data[output_idx] = sum;
-                }
-            }
+    let mut output = geometry.grad_input(&input_data, &weight_data);
+    if let Some(bias) = bias {
+        add_channel_bias(&mut output, &bias.to_list::<f32>(), geometry.input.iter().product());
+    }
+    let output = tensor_from(&output, &output_shape);
+
+    let (input_grad, weight_grad, has_bias) = (input.requires_grad(), weight.requires_grad(), bias.is_some());
+    let backward = move |grad: &Tensor| {
+        let grad = grad.to_list::<f32>();
+        let mut grads = vec![
+            if input_grad {
+                tensor_from(&geometry.forward(&grad, &weight_data), &geometry.output_shape())
+            } else {
+                Tensor::new()
+            },
+            if weight_grad {
+                tensor_from(&geometry.grad_weight(&input_data, &grad), &geometry.weight_shape())
+            } else {
+                Tensor::new()
+            },
+        ];
+        if has_bias {
+            let sums = channel_sums(&grad, geometry.in_channels, geometry.input.iter().product());
+            grads.push(tensor_from(&sums, &[geometry.in_channels as i64]));
         }
+        grads
+    };
+    match bias {
+        Some(bias) => output.with_grad_fn("ConvolutionBackward0", &[input, weight, bias], backward),
+        None => output.with_grad_fn("ConvolutionBackward0", &[input, weight], backward),
     }
-    
-    let options = Options::default().dtype(DType::Float32);
-    match TensorImpl::new_from_data(&output_data, &output_shape, options) {
-        Ok(impl_) => Tensor {
-            impl_: Some(Rc::new(impl_)),
-        },
-        Err(_) => Tensor::new(),
+}
+
+fn valid_bias(bias: Option<&Tensor>, channels: Option<i64>) -> bool {
+    match (bias, channels) {
+        (None, _) => true,
+        (Some(bias), Some(channels)) => bias.defined() && bias.shape() == [channels],
+        (Some(_), None) => false,
     }
 }
 
-pub fn max_pool2d(
-    input: &Tensor,
-    kernel_size: (i64, i64),
-    stride: Option<(i64, i64)>,
-    padding: (i64, i64),
-) -> Tensor {
-    if !input.defined() {
-        return Tensor::new();
+fn add_channel_bias(data: &mut [f32], bias: &[f32], size: usize) {
+    for (index, row) in data.chunks_mut(size.max(1)).enumerate() {
+        let value = bias[index % bias.len()];
+        row.iter_mut().for_each(|x| *x += value);
     }
+}
 
-    let input_shape = input.shape();
-    if input_shape.len() != 4 {
-        return Tensor::new();
+fn channel_sums(data: &[f32], channels: usize, size: usize) -> Vec<f32> {
+    let mut sums = vec![0.0f32; channels];
+    for (index, row) in data.chunks(size.max(1)).enumerate() {
+        sums[index % channels] += row.iter().sum::<f32>();
     }
+    sums
+}
 
-    let stride = stride.unwrap_or(kernel_size);
-    
-    let batch_size = input_shape[0];
-    let channels = input_shape[1];
-    let input_height = input_shape[2];
-    let input_width = input_shape[3];
-    
-    let output_height = (input_height + 2 * padding.0 - kernel_size.0) / stride.0 + 1;
-    let output_width = (input_width + 2 * padding.1 - kernel_size.1) / stride.1 + 1;
-    
-    let output_shape = vec![batch_size, channels, output_height, output_width];
-    let output_size = output_shape.iter().product::<i64>() as usize;
-    
-    let input_data = input.to_list::<f32>();
-    let mut output_data = vec![f32::NEG_INFINITY; output_size];
-    
-    for batch in 0..batch_size as usize {
-        for ch in 0..channels as usize {
-            for out_h in 0..output_height as usize {
-                for out_w in 0..output_width as usize {
-                    let mut max_val = f32::NEG_INFINITY;
-                    
-                    for kh in 0..kernel_size.0 as usize {
-                        for kw in 0..kernel_size.1 as usize {
-                            let in_h = out_h as i64 * stride.0 - padding.0 + kh as i64;
-                            let in_w = out_w as i64 * stride.1 - padding.1 + kw as i64;
-                            
-                            if in_h >= 0 && in_h < input_height && in_w >= 0 && in_w < input_width {
-                                let input_idx = batch * (channels as usize) * (input_height as usize) * (input_width as usize) +
-                                              ch * (input_height as usize) * (input_width as usize) +
-                                              (in_h as usize) * (input_width as usize) +
-                                              (in_w as usize);
-                                
-                                max_val = max_val.max(input_data[input_idx]);
-                            }
-                        }
-                    }
-                    
-                    let output_idx = batch * (channels as usize) * (output_height as usize) * (output_width as usize) +
-                                   ch * (output_height as usize) * (output_width as usize) +
-                                   out_h * (output_width as usize) +
-                                   out_w;
-                    
-                    output_data[output_idx] = max_val;
-                }
+#[derive(Debug, Clone)]
+struct ConvGeometry {
+    batch: usize,
+    in_channels: usize,
+    out_channels: usize,
+    groups: usize,
+    input: Vec<usize>,
+    kernel: Vec<usize>,
+    output: Vec<usize>,
+    stride: Vec<usize>,
+    dilation: Vec<usize>,
+    padding: Vec<i64>,
+}
+
+impl ConvGeometry {
+    fn new(
+        input_shape: &[i64],
+        weight_shape: &[i64],
+        stride: &[i64],
+        padding: Padding<Vec<i64>>,
+        dilation: &[i64],
+        groups: i64,
+    ) -> Option<Self> {
+        let spatial = stride.len();
+        if input_shape.len() != spatial + 2 || weight_shape.len() != spatial + 2 || dilation.len() != spatial {
+            return None;
+        }
+        if stride.iter().chain(dilation.iter()).any(|&value| value <= 0) || groups <= 0 {
+            return None;
+        }
+
+        let (in_channels, out_channels) = (input_shape[1], weight_shape[0]);
+        if in_channels % groups != 0 || out_channels % groups != 0 || weight_shape[1] * groups != in_channels {
+            return None;
+        }
+
+        let kernel = &weight_shape[2..];
+        let extents: Vec<i64> = kernel.iter().zip(dilation.iter()).map(|(&k, &d)| d * (k - 1)).collect();
+        let (before, after) = match padding {
+            Padding::Explicit(padding) if padding.len() == spatial && padding.iter().all(|&p| p >= 0) => {
+                (padding.clone(), padding)
+            }
+            Padding::Explicit(_) => return None,
+            Padding::Valid => (vec![0; spatial], vec![0; spatial]),
+            Padding::Same if stride.iter().all(|&s| s == 1) => {
+                let before: Vec<i64> = extents.iter().map(|&extent| extent / 2).collect();
+                let after = extents.iter().zip(before.iter()).map(|(&extent, &b)| extent - b).collect();
+                (before, after)
+            }
+            Padding::Same => return None,
+        };
+
+        let mut output = Vec::with_capacity(spatial);
+        for d in 0..spatial {
+            let span = input_shape[d + 2] + before[d] + after[d] - extents[d] - 1;
+            if span < 0 {
+                return None;
             }
+            output.push((span / stride[d] + 1) as usize);
         }
+
+        Some(Self {
+            batch: input_shape[0] as usize,
+            in_channels: in_channels as usize,
+            out_channels: out_channels as usize,
+            groups: groups as usize,
+            input: input_shape[2..].iter().map(|&size| size as usize).collect(),
+            kernel: kernel.iter().map(|&size| size as usize).collect(),
+            output,
+            stride: stride.iter().map(|&s| s as usize).collect(),
+            dilation: dilation.iter().map(|&d| d as usize).collect(),
+            padding: before,
+        })
     }
-    
-    let options = Options::default().dtype(DType::Float32);
-    match TensorImpl::new_from_data(&output_data, &output_shape, options) {
-        Ok(impl_) => Tensor {
-            impl_: Some(Rc::new(impl_)),
-        },
-        Err(_) => Tensor::new(),
+
+    fn input_shape(&self) -> Vec<i64> {
+        let mut shape = vec![self.batch as i64, self.in_channels as i64];
+        shape.extend(self.input.iter().map(|&size| size as i64));
+        shape
     }
-}
 
-pub fn batch_norm2d(
-    input: &Tensor,
-    weight: Option<&Tensor>,
-    bias: Option<&Tensor>,
-    running_mean: Option<&Tensor>,
-    running_var: Option<&Tensor>,
-    training: bool,
-    _momentum: f32,
-    eps: f32,
-) -> Tensor {
-    if !input.defined() {
-        return Tensor::new();
+    fn weight_shape(&self) -> Vec<i64> {
+        let mut shape = vec![self.out_channels as i64, (self.in_channels / self.groups) as i64];
+        shape.extend(self.kernel.iter().map(|&size| size as i64));
+        shape
     }
 
-    let input_shape = input.shape();
-    if input_shape.len() != 4 {
-        return Tensor::new();
+    fn output_shape(&self) -> Vec<i64> {
+        let mut shape = vec![self.batch as i64, self.out_channels as i64];
+        shape.extend(self.output.iter().map(|&size| size as i64));
+        shape
     }
 
-    let batch_size = input_shape[0] as usize;
-    let channels = input_shape[1] as usize;
-    let height = input_shape[2] as usize;
-    let width = input_shape[3] as usize;
-    
-    let input_data = input.to_list::<f32>();
-    let mut output_data = vec![0.0f32; input_data.len()];
-    
-    let weight_data = weight.map(|w| w.to_list::<f32>());
-    let bias_data = bias.map(|b| b.to_list::<f32>());
-    
-    for ch in 0..channels {
-        let mut mean = 0.0f32;
-        let mut var = 0.0f32;
-        
-        if training {
-            let mut sum = 0.0f32;
-            let count = (batch_size * height * width) as f32;
-            
-            for batch in 0..batch_size {
-                for h in 0..height {
-                    for w in 0..width {
-                        let idx = batch * channels * height * width + ch * height * width + h * width + w;
-                        sum += input_data[idx];
+    fn gather_table(&self) -> Vec<isize> {
+        let kernel_size: usize = self.kernel.iter().product();
+        let output_size: usize = self.output.iter().product();
+        let mut table = Vec::with_capacity(kernel_size * output_size);
+        let mut k = vec![0; self.kernel.len()];
+        let mut o = vec![0; self.output.len()];
+        for kk in 0..kernel_size {
+            unravel(kk, &self.kernel, &mut k);
+            for oo in 0..output_size {
+                unravel(oo, &self.output, &mut o);
+                let mut offset = 0isize;
+                for d in 0..self.input.len() {
+                    let coord = (o[d] * self.stride[d] + k[d] * self.dilation[d]) as i64 - self.padding[d];
+                    if coord < 0 || coord >= self.input[d] as i64 {
+                        offset = -1;
+                        break;
                     }
+                    offset = offset * self.input[d] as isize + coord as isize;
                 }
+                table.push(offset);
             }
-            mean = sum / count;
-            
-            let mut sum_sq_diff = 0.0f32;
-            for batch in 0..batch_size {
-                for h in 0..height {
-                    for w in 0..width {
-                        let idx = batch * channels * height * width + ch * height * width + h * width + w;
-                        let diff = input_data[idx] - mean;
-                        sum_sq_diff += diff * diff;
-                    }
-                }
+        }
+        table
+    }
+
+    fn sizes(&self) -> (usize, usize, usize, usize, usize) {
+        let input_size: usize = self.input.iter().product();
+        let output_size: usize = self.output.iter().product();
+        let (group_in, group_out) = (self.in_channels / self.groups, self.out_channels / self.groups);
+        let rows = group_in * self.kernel.iter().product::<usize>();
+        (input_size, output_size, group_in, group_out, rows)
+    }
+
+    fn forward(&self, input: &[f32], weight: &[f32]) -> Vec<f32> {
+        let (input_size, output_size, group_in, group_out, rows) = self.sizes();
+        let table = self.gather_table();
+
+        let mut output = vec![0.0f32; self.batch * self.out_channels * output_size];
+        let mut columns = vec![0.0f32; rows * output_size];
+        for n in 0..self.batch {
+            for g in 0..self.groups {
+                let x = &input[(n * self.in_channels + g * group_in) * input_size..][..group_in * input_size];
+                im2col(x, &table, group_in, input_size, &mut columns);
+
+                let w = &weight[g * group_out * rows..][..group_out * rows];
+                let out = &mut output[(n * self.out_channels + g * group_out) * output_size..][..group_out * output_size];
+                gemm(group_out, rows, output_size, w, &columns, out);
             }
-            var = sum_sq_diff / count;
-        } else {
-            if let Some(rm) = running_mean {
-                let rm_data = rm.to_list::<f32>();
-                mean = rm_data[ch];
+        }
+        output
+    }
+
+    fn grad_input(&self, grad: &[f32], weight: &[f32]) -> Vec<f32> {
+        let (input_size, output_size, group_in, group_out, rows) = self.sizes();
+        let table = self.gather_table();
+
+        let mut grad_input = vec![0.0f32; self.batch * self.in_channels * input_size];
+        let mut columns = vec![0.0f32; rows * output_size];
+        for n in 0..self.batch {
+            for g in 0..self.groups {
+                let go = &grad[(n * self.out_channels + g * group_out) * output_size..][..group_out * output_size];
+                let w = &weight[g * group_out * rows..][..group_out * rows];
+                columns.iter_mut().for_each(|value| *value = 0.0);
+                gemm_tn(rows, group_out, output_size, w, go, &mut columns);
+
+                let dx = &mut grad_input[(n * self.in_channels + g * group_in) * input_size..][..group_in * input_size];
+                col2im(&columns, &table, group_in, input_size, dx);
             }
-            if let Some(rv) = running_var {
-                let rv_data = rv.to_list::<f32>();
-                var = rv_data[ch];
+        }
+        grad_input
+    }
+
+    fn grad_weight(&self, grad: &[f32], input: &[f32]) -> Vec<f32> {
+        let (input_size, output_size, group_in, group_out, rows) = self.sizes();
+        let table = self.gather_table();
+
+        let mut grad_weight = vec![0.0f32; self.out_channels * rows];
+        let mut columns = vec![0.0f32; rows * output_size];
+        for n in 0..self.batch {
+            for g in 0..self.groups {
+                let x = &input[(n * self.in_channels + g * group_in) * input_size..][..group_in * input_size];
+                im2col(x, &table, group_in, input_size, &mut columns);
+
+                let go = &grad[(n * self.out_channels + g * group_out) * output_size..][..group_out * output_size];
+                let dw = &mut grad_weight[g * group_out * rows..][..group_out * rows];
+                gemm_nt(group_out, output_size, rows, go, &columns, dw);
             }
         }
-        
-        let std_dev = (var + eps).sqrt();
-        let gamma = weight_data.as_ref().map(|w| w[ch]).unwrap_or(1.0);
-        let beta = bias_data.as_ref().map(|b| b[ch]).unwrap_or(0.0);
-        
-        for batch in 0..batch_size {
-            for h in 0..height {
-                for w in 0..width {
-                    let idx = batch * channels * height * width + ch * height * width + h * width + w;
-                    let normalized = (input_data[idx] - mean) / std_dev;
-                    output_data[idx] = gamma * normalized + beta;
-                }
+        grad_weight
+    }
+}
+
+fn im2col(input: &[f32], table: &[isize], channels: usize, input_size: usize, columns: &mut [f32]) {
+    for c in 0..channels {
+        let x = &input[c * input_size..][..input_size];
+        let rows = &mut columns[c * table.len()..][..table.len()];
+        for (value, &offset) in rows.iter_mut().zip(table.iter()) {
+            *value = if offset >= 0 { x[offset as usize] } else { 0.0 };
+        }
+    }
+}
+
+fn col2im(columns: &[f32], table: &[isize], channels: usize, input_size: usize, input: &mut [f32]) {
+    for c in 0..channels {
+        let x = &mut input[c * input_size..][..input_size];
+        let rows = &columns[c * table.len()..][..table.len()];
+        for (&value, &offset) in rows.iter().zip(table.iter()) {
+            if offset >= 0 {
+                x[offset as usize] += value;
+            }
+        }
+    }
+}
+
+fn gemm(m: usize, k: usize, n: usize, a: &[f32], b: &[f32], out: &mut [f32]) {
+    for i in 0..m {
+        let out_row = &mut out[i * n..][..n];
+        for p in 0..k {
+            let scale = a[i * k + p];
+            if scale == 0.0 {
+                continue;
+            }
+            for (value, &b_value) in out_row.iter_mut().zip(b[p * n..][..n].iter()) {
+                *value += scale * b_value;
+            }
+        }
+    }
+}
+
+fn gemm_tn(m: usize, k: usize, n: usize, a: &[f32], b: &[f32], out: &mut [f32]) {
+    for p in 0..k {
+        let b_row = &b[p * n..][..n];
+        for i in 0..m {
+            let scale = a[p * m + i];
+            if scale == 0.0 {
+                continue;
             }
+            for (value, &b_value) in out[i * n..][..n].iter_mut().zip(b_row.iter()) {
+                *value += scale * b_value;
+            }
+        }
+    }
+}
+
+fn gemm_nt(m: usize, k: usize, n: usize, a: &[f32], b: &[f32], out: &mut [f32]) {
+    for i in 0..m {
+        let a_row = &a[i * k..][..k];
+        for j in 0..n {
+            out[i * n + j] += a_row.iter().zip(b[j * k..][..k].iter()).map(|(x, y)| x * y).sum::<f32>();
         }
     }
-    
+}
+
+fn tensor_from(data: &[f32], shape: &[i64]) -> Tensor {
     let options = Options::default().dtype(DType::Float32);
-    match TensorImpl::new_from_data(&output_data, &input_shape, options) {
+    match TensorImpl::new_from_data(data, shape, options) {
         Ok(impl_) => Tensor {
             impl_: Some(Rc::new(impl_)),
         },
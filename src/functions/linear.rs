@@ -1,10 +1,67 @@
-use crate::tensor::Tensor;
+use crate::tensor::{with_generator, Generator, Tensor};
+use rand::Rng;
 
-pub fn linear(_input: &Tensor, _weight: &Tensor, _bias: Option<&Tensor>) -> Tensor {
-    Tensor::new()
+pub fn linear(input: &Tensor, weight: &Tensor, bias: Option<&Tensor>) -> Tensor {
+    if !input.defined() || !weight.defined() || bias.is_some_and(|bias| !bias.defined()) {
+        return Tensor::new();
+    }
+
+    let shape = input.shape();
+    let weight_shape = weight.shape();
+    if shape.is_empty() || weight_shape.len() != 2 || shape[shape.len() - 1] != weight_shape[1] {
+        return Tensor::new();
+    }
+    if bias.is_some_and(|bias| bias.shape() != [weight_shape[0]]) {
+        return Tensor::new();
+    }
+
+    let in_features = weight_shape[1];
+    let mut output_shape = shape[..shape.len() - 1].to_vec();
+    output_shape.push(weight_shape[0]);
+
+    let output = input.reshape(&[-1, in_features]).matmul(&weight.transpose(0, 1));
+    let output = match bias {
+        Some(bias) => &output + bias,
+        None => output,
+    };
+    output.reshape(&output_shape)
+}
+
+pub fn bilinear(input1: &Tensor, input2: &Tensor, weight: &Tensor, bias: Option<&Tensor>) -> Tensor {
+    if !input1.defined() || !input2.defined() || !weight.defined() {
+        return Tensor::new();
+    }
+
+    let shape1 = input1.shape();
+    let shape2 = input2.shape();
+    let weight_shape = weight.shape();
+    if shape1.is_empty()
+        || shape2.is_empty()
+        || shape1[..shape1.len() - 1] != shape2[..shape2.len() - 1]
+        || weight_shape.len() != 3
+        || shape1[shape1.len() - 1] != weight_shape[1]
+        || shape2[shape2.len() - 1] != weight_shape[2]
+    {
+        return Tensor::new();
+    }
+
+    let (in1, in2) = (weight_shape[1], weight_shape[2]);
+    let outer = &input1.reshape(&[-1, in1, 1]) * &input2.reshape(&[-1, 1, in2]);
+    let mut flat_shape = shape1[..shape1.len() - 1].to_vec();
+    flat_shape.push(in1 * in2);
+    linear(&outer.reshape(&flat_shape), &weight.reshape(&[weight_shape[0], in1 * in2]), bias)
 }
 
 pub fn dropout(input: &Tensor, p: f32, training: bool) -> Tensor {
+    dropout_with_generator(input, p, training, None)
+}
+
+pub fn dropout_with_generator(
+    input: &Tensor,
+    p: f32,
+    training: bool,
+    generator: Option<&mut Generator>,
+) -> Tensor {
     if !training {
         return input.clone();
     }
@@ -14,24 +71,23 @@ pub fn dropout(input: &Tensor, p: f32, training: bool) -> Tensor {
     }
 
     let data = input.to_list::<f32>();
-    let mut rng = rand::thread_rng();
-    use rand::Rng;
     
     let scale = 1.0 / (1.0 - p);
-    let result_data: Vec<f32> = data.iter().map(|&val| {
-        if rng.gen::<f32>() < p {
-            0.0
-        } else {
-            val * scale
-        }
-    }).collect();
+    let mask: Vec<f32> = with_generator(generator, |rng| {
+        data.iter().map(|_| if rng.gen::<f32>() < p { 0.0 } else { scale }).collect()
+    });
+    let result_data: Vec<f32> = data.iter().zip(mask.iter()).map(|(&val, &m)| val * m).collect();
 
     let shape = input.shape();
     let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
     match crate::tensor::TensorImpl::new_from_data(&result_data, &shape, options) {
-        Ok(impl_) => Tensor {
-            impl_: Some(std::rc::Rc::new(impl_)),
-        },
+        Ok(impl_) => {
+            let mask = Tensor::from_vec(mask, &shape);
+            Tensor {
+                impl_: Some(std::rc::Rc::new(impl_)),
+            }
+            .with_grad_fn("NativeDropoutBackward0", &[input], move |grad| vec![grad * &mask])
+        }
         Err(_) => Tensor::new(),
     }
 }
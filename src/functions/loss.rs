@@ -112,7 +112,7 @@ pub fn bce_loss(input: &Tensor, target: &Tensor, reduction: LossReduction) -> Te
 
     let losses: Vec<f32> = input_data.iter().zip(target_data.iter())
         .map(|(&pred, &target)| {
-            let pred_clamped = pred.max(1e-7).min(1.0 - 1e-7);
+            let pred_clamped = pred.clamp(1e-7, 1.0 - 1e-7);
             -(target * pred_clamped.ln() + (1.0 - target) * (1.0 - pred_clamped).ln())
         })
         .collect();
@@ -2,11 +2,18 @@ pub mod activation;
 pub mod loss;
 pub mod linear;
 pub mod conv;
+pub mod pooling;
+pub mod normalization;
+pub mod recurrent;
+mod common;
 
 pub use activation::*;
 pub use loss::*;
 pub use linear::*;
 pub use conv::*;
+pub use pooling::*;
+pub use normalization::*;
+pub use recurrent::*;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,355 @@
+use crate::tensor::{meta_shape_rule, Tensor};
+
+#[allow(clippy::too_many_arguments)]
+pub fn batch_norm(
+    input: &Tensor,
+    running_mean: Option<&Tensor>,
+    running_var: Option<&Tensor>,
+    weight: Option<&Tensor>,
+    bias: Option<&Tensor>,
+    training: bool,
+    momentum: f64,
+    eps: f64,
+) -> Tensor {
+    if !input.defined() || input.dim() < 2 {
+        return Tensor::new();
+    }
+    let shape = input.shape();
+    let channels = shape[1];
+    if !matches_channels(&[running_mean, running_var, weight, bias], channels) {
+        return Tensor::new();
+    }
+
+    let groups = Groups::new(shape[0], channels, shape[2..].iter().product());
+    if let Some(output) = meta_shape_rule(&[input], &input.shape(), input.dtype()) {
+        return output;
+    }
+    let data = values(input);
+    let statistics = match (training, running_mean, running_var) {
+        (false, Some(mean), Some(var)) => Statistics::running(values(mean), values(var)),
+        _ => {
+            if training && groups.count <= 1 {
+                return Tensor::new();
+            }
+            let statistics = Statistics::batch(&data, groups, true);
+            if training {
+                update_running_stats(running_mean, running_var, &statistics.mean, &statistics.unbiased_var(groups.count), momentum);
+            }
+            statistics
+        }
+    };
+
+    let output = normalize(input, &data, groups, &statistics, eps, "NativeBatchNormBackward0");
+    affine(output, weight, bias, &channel_shape(input.dim(), channels))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn batch_norm2d(
+    input: &Tensor,
+    weight: Option<&Tensor>,
+    bias: Option<&Tensor>,
+    running_mean: Option<&Tensor>,
+    running_var: Option<&Tensor>,
+    training: bool,
+    momentum: f32,
+    eps: f32,
+) -> Tensor {
+    if !input.defined() || input.dim() != 4 {
+        return Tensor::new();
+    }
+    batch_norm(input, running_mean, running_var, weight, bias, training, momentum as f64, eps as f64)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn instance_norm(
+    input: &Tensor,
+    running_mean: Option<&Tensor>,
+    running_var: Option<&Tensor>,
+    weight: Option<&Tensor>,
+    bias: Option<&Tensor>,
+    use_input_stats: bool,
+    momentum: f64,
+    eps: f64,
+) -> Tensor {
+    if !input.defined() || input.dim() < 2 {
+        return Tensor::new();
+    }
+    let shape = input.shape();
+    let (batch, channels) = (shape[0], shape[1]);
+    let spatial: i64 = shape[2..].iter().product();
+    if !matches_channels(&[running_mean, running_var, weight, bias], channels) {
+        return Tensor::new();
+    }
+
+    if let Some(output) = meta_shape_rule(&[input], &input.shape(), input.dtype()) {
+        return output;
+    }
+    let data = values(input);
+    let (groups, statistics) = match (use_input_stats, running_mean, running_var) {
+        (false, Some(mean), Some(var)) => (Groups::new(batch, channels, spatial), Statistics::running(values(mean), values(var))),
+        _ => {
+            let groups = Groups::new(1, batch * channels, spatial);
+            if use_input_stats && groups.count <= 1 {
+                return Tensor::new();
+            }
+            let statistics = Statistics::batch(&data, groups, true);
+            if use_input_stats {
+                let channel_mean = average_over_batch(&statistics.mean, batch as usize, channels as usize);
+                let channel_var = average_over_batch(&statistics.unbiased_var(groups.count), batch as usize, channels as usize);
+                update_running_stats(running_mean, running_var, &channel_mean, &channel_var, momentum);
+            }
+            (groups, statistics)
+        }
+    };
+
+    let output = normalize(input, &data, groups, &statistics, eps, "NativeInstanceNormBackward0");
+    affine(output, weight, bias, &channel_shape(input.dim(), channels))
+}
+
+pub fn layer_norm(
+    input: &Tensor,
+    normalized_shape: &[i64],
+    weight: Option<&Tensor>,
+    bias: Option<&Tensor>,
+    eps: f64,
+) -> Tensor {
+    let groups = match trailing_groups(input, normalized_shape, &[weight, bias]) {
+        Some(groups) => groups,
+        None => return Tensor::new(),
+    };
+    if let Some(output) = meta_shape_rule(&[input], &input.shape(), input.dtype()) {
+        return output;
+    }
+    let data = values(input);
+    let statistics = Statistics::batch(&data, groups, true);
+    let output = normalize(input, &data, groups, &statistics, eps, "NativeLayerNormBackward0");
+    affine(output, weight, bias, normalized_shape)
+}
+
+pub fn group_norm(
+    input: &Tensor,
+    num_groups: i64,
+    weight: Option<&Tensor>,
+    bias: Option<&Tensor>,
+    eps: f64,
+) -> Tensor {
+    if !input.defined() || input.dim() < 2 || num_groups <= 0 {
+        return Tensor::new();
+    }
+    let shape = input.shape();
+    let (batch, channels) = (shape[0], shape[1]);
+    if channels % num_groups != 0 || !matches_channels(&[weight, bias], channels) {
+        return Tensor::new();
+    }
+
+    let spatial: i64 = shape[2..].iter().product();
+    let groups = Groups::new(1, batch * num_groups, channels / num_groups * spatial);
+    if let Some(output) = meta_shape_rule(&[input], &input.shape(), input.dtype()) {
+        return output;
+    }
+    let data = values(input);
+    let statistics = Statistics::batch(&data, groups, true);
+    let output = normalize(input, &data, groups, &statistics, eps, "NativeGroupNormBackward0");
+    affine(output, weight, bias, &channel_shape(input.dim(), channels))
+}
+
+pub fn rms_norm(input: &Tensor, normalized_shape: &[i64], weight: Option<&Tensor>, eps: Option<f64>) -> Tensor {
+    let groups = match trailing_groups(input, normalized_shape, &[weight]) {
+        Some(groups) => groups,
+        None => return Tensor::new(),
+    };
+    if let Some(output) = meta_shape_rule(&[input], &input.shape(), input.dtype()) {
+        return output;
+    }
+    let data = values(input);
+    let statistics = Statistics::batch(&data, groups, false);
+    let eps = eps.unwrap_or(f32::EPSILON as f64);
+    let output = normalize(input, &data, groups, &statistics, eps, "RmsNormBackward0");
+    affine(output, weight, None, normalized_shape)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Groups {
+    groups: usize,
+    inner: usize,
+    count: usize,
+}
+
+impl Groups {
+    fn new(outer: i64, groups: i64, inner: i64) -> Self {
+        Self {
+            groups: groups as usize,
+            inner: inner as usize,
+            count: (outer * inner) as usize,
+        }
+    }
+
+    fn of(&self, index: usize) -> usize {
+        index / self.inner % self.groups
+    }
+
+    fn mean(&self, values: impl Iterator<Item = f64>) -> Vec<f64> {
+        let mut sums = vec![0.0; self.groups];
+        for (index, value) in values.enumerate() {
+            sums[self.of(index)] += value;
+        }
+        sums.iter().map(|sum| sum / self.count as f64).collect()
+    }
+}
+
+struct Statistics {
+    mean: Vec<f64>,
+    var: Vec<f64>,
+    centered: bool,
+    from_input: bool,
+}
+
+impl Statistics {
+    fn batch(data: &[f64], groups: Groups, centered: bool) -> Self {
+        let mean = if centered {
+            groups.mean(data.iter().copied())
+        } else {
+            vec![0.0; groups.groups]
+        };
+        let var = groups.mean(data.iter().enumerate().map(|(i, &x)| (x - mean[groups.of(i)]).powi(2)));
+        Self {
+            mean,
+            var,
+            centered,
+            from_input: true,
+        }
+    }
+
+    fn running(mean: Vec<f64>, var: Vec<f64>) -> Self {
+        Self {
+            mean,
+            var,
+            centered: true,
+            from_input: false,
+        }
+    }
+
+    fn unbiased_var(&self, count: usize) -> Vec<f64> {
+        let correction = count as f64 / (count as f64 - 1.0).max(1.0);
+        self.var.iter().map(|var| var * correction).collect()
+    }
+}
+
+fn normalize(
+    input: &Tensor,
+    data: &[f64],
+    groups: Groups,
+    statistics: &Statistics,
+    eps: f64,
+    name: &'static str,
+) -> Tensor {
+    let inv_std: Vec<f64> = statistics.var.iter().map(|var| 1.0 / (var + eps).sqrt()).collect();
+    let normalized: Vec<f64> = data
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let group = groups.of(i);
+            (x - statistics.mean[group]) * inv_std[group]
+        })
+        .collect();
+
+    let shape = input.shape();
+    let (centered, from_input) = (statistics.centered, statistics.from_input);
+    let output = Tensor::from_vec(normalized.iter().map(|&x| x as f32).collect(), &shape);
+    output.with_grad_fn(name, &[input], move |grad| {
+        let grad: Vec<f64> = grad.to_list::<f32>().iter().map(|&g| g as f64).collect();
+        let (mean_grad, mean_grad_x) = if from_input {
+            let mean_grad = if centered {
+                groups.mean(grad.iter().copied())
+            } else {
+                vec![0.0; groups.groups]
+            };
+            let mean_grad_x = groups.mean(grad.iter().zip(normalized.iter()).map(|(g, x)| g * x));
+            (mean_grad, mean_grad_x)
+        } else {
+            (vec![0.0; groups.groups], vec![0.0; groups.groups])
+        };
+
+        let grad_input = grad
+            .iter()
+            .zip(normalized.iter())
+            .enumerate()
+            .map(|(i, (&g, &x))| {
+                let group = groups.of(i);
+                ((g - mean_grad[group] - x * mean_grad_x[group]) * inv_std[group]) as f32
+            })
+            .collect();
+        vec![Tensor::from_vec(grad_input, &shape)]
+    })
+}
+
+fn affine(output: Tensor, weight: Option<&Tensor>, bias: Option<&Tensor>, shape: &[i64]) -> Tensor {
+    let output = match weight {
+        Some(weight) => &output * &weight.reshape(shape),
+        None => output,
+    };
+    match bias {
+        Some(bias) => &output + &bias.reshape(shape),
+        None => output,
+    }
+}
+
+fn trailing_groups(input: &Tensor, normalized_shape: &[i64], affine: &[Option<&Tensor>]) -> Option<Groups> {
+    if !input.defined() || normalized_shape.is_empty() {
+        return None;
+    }
+    let shape = input.shape();
+    if shape.len() < normalized_shape.len() || !shape.ends_with(normalized_shape) {
+        return None;
+    }
+    if affine.iter().flatten().any(|tensor| tensor.shape() != normalized_shape) {
+        return None;
+    }
+    let inner: i64 = normalized_shape.iter().product();
+    let outer: i64 = shape[..shape.len() - normalized_shape.len()].iter().product();
+    Some(Groups::new(1, outer, inner))
+}
+
+fn matches_channels(tensors: &[Option<&Tensor>], channels: i64) -> bool {
+    tensors
+        .iter()
+        .flatten()
+        .all(|tensor| tensor.defined() && tensor.numel() == channels)
+}
+
+fn channel_shape(dim: i64, channels: i64) -> Vec<i64> {
+    let mut shape = vec![1; dim as usize];
+    shape[1] = channels;
+    shape
+}
+
+fn average_over_batch(values: &[f64], batch: usize, channels: usize) -> Vec<f64> {
+    (0..channels)
+        .map(|c| (0..batch).map(|n| values[n * channels + c]).sum::<f64>() / batch.max(1) as f64)
+        .collect()
+}
+
+fn update_running_stats(
+    running_mean: Option<&Tensor>,
+    running_var: Option<&Tensor>,
+    mean: &[f64],
+    var: &[f64],
+    momentum: f64,
+) {
+    for (running, batch) in [(running_mean, mean), (running_var, var)] {
+        if let Some(impl_) = running.and_then(|running| running.impl_.as_ref()) {
+            if let Ok(current) = impl_.to_f64_list() {
+                let updated: Vec<f64> = current
+                    .iter()
+                    .zip(batch.iter())
+                    .map(|(running, batch)| (1.0 - momentum) * running + momentum * batch)
+                    .collect();
+                let _ = impl_.write_f64(&updated);
+            }
+        }
+    }
+}
+
+fn values(tensor: &Tensor) -> Vec<f64> {
+    tensor.to_list::<f32>().iter().map(|&x| x as f64).collect()
+}
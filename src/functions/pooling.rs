@@ -0,0 +1,517 @@
+use crate::functions::common::{float_operands, unravel};
+use crate::tensor::{meta_shape_rule, DType, Tensor};
+
+pub fn max_pool1d(
+    input: &Tensor,
+    kernel_size: i64,
+    stride: Option<i64>,
+    padding: i64,
+    dilation: i64,
+    ceil_mode: bool,
+) -> Tensor {
+    max_pool1d_with_indices(input, kernel_size, stride, padding, dilation, ceil_mode).0
+}
+
+pub fn max_pool1d_with_indices(
+    input: &Tensor,
+    kernel_size: i64,
+    stride: Option<i64>,
+    padding: i64,
+    dilation: i64,
+    ceil_mode: bool,
+) -> (Tensor, Tensor) {
+    let window = Window {
+        kernel: vec![kernel_size],
+        stride: vec![stride.unwrap_or(kernel_size)],
+        padding: vec![padding],
+        dilation: vec![dilation],
+        ceil_mode,
+    };
+    max_pool(input, &window, "MaxPool1DWithIndicesBackward0")
+}
+
+pub fn max_pool2d(
+    input: &Tensor,
+    kernel_size: (i64, i64),
+    stride: Option<(i64, i64)>,
+    padding: (i64, i64),
+    dilation: (i64, i64),
+    ceil_mode: bool,
+) -> Tensor {
+    max_pool2d_with_indices(input, kernel_size, stride, padding, dilation, ceil_mode).0
+}
+
+pub fn max_pool2d_with_indices(
+    input: &Tensor,
+    kernel_size: (i64, i64),
+    stride: Option<(i64, i64)>,
+    padding: (i64, i64),
+    dilation: (i64, i64),
+    ceil_mode: bool,
+) -> (Tensor, Tensor) {
+    let stride = stride.unwrap_or(kernel_size);
+    let window = Window {
+        kernel: vec![kernel_size.0, kernel_size.1],
+        stride: vec![stride.0, stride.1],
+        padding: vec![padding.0, padding.1],
+        dilation: vec![dilation.0, dilation.1],
+        ceil_mode,
+    };
+    max_pool(input, &window, "MaxPool2DWithIndicesBackward0")
+}
+
+pub fn max_pool3d(
+    input: &Tensor,
+    kernel_size: (i64, i64, i64),
+    stride: Option<(i64, i64, i64)>,
+    padding: (i64, i64, i64),
+    dilation: (i64, i64, i64),
+    ceil_mode: bool,
+) -> Tensor {
+    max_pool3d_with_indices(input, kernel_size, stride, padding, dilation, ceil_mode).0
+}
+
+pub fn max_pool3d_with_indices(
+    input: &Tensor,
+    kernel_size: (i64, i64, i64),
+    stride: Option<(i64, i64, i64)>,
+    padding: (i64, i64, i64),
+    dilation: (i64, i64, i64),
+    ceil_mode: bool,
+) -> (Tensor, Tensor) {
+    let stride = stride.unwrap_or(kernel_size);
+    let window = Window {
+        kernel: vec![kernel_size.0, kernel_size.1, kernel_size.2],
+        stride: vec![stride.0, stride.1, stride.2],
+        padding: vec![padding.0, padding.1, padding.2],
+        dilation: vec![dilation.0, dilation.1, dilation.2],
+        ceil_mode,
+    };
+    max_pool(input, &window, "MaxPool3DWithIndicesBackward0")
+}
+
+pub fn avg_pool1d(
+    input: &Tensor,
+    kernel_size: i64,
+    stride: Option<i64>,
+    padding: i64,
+    ceil_mode: bool,
+    count_include_pad: bool,
+) -> Tensor {
+    let window = Window {
+        kernel: vec![kernel_size],
+        stride: vec![stride.unwrap_or(kernel_size)],
+        padding: vec![padding],
+        dilation: vec![1],
+        ceil_mode,
+    };
+    avg_pool(input, &window, count_include_pad, None, "AvgPool1DBackward0")
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn avg_pool2d(
+    input: &Tensor,
+    kernel_size: (i64, i64),
+    stride: Option<(i64, i64)>,
+    padding: (i64, i64),
+    ceil_mode: bool,
+    count_include_pad: bool,
+    divisor_override: Option<i64>,
+) -> Tensor {
+    let stride = stride.unwrap_or(kernel_size);
+    let window = Window {
+        kernel: vec![kernel_size.0, kernel_size.1],
+        stride: vec![stride.0, stride.1],
+        padding: vec![padding.0, padding.1],
+        dilation: vec![1, 1],
+        ceil_mode,
+    };
+    avg_pool(input, &window, count_include_pad, divisor_override, "AvgPool2DBackward0")
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn avg_pool3d(
+    input: &Tensor,
+    kernel_size: (i64, i64, i64),
+    stride: Option<(i64, i64, i64)>,
+    padding: (i64, i64, i64),
+    ceil_mode: bool,
+    count_include_pad: bool,
+    divisor_override: Option<i64>,
+) -> Tensor {
+    let stride = stride.unwrap_or(kernel_size);
+    let window = Window {
+        kernel: vec![kernel_size.0, kernel_size.1, kernel_size.2],
+        stride: vec![stride.0, stride.1, stride.2],
+        padding: vec![padding.0, padding.1, padding.2],
+        dilation: vec![1, 1, 1],
+        ceil_mode,
+    };
+    avg_pool(input, &window, count_include_pad, divisor_override, "AvgPool3DBackward0")
+}
+
+pub fn adaptive_avg_pool2d(input: &Tensor, output_size: (i64, i64)) -> Tensor {
+    let pools = match Pools::adaptive(input, &[output_size.0, output_size.1]) {
+        Some(pools) => pools,
+        None => return Tensor::new(),
+    };
+    pools.average(input, "AdaptiveAvgPool2DBackward0")
+}
+
+pub fn adaptive_max_pool2d(input: &Tensor, output_size: (i64, i64)) -> Tensor {
+    adaptive_max_pool2d_with_indices(input, output_size).0
+}
+
+pub fn adaptive_max_pool2d_with_indices(input: &Tensor, output_size: (i64, i64)) -> (Tensor, Tensor) {
+    match Pools::adaptive(input, &[output_size.0, output_size.1]) {
+        Some(pools) => pools.maximum(input, "AdaptiveMaxPool2DBackward0"),
+        None => (Tensor::new(), Tensor::new()),
+    }
+}
+
+pub fn global_avg_pool2d(input: &Tensor) -> Tensor {
+    adaptive_avg_pool2d(input, (1, 1))
+}
+
+pub fn global_max_pool2d(input: &Tensor) -> Tensor {
+    adaptive_max_pool2d(input, (1, 1))
+}
+
+pub fn lp_pool2d(
+    input: &Tensor,
+    norm_type: f64,
+    kernel_size: (i64, i64),
+    stride: Option<(i64, i64)>,
+    ceil_mode: bool,
+) -> Tensor {
+    if norm_type <= 0.0 || !float_operands(&[input]) {
+        return Tensor::new();
+    }
+    let powered = input.pow(&Tensor::scalar(norm_type as f32));
+    let averaged = avg_pool2d(&powered, kernel_size, stride, (0, 0), ceil_mode, true, None);
+    if !averaged.defined() {
+        return Tensor::new();
+    }
+    let summed = &averaged * (kernel_size.0 * kernel_size.1) as f32;
+    summed.pow(&Tensor::scalar((1.0 / norm_type) as f32))
+}
+
+pub fn max_unpool2d(
+    input: &Tensor,
+    indices: &Tensor,
+    kernel_size: (i64, i64),
+    stride: Option<(i64, i64)>,
+    padding: (i64, i64),
+    output_size: Option<(i64, i64)>,
+) -> Tensor {
+    let (leading, spatial) = match split_shape(input, 2) {
+        Some(split) if valid_indices(indices, input) => split,
+        _ => return Tensor::new(),
+    };
+    let stride = stride.unwrap_or(kernel_size);
+    let output = match output_size {
+        Some((h, w)) => [h, w],
+        None => [
+            (spatial[0] as i64 - 1) * stride.0 - 2 * padding.0 + kernel_size.0,
+            (spatial[1] as i64 - 1) * stride.1 - 2 * padding.1 + kernel_size.1,
+        ],
+    };
+    if output.iter().any(|&size| size < 0) {
+        return Tensor::new();
+    }
+    let mut output_shape = leading.clone();
+    output_shape.extend_from_slice(&output);
+    if let Some(output) = meta_shape_rule(&[input, indices], &output_shape, input.dtype()) {
+        return output;
+    }
+
+    let input_size: usize = spatial.iter().product();
+    let output_size = (output[0] * output[1]) as usize;
+    let planes = leading.iter().product::<i64>() as usize;
+    let values = input.to_list::<f32>();
+    let mut targets = Vec::with_capacity(values.len());
+    for (i, &index) in indices.to_list::<i64>().iter().enumerate() {
+        if index < 0 || index as usize >= output_size {
+            return Tensor::new();
+        }
+        targets.push((i / input_size.max(1)) * output_size + index as usize);
+    }
+
+    let mut data = vec![0.0f32; planes * output_size];
+    for (&target, &value) in targets.iter().zip(values.iter()) {
+        data[target] = value;
+    }
+
+    let input_shape = input.shape();
+    Tensor::from_vec(data, &output_shape).with_grad_fn("MaxUnpool2DBackward0", &[input], move |grad| {
+        let grad = grad.to_list::<f32>();
+        let data = targets.iter().map(|&target| grad[target]).collect();
+        vec![Tensor::from_vec(data, &input_shape)]
+    })
+}
+
+struct Window {
+    kernel: Vec<i64>,
+    stride: Vec<i64>,
+    padding: Vec<i64>,
+    dilation: Vec<i64>,
+    ceil_mode: bool,
+}
+
+struct Pools {
+    leading: Vec<i64>,
+    input: Vec<usize>,
+    output: Vec<usize>,
+    taps: Vec<Vec<usize>>,
+    divisors: Vec<f32>,
+}
+
+impl Pools {
+    fn sliding(input: &Tensor, window: &Window) -> Option<Self> {
+        let spatial_dims = window.kernel.len();
+        let (leading, input_spatial) = split_shape(input, spatial_dims)?;
+        let params = [&window.kernel, &window.stride, &window.dilation];
+        if params.iter().any(|values| values.iter().any(|&value| value <= 0)) {
+            return None;
+        }
+
+        let mut output = Vec::with_capacity(spatial_dims);
+        for (d, &length) in input_spatial.iter().enumerate() {
+            let (k, s, p, dil) = (window.kernel[d], window.stride[d], window.padding[d], window.dilation[d]);
+            let extent = dil * (k - 1) + 1;
+            if p < 0 || 2 * p > extent {
+                return None;
+            }
+            let span = length as i64 + 2 * p - extent;
+            if span < 0 {
+                return None;
+            }
+            let mut size = if window.ceil_mode { (span + s - 1) / s } else { span / s } + 1;
+            if window.ceil_mode && (size - 1) * s >= length as i64 + p {
+                size -= 1;
+            }
+            output.push(size as usize);
+        }
+
+        let kernel: Vec<usize> = window.kernel.iter().map(|&k| k as usize).collect();
+        let kernel_size: usize = kernel.iter().product();
+        let output_size: usize = output.iter().product();
+        let mut taps = Vec::with_capacity(output_size);
+        let mut divisors = Vec::with_capacity(output_size);
+        let (mut o, mut k) = (vec![0; spatial_dims], vec![0; spatial_dims]);
+        for oo in 0..output_size {
+            unravel(oo, &output, &mut o);
+            let mut positions = Vec::with_capacity(kernel_size);
+            for kk in 0..kernel_size {
+                unravel(kk, &kernel, &mut k);
+                let mut offset = 0;
+                let mut inside = true;
+                for d in 0..spatial_dims {
+                    let coord = o[d] as i64 * window.stride[d] - window.padding[d] + k[d] as i64 * window.dilation[d];
+                    if coord < 0 || coord >= input_spatial[d] as i64 {
+                        inside = false;
+                        break;
+                    }
+                    offset = offset * input_spatial[d] + coord as usize;
+                }
+                if inside {
+                    positions.push(offset);
+                }
+            }
+
+            let padded: i64 = (0..spatial_dims)
+                .map(|d| {
+                    let start = o[d] as i64 * window.stride[d] - window.padding[d];
+                    let end = (start + window.kernel[d]).min(input_spatial[d] as i64 + window.padding[d]);
+                    end - start
+                })
+                .product();
+            divisors.push(padded as f32);
+            taps.push(positions);
+        }
+
+        Some(Self {
+            leading,
+            input: input_spatial,
+            output,
+            taps,
+            divisors,
+        })
+    }
+
+    fn adaptive(input: &Tensor, output_size: &[i64]) -> Option<Self> {
+        let spatial_dims = output_size.len();
+        let (leading, input_spatial) = split_shape(input, spatial_dims)?;
+        if output_size.iter().any(|&size| size <= 0) {
+            return None;
+        }
+
+        let output: Vec<usize> = output_size.iter().map(|&size| size as usize).collect();
+        let total: usize = output.iter().product();
+        let mut taps = Vec::with_capacity(total);
+        let mut o = vec![0; spatial_dims];
+        for oo in 0..total {
+            unravel(oo, &output, &mut o);
+            let ranges: Vec<(usize, usize)> = (0..spatial_dims)
+                .map(|d| {
+                    let (i, n) = (input_spatial[d], output[d]);
+                    (o[d] * i / n, ((o[d] + 1) * i).div_ceil(n))
+                })
+                .collect();
+            let mut positions = vec![0usize];
+            for (&(start, end), &size) in ranges.iter().zip(input_spatial.iter()) {
+                positions = positions
+                    .iter()
+                    .flat_map(|&base| (start..end).map(move |coord| base * size + coord))
+                    .collect();
+            }
+            taps.push(positions);
+        }
+        let divisors = taps.iter().map(|positions| positions.len() as f32).collect();
+
+        Some(Self {
+            leading,
+            input: input_spatial,
+            output,
+            taps,
+            divisors,
+        })
+    }
+
+    fn output_shape(&self) -> Vec<i64> {
+        let mut shape = self.leading.clone();
+        shape.extend(self.output.iter().map(|&size| size as i64));
+        shape
+    }
+
+    fn planes(&self) -> usize {
+        self.leading.iter().product::<i64>() as usize
+    }
+
+    fn maximum(self, input: &Tensor, name: &'static str) -> (Tensor, Tensor) {
+        let output_shape = self.output_shape();
+        if let Some(values) = meta_shape_rule(&[input], &output_shape, input.dtype()) {
+            return (values, meta_shape_rule(&[input], &output_shape, DType::Int64).unwrap_or_default());
+        }
+        let data = input.to_list::<f32>();
+        let input_size: usize = self.input.iter().product();
+
+        let mut values = Vec::with_capacity(self.planes() * self.taps.len());
+        let mut indices = Vec::with_capacity(values.capacity());
+        let mut sources = Vec::with_capacity(values.capacity());
+        for plane in 0..self.planes() {
+            let x = &data[plane * input_size..][..input_size];
+            for positions in &self.taps {
+                let mut best = (f32::NEG_INFINITY, positions.first().copied().unwrap_or(0));
+                for &position in positions {
+                    let value = x[position];
+                    if value > best.0 || value.is_nan() {
+                        best = (value, position);
+                        if value.is_nan() {
+                            break;
+                        }
+                    }
+                }
+                values.push(best.0);
+                indices.push(best.1 as i64);
+                sources.push(plane * input_size + best.1);
+            }
+        }
+
+        let input_shape = input.shape();
+        let numel = data.len();
+        let output = Tensor::from_vec(values, &output_shape).with_grad_fn(name, &[input], move |grad| {
+            let mut grad_input = vec![0.0f32; numel];
+            for (&source, &g) in sources.iter().zip(grad.to_list::<f32>().iter()) {
+                grad_input[source] += g;
+            }
+            vec![Tensor::from_vec(grad_input, &input_shape)]
+        });
+        (output, Tensor::from_vec(indices, &output_shape))
+    }
+
+    fn average(self, input: &Tensor, name: &'static str) -> Tensor {
+        let output_shape = self.output_shape();
+        if let Some(output) = meta_shape_rule(&[input], &output_shape, input.dtype()) {
+            return output;
+        }
+        let data = input.to_list::<f32>();
+        let input_size: usize = self.input.iter().product();
+
+        let mut values = Vec::with_capacity(self.planes() * self.taps.len());
+        for plane in 0..self.planes() {
+            let x = &data[plane * input_size..][..input_size];
+            for (positions, &divisor) in self.taps.iter().zip(self.divisors.iter()) {
+                values.push(positions.iter().map(|&position| x[position]).sum::<f32>() / divisor);
+            }
+        }
+
+        let input_shape = input.shape();
+        let planes = self.planes();
+        Tensor::from_vec(values, &output_shape).with_grad_fn(name, &[input], move |grad| {
+            let grad = grad.to_list::<f32>();
+            let mut grad_input = vec![0.0f32; planes * input_size];
+            for plane in 0..planes {
+                let dx = &mut grad_input[plane * input_size..][..input_size];
+                let go = &grad[plane * self.taps.len()..][..self.taps.len()];
+                for ((positions, &divisor), &g) in self.taps.iter().zip(self.divisors.iter()).zip(go.iter()) {
+                    for &position in positions {
+                        dx[position] += g / divisor;
+                    }
+                }
+            }
+            vec![Tensor::from_vec(grad_input, &input_shape)]
+        })
+    }
+}
+
+fn max_pool(input: &Tensor, window: &Window, name: &'static str) -> (Tensor, Tensor) {
+    match Pools::sliding(input, window) {
+        Some(pools) => pools.maximum(input, name),
+        None => (Tensor::new(), Tensor::new()),
+    }
+}
+
+fn avg_pool(
+    input: &Tensor,
+    window: &Window,
+    count_include_pad: bool,
+    divisor_override: Option<i64>,
+    name: &'static str,
+) -> Tensor {
+    let mut pools = match Pools::sliding(input, window) {
+        Some(pools) => pools,
+        None => return Tensor::new(),
+    };
+    match divisor_override {
+        Some(divisor) if divisor <= 0 => return Tensor::new(),
+        Some(divisor) => pools.divisors.iter_mut().for_each(|d| *d = divisor as f32),
+        None if !count_include_pad => {
+            for (d, positions) in pools.divisors.iter_mut().zip(pools.taps.iter()) {
+                *d = positions.len() as f32;
+            }
+        }
+        None => {}
+    }
+    pools.average(input, name)
+}
+
+fn split_shape(input: &Tensor, spatial_dims: usize) -> Option<(Vec<i64>, Vec<usize>)> {
+    if !float_operands(&[input]) {
+        return None;
+    }
+    let shape = input.shape();
+    if shape.len() != spatial_dims + 1 && shape.len() != spatial_dims + 2 {
+        return None;
+    }
+    let split = shape.len() - spatial_dims;
+    let spatial = shape[split..].iter().map(|&size| size as usize).collect();
+    Some((shape[..split].to_vec(), spatial))
+}
+
+fn valid_indices(indices: &Tensor, input: &Tensor) -> bool {
+    indices.defined()
+        && indices.dtype() == DType::Int64
+        && indices.device() == input.device()
+        && indices.shape() == input.shape()
+}
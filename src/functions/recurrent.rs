@@ -0,0 +1,213 @@
+use crate::functions::{linear, relu, sigmoid, tanh};
+use crate::tensor::{Options, Tensor};
+
+#[derive(Clone)]
+pub struct PackedSequence {
+    pub data: Tensor,
+    pub batch_sizes: Vec<i64>,
+    pub sorted_indices: Option<Vec<i64>>,
+    pub unsorted_indices: Option<Vec<i64>>,
+}
+
+pub fn rnn_tanh_cell(
+    input: &Tensor,
+    hx: &Tensor,
+    w_ih: &Tensor,
+    w_hh: &Tensor,
+    b_ih: Option<&Tensor>,
+    b_hh: Option<&Tensor>,
+) -> Tensor {
+    tanh(&(&linear(input, w_ih, b_ih) + &linear(hx, w_hh, b_hh)))
+}
+
+pub fn rnn_relu_cell(
+    input: &Tensor,
+    hx: &Tensor,
+    w_ih: &Tensor,
+    w_hh: &Tensor,
+    b_ih: Option<&Tensor>,
+    b_hh: Option<&Tensor>,
+) -> Tensor {
+    relu(&(&linear(input, w_ih, b_ih) + &linear(hx, w_hh, b_hh)))
+}
+
+pub fn lstm_cell(
+    input: &Tensor,
+    hx: (&Tensor, &Tensor),
+    w_ih: &Tensor,
+    w_hh: &Tensor,
+    b_ih: Option<&Tensor>,
+    b_hh: Option<&Tensor>,
+) -> (Tensor, Tensor) {
+    let (h, c) = hx;
+    if !h.defined() || h.dim() == 0 {
+        return (Tensor::new(), Tensor::new());
+    }
+    let hidden_size = h.shape()[h.dim() as usize - 1];
+    let gates = &linear(input, w_ih, b_ih) + &linear(h, w_hh, b_hh);
+    let gate = |index: i64| gates.narrow(-1, index * hidden_size, hidden_size);
+
+    let input_gate = sigmoid(&gate(0));
+    let forget_gate = sigmoid(&gate(1));
+    let cell_gate = tanh(&gate(2));
+    let output_gate = sigmoid(&gate(3));
+    let c = &(&forget_gate * c) + &(&input_gate * &cell_gate);
+    let h = &output_gate * &tanh(&c);
+    (h, c)
+}
+
+pub fn gru_cell(
+    input: &Tensor,
+    hx: &Tensor,
+    w_ih: &Tensor,
+    w_hh: &Tensor,
+    b_ih: Option<&Tensor>,
+    b_hh: Option<&Tensor>,
+) -> Tensor {
+    if !hx.defined() || hx.dim() == 0 {
+        return Tensor::new();
+    }
+    let hidden_size = hx.shape()[hx.dim() as usize - 1];
+    let input_gates = linear(input, w_ih, b_ih);
+    let hidden_gates = linear(hx, w_hh, b_hh);
+    let gate = |gates: &Tensor, index: i64| gates.narrow(-1, index * hidden_size, hidden_size);
+
+    let reset_gate = sigmoid(&(&gate(&input_gates, 0) + &gate(&hidden_gates, 0)));
+    let update_gate = sigmoid(&(&gate(&input_gates, 1) + &gate(&hidden_gates, 1)));
+    let new_gate = tanh(&(&gate(&input_gates, 2) + &(&reset_gate * &gate(&hidden_gates, 2))));
+    &new_gate + &(&update_gate * &(hx - &new_gate))
+}
+
+pub fn pad_sequence(sequences: &[Tensor], batch_first: bool, padding_value: f64) -> Tensor {
+    if sequences.is_empty() || sequences.iter().any(|sequence| !sequence.defined() || sequence.dim() == 0) {
+        return Tensor::new();
+    }
+    let max_length = sequences.iter().map(|sequence| sequence.shape()[0]).max().unwrap_or(0);
+    let padded: Vec<Tensor> = sequences
+        .iter()
+        .map(|sequence| pad_rows(sequence, max_length, padding_value))
+        .collect();
+    Tensor::stack(&padded, if batch_first { 0 } else { 1 })
+}
+
+pub fn pack_padded_sequence(
+    input: &Tensor,
+    lengths: &[i64],
+    batch_first: bool,
+    enforce_sorted: bool,
+) -> Result<PackedSequence, String> {
+    if !input.defined() || input.dim() < 2 {
+        return Err("Expected a padded input with at least 2 dimensions".to_string());
+    }
+    let input = if batch_first { input.transpose(0, 1) } else { Clone::clone(input) };
+    let shape = input.shape();
+    if lengths.len() as i64 != shape[1] {
+        return Err(format!("Expected {} lengths, got {}", shape[1], lengths.len()));
+    }
+    if lengths.iter().any(|&length| length <= 0 || length > shape[0]) {
+        return Err(format!("Sequence lengths must be between 1 and {}", shape[0]));
+    }
+
+    let (input, lengths, sorted_indices, unsorted_indices) = if enforce_sorted {
+        if lengths.windows(2).any(|pair| pair[0] < pair[1]) {
+            return Err("Lengths must be sorted in decreasing order when enforce_sorted is true".to_string());
+        }
+        (input, lengths.to_vec(), None, None)
+    } else {
+        let mut sorted: Vec<i64> = (0..lengths.len() as i64).collect();
+        sorted.sort_by_key(|&index| std::cmp::Reverse(lengths[index as usize]));
+        let mut unsorted = vec![0; sorted.len()];
+        for (position, &index) in sorted.iter().enumerate() {
+            unsorted[index as usize] = position as i64;
+        }
+        let input = input.index_select(1, &index_tensor(&sorted));
+        let lengths = sorted.iter().map(|&index| lengths[index as usize]).collect();
+        (input, lengths, Some(sorted), Some(unsorted))
+    };
+
+    let batch_sizes: Vec<i64> = (0..lengths[0])
+        .map(|step| lengths.iter().filter(|&&length| length > step).count() as i64)
+        .collect();
+    let steps: Vec<Tensor> = batch_sizes
+        .iter()
+        .enumerate()
+        .map(|(step, &batch_size)| input.narrow(0, step as i64, 1).squeeze(Some(0)).narrow(0, 0, batch_size))
+        .collect();
+    Ok(PackedSequence {
+        data: Tensor::cat(&steps, 0),
+        batch_sizes,
+        sorted_indices,
+        unsorted_indices,
+    })
+}
+
+pub fn pack_sequence(sequences: &[Tensor], enforce_sorted: bool) -> Result<PackedSequence, String> {
+    let padded = pad_sequence(sequences, false, 0.0);
+    if !padded.defined() {
+        return Err("Expected non-empty sequences with matching trailing dimensions".to_string());
+    }
+    let lengths: Vec<i64> = sequences.iter().map(|sequence| sequence.shape()[0]).collect();
+    pack_padded_sequence(&padded, &lengths, false, enforce_sorted)
+}
+
+pub fn pad_packed_sequence(
+    sequence: &PackedSequence,
+    batch_first: bool,
+    padding_value: f64,
+    total_length: Option<i64>,
+) -> Result<(Tensor, Vec<i64>), String> {
+    let batch_sizes = &sequence.batch_sizes;
+    if !sequence.data.defined() || batch_sizes.is_empty() {
+        return Err("Cannot pad an empty packed sequence".to_string());
+    }
+    let max_length = batch_sizes.len() as i64;
+    let total_length = total_length.unwrap_or(max_length);
+    if total_length < max_length {
+        return Err(format!("total_length {} is shorter than the longest sequence {}", total_length, max_length));
+    }
+
+    let batch_size = batch_sizes[0];
+    let steps: Vec<Tensor> = split_steps(&sequence.data, batch_sizes)
+        .iter()
+        .map(|step| pad_rows(step, batch_size, padding_value))
+        .collect();
+    let padded = pad_rows(&Tensor::stack(&steps, 0), total_length, padding_value);
+
+    let mut lengths: Vec<i64> = (0..batch_size)
+        .map(|row| batch_sizes.iter().filter(|&&size| size > row).count() as i64)
+        .collect();
+    let padded = match &sequence.unsorted_indices {
+        Some(unsorted) => {
+            lengths = unsorted.iter().map(|&index| lengths[index as usize]).collect();
+            padded.index_select(1, &index_tensor(unsorted))
+        }
+        None => padded,
+    };
+    Ok((if batch_first { padded.transpose(0, 1) } else { padded }, lengths))
+}
+
+pub(crate) fn split_steps(data: &Tensor, batch_sizes: &[i64]) -> Vec<Tensor> {
+    let mut offset = 0;
+    batch_sizes
+        .iter()
+        .map(|&batch_size| {
+            let step = data.narrow(0, offset, batch_size);
+            offset += batch_size;
+            step
+        })
+        .collect()
+}
+
+pub(crate) fn index_tensor(indices: &[i64]) -> Tensor {
+    Tensor::from_vec(indices.to_vec(), &[indices.len() as i64])
+}
+
+fn pad_rows(tensor: &Tensor, rows: i64, padding_value: f64) -> Tensor {
+    let mut shape = tensor.shape();
+    if shape[0] >= rows {
+        return Clone::clone(tensor);
+    }
+    shape[0] = rows - shape[0];
+    let options = Options::default().dtype(tensor.dtype()).device(tensor.device());
+    Tensor::cat(&[Clone::clone(tensor), Tensor::full_with_options(&shape, padding_value, options)], 0)
+}
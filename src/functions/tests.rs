@@ -1,6 +1,8 @@
+#![allow(unused_imports, unused_variables, dead_code, clippy::module_inception, clippy::excessive_precision)]
+
 use super::*;
 use crate::autograd::function;
-use crate::tensor::{Tensor, Options};
+use crate::tensor::{DType, Device, Generator, Tensor, Options};
 
 #[cfg(test)]
 mod tests {
@@ -15,7 +17,6 @@ mod tests {
 
     #[test]
     fn test_func_add() {
-        let options = Options::new().requires_grad(true);
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let b = Tensor::from_array_1d(vec![4.0f32, 5.0, 6.0]);
         let y = function::function::add(&a, &b, 0.5);
@@ -24,7 +25,6 @@ mod tests {
 
     #[test]
     fn test_func_sub() {
-        let options = Options::new().requires_grad(true);
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let b = Tensor::from_array_1d(vec![4.0f32, 5.0, 6.0]);
         let y = function::function::sub(&a, &b);
@@ -33,7 +33,6 @@ mod tests {
 
     #[test]
     fn test_func_mul() {
-        let options = Options::new().requires_grad(true);
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let b = Tensor::from_array_1d(vec![4.0f32, 5.0, 6.0]);
         let y = function::function::mul(&a, &b);
@@ -42,7 +41,6 @@ mod tests {
 
     #[test]
     fn test_func_div() {
-        let options = Options::new().requires_grad(true);
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let b = Tensor::from_array_1d(vec![4.0f32, 5.0, 6.0]);
         let y = function::function::div(&a, &b);
@@ -51,7 +49,6 @@ mod tests {
 
     #[test]
     fn test_func_sin() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_1d(vec![0.0f32, std::f32::consts::PI / 2.0, std::f32::consts::PI]);
         let y = function::function::sin(&x);
         assert_vec_near(&y.to_list::<f32>(), &[0.0, 1.0, 0.0], 1e-6);
@@ -59,7 +56,6 @@ mod tests {
 
     #[test]
     fn test_func_cos() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_1d(vec![0.0f32, std::f32::consts::PI / 2.0, std::f32::consts::PI]);
         let y = function::function::cos(&x);
         assert_vec_near(&y.to_list::<f32>(), &[1.0, 0.0, -1.0], 1e-6);
@@ -67,7 +63,6 @@ mod tests {
 
     #[test]
     fn test_func_pow() {
-        let options = Options::new().requires_grad(true);
         let x1 = Tensor::from_array_1d(vec![2.0f32, 3.0, 4.0]);
         let x2 = Tensor::from_array_1d(vec![3.0f32, 3.0, 3.0]);
         let y = function::function::pow(&x1, &x2);
@@ -79,7 +74,6 @@ mod tests {
 
     #[test]
     fn test_func_sum() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let y = function::function::sum(&x);
         assert_eq!(y.to_list::<f32>(), vec![6.0]);
@@ -87,7 +81,6 @@ mod tests {
 
     #[test]
     fn test_func_relu() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_2d(vec![vec![-1.0f32, 2.0], vec![3.0, -4.0]]);
         let y = function::function::relu(&x);
         assert_eq!(y.to_list::<f32>(), vec![0.0, 2.0, 3.0, 0.0]);
@@ -109,7 +102,6 @@ mod tests {
 
     #[test]
     fn test_func_softmax() {
-        let options = Options::new().requires_grad(true);
         let input = Tensor::from_array_1d(vec![1.1f32, 1.2, 1.3, 1.6]);
         let output = function::function::softmax(&input, 0);
         assert_vec_near(&output.to_list::<f32>(), &[0.2010, 0.2221, 0.2455, 0.3314], 1e-3);
@@ -117,7 +109,6 @@ mod tests {
 
     #[test]
     fn test_func_log_softmax() {
-        let options = Options::new().requires_grad(true);
         let input = Tensor::from_array_1d(vec![1.1f32, 1.2, 1.3, 1.6]);
         let output = function::function::log_softmax(&input, 0);
         assert_vec_near(&output.to_list::<f32>(), &[-1.6045, -1.5045, -1.4045, -1.1045], 1e-3);
@@ -125,7 +116,6 @@ mod tests {
 
     #[test]
     fn test_func_mse_loss_none() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_2d(vec![
             vec![-0.3089f32, 0.5301, -0.0245],
             vec![1.5852, 0.8954, 0.7485]
@@ -136,13 +126,12 @@ mod tests {
         ]);
         let loss = mse_loss(&x, &y, LossReduction::None);
         assert_vec_near(&loss.to_list::<f32>(), 
-                       &[1.31928194, 1.6101073, 0.0621504858, 6.13156557, 2.46489978, 0.165323555], 
+                       &[1.3193, 1.6101, 0.0622, 6.1316, 2.4649, 0.1653], 
                        1e-3);
     }
 
     #[test]
     fn test_func_mse_loss_mean() {
-        let options = Options::new().requires_grad(true);
         let x = Tensor::from_array_2d(vec![
             vec![-0.3089f32, 0.5301, -0.0245],
             vec![1.5852, 0.8954, 0.7485]
@@ -152,12 +141,11 @@ mod tests {
             vec![-0.8910, -0.6746, 0.3419]
         ]);
         let loss = mse_loss(&x, &y, LossReduction::Mean);
-        assert!((loss.item::<f32>() - 1.95888805).abs() < 1e-3);
+        assert!((loss.item::<f32>() - 1.9589).abs() < 1e-3);
     }
 
     #[test]
     fn test_func_nll_loss() {
-        let options = Options::new().requires_grad(true);
         let input = Tensor::from_array_2d(vec![vec![0.1f32, 0.2, 0.7], vec![0.3, 0.4, 0.3]]);
         let target = Tensor::from_array_1d(vec![2i64, 1]);
         let loss = nll_loss(&input, &target, LossReduction::None);
@@ -166,7 +154,6 @@ mod tests {
 
     #[test]
     fn test_func_dropout() {
-        let options = Options::new().requires_grad(true);
         let input = Tensor::ones(&[100, 10]);
         let p = 0.3f32;
         let output = dropout(&input, p, true);
@@ -212,7 +199,7 @@ mod tests {
         ]);
         let target = Tensor::from_array_1d(vec![0i64, 1]);
         let loss = cross_entropy_loss(&input, &target, LossReduction::Mean);
-        
+
         assert!(loss.item::<f32>() > 0.0);
         assert!(loss.item::<f32>() < 5.0); // Reasonable upper bound
     }
@@ -222,7 +209,7 @@ mod tests {
         let input = Tensor::from_array_1d(vec![0.8f32, 0.2, 0.9, 0.1]);
         let target = Tensor::from_array_1d(vec![1.0f32, 0.0, 1.0, 0.0]);
         let loss = bce_loss(&input, &target, LossReduction::Mean);
-        
+
         assert!(loss.item::<f32>() > 0.0);
         assert!(loss.item::<f32>() < 1.0);
     }
@@ -232,9 +219,9 @@ mod tests {
         let input = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0, 4.0]);
         let target = Tensor::from_array_1d(vec![1.5f32, 1.8, 3.2, 3.9]);
         let loss = l1_loss(&input, &target, LossReduction::None);
-        
+
         assert_vec_near(&loss.to_list::<f32>(), &[0.5, 0.2, 0.2, 0.1], 1e-6);
-        
+
         let loss_mean = l1_loss(&input, &target, LossReduction::Mean);
         assert_vec_near(&[loss_mean.item::<f32>()], &[0.25], 1e-6);
     }
@@ -246,14 +233,14 @@ mod tests {
             vec![4.0, 5.0, 6.0],
             vec![7.0, 8.0, 9.0]
         ]]]);
-        
+
         let weight = Tensor::from_array_4d(vec![vec![vec![
             vec![1.0f32, 0.0],
             vec![0.0, 1.0]
         ]]]);
-        
-        let output = conv2d(&input, &weight, None, (1, 1), (0, 0), (1, 1));
-        
+
+        let output = conv2d(&input, &weight, None, (1, 1), (0, 0), (1, 1), 1);
+
         assert!(output.defined());
         assert_eq!(output.shape(), vec![1, 1, 2, 2]);
         assert_eq!(output.to_list::<f32>(), vec![6.0, 8.0, 12.0, 14.0]);
@@ -267,9 +254,9 @@ mod tests {
             vec![9.0, 10.0, 11.0, 12.0],
             vec![13.0, 14.0, 15.0, 16.0]
         ]]]);
-        
-        let output = max_pool2d(&input, (2, 2), None, (0, 0));
-        
+
+        let output = max_pool2d(&input, (2, 2), None, (0, 0), (1, 1), false);
+
         assert!(output.defined());
         assert_eq!(output.shape(), vec![1, 1, 2, 2]);
         assert_eq!(output.to_list::<f32>(), vec![6.0, 8.0, 14.0, 16.0]);
@@ -281,13 +268,618 @@ mod tests {
             vec![1.0f32, 2.0],
             vec![3.0, 4.0]
         ]]]);
-        
+
         let output = batch_norm2d(&input, None, None, None, None, true, 0.1, 1e-5);
-        
+
         assert!(output.defined());
         assert_eq!(output.shape(), vec![1, 1, 2, 2]);
-        
+
         let output_data = output.to_list::<f32>();
         assert!(output_data.iter().all(|&x| x.is_finite()));
     }
+
+    #[test]
+    fn test_dropout_with_generator() {
+        let x = Tensor::ones(&[32]);
+        let mut g1 = crate::tensor::Generator::with_seed(11);
+        let mut g2 = crate::tensor::Generator::with_seed(11);
+
+        let a = dropout_with_generator(&x, 0.5, true, Some(&mut g1));
+        let b = dropout_with_generator(&x, 0.5, true, Some(&mut g2));
+        assert_eq!(a.to_list::<f32>(), b.to_list::<f32>());
+        assert!(a.to_list::<f32>().iter().all(|&v| v == 0.0 || v == 2.0));
+    }
+
+    #[test]
+    fn test_func_linear() {
+        let options = Options::default().requires_grad(true);
+        let x = Tensor::arange_with_options(0.0, 6.0, 1.0, options.clone());
+        let w = Tensor::arange_with_options(0.0, 6.0, 1.0, options.clone());
+        let mut b = Tensor::from_vec(vec![1.0f32, -1.0], &[2]);
+        b.set_requires_grad(true);
+
+        let y = linear(&x.reshape(&[2, 1, 3]), &w.reshape(&[2, 3]), Some(&b));
+        assert_eq!(y.shape(), vec![2, 1, 2]);
+        assert_eq!(y.to_list::<f32>(), vec![6.0, 13.0, 15.0, 49.0]);
+
+        y.sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![3.0, 5.0, 7.0, 3.0, 5.0, 7.0]);
+        assert_eq!(w.grad().to_list::<f32>(), vec![3.0, 5.0, 7.0, 3.0, 5.0, 7.0]);
+        assert_eq!(b.grad().to_list::<f32>(), vec![2.0, 2.0]);
+
+        let single = linear(&Tensor::ones(&[3]), &w.reshape(&[2, 3]), None);
+        assert_eq!(single.shape(), vec![2]);
+        assert_eq!(single.to_list::<f32>(), vec![3.0, 12.0]);
+        assert!(!linear(&Tensor::ones(&[2, 4]), &w.reshape(&[2, 3]), None).defined());
+    }
+
+    #[test]
+    fn test_func_bilinear() {
+        let x1 = Tensor::from_vec(vec![1.0f32, 2.0], &[1, 2]);
+        let x2 = Tensor::from_vec(vec![3.0f32, 4.0], &[1, 2]);
+        let weight = Tensor::from_vec(vec![1.0f32, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0], &[2, 2, 2]);
+        let bias = Tensor::from_vec(vec![0.5f32, 0.0], &[2]);
+        let y = bilinear(&x1, &x2, &weight, Some(&bias));
+        assert_eq!(y.shape(), vec![1, 2]);
+        assert_eq!(y.to_list::<f32>(), vec![11.5, 4.0]);
+        assert!(!bilinear(&x1, &Tensor::ones(&[1, 3]), &weight, None).defined());
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn reference_conv2d(
+        x: &[f32],
+        x_shape: [usize; 4],
+        w: &[f32],
+        w_shape: [usize; 4],
+        bias: &[f32],
+        stride: (usize, usize),
+        padding: (i64, i64),
+        dilation: (usize, usize),
+        groups: usize,
+    ) -> Vec<f32> {
+        let [n, c, h, wd] = x_shape;
+        let [o, cg, kh, kw] = w_shape;
+        let oh = ((h as i64 + 2 * padding.0 - (dilation.0 * (kh - 1)) as i64 - 1) / stride.0 as i64 + 1) as usize;
+        let ow = ((wd as i64 + 2 * padding.1 - (dilation.1 * (kw - 1)) as i64 - 1) / stride.1 as i64 + 1) as usize;
+        let og = o / groups;
+        let mut out = vec![0.0f32; n * o * oh * ow];
+        for b in 0..n {
+            for oc in 0..o {
+                for y in 0..oh {
+                    for xx in 0..ow {
+                        let mut sum = bias.get(oc).copied().unwrap_or(0.0);
+                        for ic in 0..cg {
+                            let channel = (oc / og) * cg + ic;
+                            for i in 0..kh {
+                                for j in 0..kw {
+                                    let r = (y * stride.0 + i * dilation.0) as i64 - padding.0;
+                                    let q = (xx * stride.1 + j * dilation.1) as i64 - padding.1;
+                                    if r >= 0 && r < h as i64 && q >= 0 && q < wd as i64 {
+                                        sum += x[((b * c + channel) * h + r as usize) * wd + q as usize]
+                                            * w[((oc * cg + ic) * kh + i) * kw + j];
+                                    }
+                                }
+                            }
+                        }
+                        out[((b * o + oc) * oh + y) * ow + xx] = sum;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn random_ints(shape: &[i64], generator: &mut Generator) -> Tensor {
+        Tensor::randint_with_options(-3, 4, shape, Options::default(), Some(generator))
+    }
+
+    #[test]
+    fn test_func_conv2d_matches_reference() {
+        let mut generator = Generator::with_seed(46);
+        let x = random_ints(&[2, 4, 7, 6], &mut generator);
+        let w = random_ints(&[6, 2, 3, 2], &mut generator);
+        let b = random_ints(&[6], &mut generator);
+        let y = conv2d(&x, &w, Some(&b), (2, 1), (1, 2), (2, 1), 2);
+        let expected = reference_conv2d(
+            &x.to_list::<f32>(), [2, 4, 7, 6], &w.to_list::<f32>(), [6, 2, 3, 2], &b.to_list::<f32>(),
+            (2, 1), (1, 2), (2, 1), 2,
+        );
+        assert_eq!(y.shape(), vec![2, 6, 3, 9]);
+        assert_eq!(y.to_list::<f32>(), expected);
+
+        let depthwise = random_ints(&[4, 1, 3, 3], &mut generator);
+        let y = conv2d(&x, &depthwise, None, (1, 1), (1, 1), (1, 1), 4);
+        let expected = reference_conv2d(
+            &x.to_list::<f32>(), [2, 4, 7, 6], &depthwise.to_list::<f32>(), [4, 1, 3, 3], &[],
+            (1, 1), (1, 1), (1, 1), 4,
+        );
+        assert_eq!(y.to_list::<f32>(), expected);
+        assert!(!conv2d(&x, &w, None, (1, 1), (0, 0), (1, 1), 3).defined());
+    }
+
+    #[test]
+    fn test_func_conv2d_padding_modes() {
+        let x = Tensor::ones(&[1, 1, 5, 4]);
+        let even = Tensor::ones(&[1, 1, 2, 2]);
+        let same = conv2d(&x, &even, None, (1, 1), Padding::Same, (1, 1), 1);
+        assert_eq!(same.shape(), vec![1, 1, 5, 4]);
+        assert_eq!(&same.to_list::<f32>()[..4], &[4.0, 4.0, 4.0, 2.0]);
+        assert_eq!(&same.to_list::<f32>()[16..], &[2.0, 2.0, 2.0, 1.0]);
+
+        let dilated = conv2d(&x, &Tensor::ones(&[1, 1, 3, 3]), None, (1, 1), Padding::Same, (2, 1), 1);
+        assert_eq!(dilated.shape(), vec![1, 1, 5, 4]);
+        let valid = conv2d(&x, &even, None, (1, 1), Padding::Valid, (1, 1), 1);
+        assert_eq!(valid.shape(), vec![1, 1, 4, 3]);
+        assert!(!conv2d(&x, &even, None, (2, 2), Padding::Same, (1, 1), 1).defined());
+    }
+
+    #[test]
+    fn test_func_rejects_unsupported_operands() {
+        let long = Options::default().dtype(DType::Int64);
+        let half = Options::default().dtype(DType::Float16);
+        let x = Tensor::ones(&[1, 2, 4, 4]);
+        let weight = Tensor::ones(&[3, 2, 3, 3]);
+        let transposed = Tensor::ones(&[2, 1, 2, 2]);
+        let integers = Tensor::ones_with_options(&[1, 2, 4, 4], long.clone());
+        let halves = Tensor::ones_with_options(&[1, 2, 4, 4], half.clone());
+        let half_weight = Tensor::ones_with_options(&[3, 2, 3, 3], half.clone());
+        let half_transposed = Tensor::ones_with_options(&[2, 1, 2, 2], half.clone());
+        let half_bias = Tensor::zeros_with_options(&[3], half.clone());
+        let long_bias = Tensor::zeros_with_options(&[1], long);
+        let (pooled, indices) = max_pool2d_with_indices(&grid(16, &[1, 1, 4, 4]), (2, 2), None, (0, 0), (1, 1), false);
+        let int_indices = Tensor::from_vec(vec![5i32, 7, 13, 15], &[1, 1, 2, 2]);
+        let half_pooled = Tensor::ones_with_options(&[1, 1, 2, 2], half);
+
+        let cases = [
+            ("conv2d integer input", conv2d(&integers, &weight, None, (1, 1), (0, 0), (1, 1), 1)),
+            ("conv2d half weight", conv2d(&x, &half_weight, None, (1, 1), (0, 0), (1, 1), 1)),
+            ("conv2d half bias", conv2d(&x, &weight, Some(&half_bias), (1, 1), (0, 0), (1, 1), 1)),
+            ("conv2d meta input", conv2d(&x.to(Device::meta()), &weight, None, (1, 1), (0, 0), (1, 1), 1)),
+            ("conv2d meta weight", conv2d(&x, &weight.to(Device::meta()), None, (1, 1), (0, 0), (1, 1), 1)),
+            ("conv_transpose2d integer input", conv_transpose2d(&integers, &transposed, None, (1, 1), (0, 0), (0, 0), 1, (1, 1))),
+            ("conv_transpose2d half input", conv_transpose2d(&halves, &transposed, None, (1, 1), (0, 0), (0, 0), 1, (1, 1))),
+            ("conv_transpose2d half weight", conv_transpose2d(&x, &half_transposed, None, (1, 1), (0, 0), (0, 0), 1, (1, 1))),
+            ("conv_transpose2d long bias", conv_transpose2d(&x, &transposed, Some(&long_bias), (1, 1), (0, 0), (0, 0), 1, (1, 1))),
+            ("conv_transpose2d meta input", conv_transpose2d(&x.to(Device::meta()), &transposed, None, (1, 1), (0, 0), (0, 0), 1, (1, 1))),
+            ("max_pool2d integer input", max_pool2d(&integers, (2, 2), None, (0, 0), (1, 1), false)),
+            ("max_pool2d half input", max_pool2d(&halves, (2, 2), None, (0, 0), (1, 1), false)),
+            ("avg_pool2d integer input", avg_pool2d(&integers, (2, 2), None, (0, 0), false, true, None)),
+            ("avg_pool2d half input", avg_pool2d(&halves, (2, 2), None, (0, 0), false, true, None)),
+            ("adaptive_max_pool2d integer input", adaptive_max_pool2d(&integers, (2, 2))),
+            ("adaptive_max_pool2d half input", adaptive_max_pool2d(&halves, (2, 2))),
+            ("adaptive_avg_pool2d integer input", adaptive_avg_pool2d(&integers, (2, 2))),
+            ("adaptive_avg_pool2d half input", adaptive_avg_pool2d(&halves, (2, 2))),
+            ("lp_pool2d integer input", lp_pool2d(&integers, 2.0, (2, 2), None, false)),
+            ("lp_pool2d half input", lp_pool2d(&halves, 2.0, (2, 2), None, false)),
+            ("max_unpool2d int32 indices", max_unpool2d(&pooled, &int_indices, (2, 2), None, (0, 0), None)),
+            ("max_unpool2d meta indices", max_unpool2d(&pooled, &indices.to(Device::meta()), (2, 2), None, (0, 0), None)),
+            ("max_unpool2d half input", max_unpool2d(&half_pooled, &indices, (2, 2), None, (0, 0), None)),
+        ];
+        for (case, output) in cases {
+            assert!(!output.defined(), "{} should be rejected", case);
+        }
+
+        assert_eq!(conv2d(&x, &weight, None, (1, 1), (0, 0), (1, 1), 1).shape(), vec![1, 3, 2, 2]);
+        assert_eq!(conv_transpose2d(&x, &transposed, None, (1, 1), (0, 0), (0, 0), 1, (1, 1)).shape(), vec![1, 1, 5, 5]);
+        assert_eq!(max_unpool2d(&pooled, &indices, (2, 2), None, (0, 0), None).shape(), vec![1, 1, 4, 4]);
+    }
+
+    #[test]
+    fn test_func_conv2d_backward() {
+        let mut generator = Generator::with_seed(7);
+        let x_data = random_ints(&[2, 4, 5, 5], &mut generator).to_list::<f32>();
+        let w_data = random_ints(&[6, 2, 3, 2], &mut generator).to_list::<f32>();
+        let b_data = random_ints(&[6], &mut generator).to_list::<f32>();
+        let mut x = Tensor::from_vec(x_data.clone(), &[2, 4, 5, 5]);
+        let mut w = Tensor::from_vec(w_data.clone(), &[6, 2, 3, 2]);
+        let mut b = Tensor::from_vec(b_data.clone(), &[6]);
+        x.set_requires_grad(true);
+        w.set_requires_grad(true);
+        b.set_requires_grad(true);
+
+        let y = conv2d(&x, &w, Some(&b), (2, 1), (1, 1), (1, 2), 2);
+        let g = random_ints(&y.shape(), &mut generator);
+        (&y * &g).sum().backward();
+
+        let g_data = g.to_list::<f32>();
+        let loss = |x: &[f32], w: &[f32], b: &[f32]| -> f32 {
+            reference_conv2d(x, [2, 4, 5, 5], w, [6, 2, 3, 2], b, (2, 1), (1, 1), (1, 2), 2)
+                .iter()
+                .zip(g_data.iter())
+                .map(|(a, b)| a * b)
+                .sum()
+        };
+        let base = loss(&x_data, &w_data, &b_data);
+        let perturbed = |data: &[f32], i: usize| {
+            let mut data = data.to_vec();
+            data[i] += 1.0;
+            data
+        };
+
+        let grad_x: Vec<f32> = (0..x_data.len()).map(|i| loss(&perturbed(&x_data, i), &w_data, &b_data) - base).collect();
+        let grad_w: Vec<f32> = (0..w_data.len()).map(|i| loss(&x_data, &perturbed(&w_data, i), &b_data) - base).collect();
+        let grad_b: Vec<f32> = (0..b_data.len()).map(|i| loss(&x_data, &w_data, &perturbed(&b_data, i)) - base).collect();
+        assert_eq!(x.grad().to_list::<f32>(), grad_x);
+        assert_eq!(w.grad().to_list::<f32>(), grad_w);
+        assert_eq!(b.grad().to_list::<f32>(), grad_b);
+    }
+
+    fn dot(a: &Tensor, b: &Tensor) -> f32 {
+        a.to_list::<f32>().iter().zip(b.to_list::<f32>().iter()).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_func_conv1d_and_conv3d() {
+        let mut generator = Generator::with_seed(47);
+        let x = random_ints(&[2, 4, 9], &mut generator);
+        let w = random_ints(&[6, 2, 3], &mut generator);
+        let b = random_ints(&[6], &mut generator);
+        let y = conv1d(&x, &w, Some(&b), 2, 1, 2, 2);
+        let expected = conv2d(&x.unsqueeze(2), &w.unsqueeze(2), Some(&b), (1, 2), (0, 1), (1, 2), 2);
+        assert_eq!(y.shape(), vec![2, 6, 4]);
+        assert_eq!(y.to_list::<f32>(), expected.to_list::<f32>());
+        assert_eq!(conv1d(&x, &w, None, 1, Padding::Same, 3, 2).shape(), vec![2, 6, 9]);
+
+        let x = random_ints(&[1, 2, 4, 4, 5], &mut generator);
+        let w = random_ints(&[3, 2, 2, 3, 3], &mut generator);
+        let y = conv3d(&x, &w, None, (1, 1, 2), (0, 1, 1), (2, 1, 1), 1);
+        assert_eq!(y.shape(), vec![1, 3, 2, 4, 3]);
+        for d in 0..2 {
+            let mut expected = vec![0.0f32; 36];
+            for k in 0..2 {
+                let plane = x.narrow(2, d + 2 * k, 1).squeeze(Some(2));
+                let kernel = w.narrow(2, k, 1).squeeze(Some(2));
+                let part = conv2d(&plane, &kernel, None, (1, 2), (1, 1), (1, 1), 1).to_list::<f32>();
+                expected.iter_mut().zip(part.iter()).for_each(|(e, p)| *e += p);
+            }
+            let slice = y.narrow(2, d, 1).contiguous().to_list::<f32>();
+            assert_eq!(slice, expected);
+        }
+    }
+
+    #[test]
+    fn test_func_conv_transpose() {
+        let x = Tensor::from_vec(vec![1.0f32, 2.0], &[1, 1, 2]);
+        let w = Tensor::ones(&[1, 1, 3]);
+        let y = conv_transpose1d(&x, &w, None, 2, 0, 0, 1, 1);
+        assert_eq!(y.to_list::<f32>(), vec![1.0, 1.0, 3.0, 2.0, 2.0]);
+
+        let mut generator = Generator::with_seed(48);
+        let x = random_ints(&[2, 4, 7, 6], &mut generator);
+        let w = random_ints(&[4, 3, 3, 2], &mut generator);
+        let adjoint_weight = w.narrow(1, 0, 2);
+        let y = conv2d(&x, &adjoint_weight, None, (2, 2), (1, 0), (1, 2), 2);
+        let z = random_ints(&y.shape(), &mut generator);
+        let transposed = conv_transpose2d(&z, &adjoint_weight, None, (2, 2), (1, 0), (0, 1), 2, (1, 2));
+        assert_eq!(transposed.shape(), x.shape());
+        assert_eq!(dot(&transposed, &x), dot(&z, &y));
+
+        let volume = conv_transpose3d(&Tensor::ones(&[1, 2, 2, 3, 4]), &Tensor::ones(&[2, 1, 3, 3, 3]), None, (2, 1, 2), (1, 0, 1), (1, 0, 1), 1, (1, 2, 1));
+        assert_eq!(volume.shape(), vec![1, 1, 4, 7, 8]);
+        assert!(!conv_transpose1d(&Tensor::ones(&[1, 1, 3]), &Tensor::ones(&[1, 1, 2]), None, 1, 0, 1, 1, 1).defined());
+    }
+
+    #[test]
+    fn test_func_conv_transpose_backward() {
+        let mut generator = Generator::with_seed(49);
+        let x_data = random_ints(&[2, 4, 5], &mut generator);
+        let w_data = random_ints(&[4, 3, 3], &mut generator);
+        let b_data = random_ints(&[6], &mut generator);
+        let (mut x, mut w, mut b) = (x_data.clone(), w_data.clone(), b_data.clone());
+        x.set_requires_grad(true);
+        w.set_requires_grad(true);
+        b.set_requires_grad(true);
+
+        let forward = |x: &Tensor, w: &Tensor, b: &Tensor| conv_transpose1d(x, w, Some(b), 2, 1, 1, 2, 2);
+        let y = forward(&x, &w, &b);
+        assert_eq!(y.shape(), vec![2, 6, 12]);
+        let g = random_ints(&y.shape(), &mut generator);
+        (&y * &g).sum().backward();
+
+        let loss = |x: &Tensor, w: &Tensor, b: &Tensor| dot(&forward(x, w, b), &g);
+        let base = loss(&x_data, &w_data, &b_data);
+        let numeric = |data: &Tensor, f: &dyn Fn(&Tensor) -> f32| -> Vec<f32> {
+            let values = data.to_list::<f32>();
+            (0..values.len())
+                .map(|i| {
+                    let mut shifted = values.clone();
+                    shifted[i] += 1.0;
+                    f(&Tensor::from_vec(shifted, &data.shape())) - base
+                })
+                .collect()
+        };
+        assert_eq!(x.grad().to_list::<f32>(), numeric(&x_data, &|x| loss(x, &w_data, &b_data)));
+        assert_eq!(w.grad().to_list::<f32>(), numeric(&w_data, &|w| loss(&x_data, w, &b_data)));
+        assert_eq!(b.grad().to_list::<f32>(), numeric(&b_data, &|b| loss(&x_data, &w_data, b)));
+    }
+
+    fn grid(n: usize, shape: &[i64]) -> Tensor {
+        Tensor::from_vec((0..n).map(|i| i as f32).collect(), shape)
+    }
+
+    #[test]
+    fn test_func_max_pool_options() {
+        let x = grid(16, &[1, 1, 4, 4]);
+        let (values, indices) = max_pool2d_with_indices(&x, (2, 2), None, (0, 0), (1, 1), false);
+        assert_eq!(values.to_list::<f32>(), vec![5.0, 7.0, 13.0, 15.0]);
+        assert_eq!(indices.to_list::<i64>(), vec![5, 7, 13, 15]);
+
+        let dilated = max_pool2d(&x, (2, 2), Some((1, 1)), (0, 0), (2, 2), false);
+        assert_eq!(dilated.to_list::<f32>(), vec![10.0, 11.0, 14.0, 15.0]);
+        let padded = max_pool2d(&x, (3, 3), Some((2, 2)), (1, 1), (1, 1), false);
+        assert_eq!(padded.to_list::<f32>(), vec![5.0, 7.0, 13.0, 15.0]);
+        assert!(!max_pool2d(&x, (2, 2), None, (2, 2), (1, 1), false).defined());
+
+        let x = grid(25, &[1, 5, 5]);
+        assert_eq!(max_pool2d(&x, (2, 2), None, (0, 0), (1, 1), false).shape(), vec![1, 2, 2]);
+        let ceil = max_pool2d(&x, (2, 2), None, (0, 0), (1, 1), true);
+        assert_eq!(ceil.to_list::<f32>(), vec![6.0, 8.0, 9.0, 16.0, 18.0, 19.0, 21.0, 23.0, 24.0]);
+
+        let volume = max_pool3d(&grid(27, &[1, 1, 3, 3, 3]), (2, 2, 2), Some((1, 1, 1)), (0, 0, 0), (1, 1, 1), false);
+        assert_eq!(volume.to_list::<f32>(), vec![13.0, 14.0, 16.0, 17.0, 22.0, 23.0, 25.0, 26.0]);
+
+        let mut signal = Tensor::from_vec(vec![1.0f32, 3.0, 2.0, 0.0], &[1, 1, 4]);
+        signal.set_requires_grad(true);
+        let pooled = max_pool1d(&signal, 2, Some(1), 0, 1, false);
+        assert_eq!(pooled.to_list::<f32>(), vec![3.0, 3.0, 2.0]);
+        pooled.sum().backward();
+        assert_eq!(signal.grad().to_list::<f32>(), vec![0.0, 2.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_func_avg_pool() {
+        let x = Tensor::ones(&[1, 1, 3, 3]);
+        let included = avg_pool2d(&x, (2, 2), Some((1, 1)), (1, 1), false, true, None);
+        assert_eq!(included.shape(), vec![1, 1, 4, 4]);
+        assert_eq!(&included.to_list::<f32>()[..8], &[0.25, 0.5, 0.5, 0.25, 0.5, 1.0, 1.0, 0.5]);
+        let excluded = avg_pool2d(&x, (2, 2), Some((1, 1)), (1, 1), false, false, None);
+        assert_eq!(excluded.to_list::<f32>(), vec![1.0; 16]);
+        let summed = avg_pool2d(&x, (2, 2), Some((1, 1)), (1, 1), false, true, Some(1));
+        assert_eq!(&summed.to_list::<f32>()[..4], &[1.0, 2.0, 2.0, 1.0]);
+
+        let mut signal = Tensor::from_vec(vec![1.0f32, 2.0, 3.0, 4.0, 5.0], &[1, 5]);
+        signal.set_requires_grad(true);
+        let pooled = avg_pool1d(&signal, 2, None, 0, true, true);
+        assert_eq!(pooled.to_list::<f32>(), vec![1.5, 3.5, 5.0]);
+        pooled.sum().backward();
+        assert_eq!(signal.grad().to_list::<f32>(), vec![0.5, 0.5, 0.5, 0.5, 1.0]);
+
+        let volume = avg_pool3d(&grid(8, &[1, 1, 2, 2, 2]), (2, 2, 2), None, (0, 0, 0), false, true, None);
+        assert_eq!(volume.to_list::<f32>(), vec![3.5]);
+    }
+
+    #[test]
+    fn test_func_adaptive_and_global_pool() {
+        let mut x = grid(15, &[1, 1, 3, 5]);
+        x.set_requires_grad(true);
+        let averaged = adaptive_avg_pool2d(&x, (2, 3));
+        assert_eq!(averaged.to_list::<f32>(), vec![3.0, 4.5, 6.0, 8.0, 9.5, 11.0]);
+        let (maxima, indices) = adaptive_max_pool2d_with_indices(&x, (2, 3));
+        assert_eq!(maxima.to_list::<f32>(), vec![6.0, 8.0, 9.0, 11.0, 13.0, 14.0]);
+        assert_eq!(indices.to_list::<i64>(), vec![6, 8, 9, 11, 13, 14]);
+
+        assert_eq!(global_avg_pool2d(&x).to_list::<f32>(), vec![7.0]);
+        assert_eq!(global_max_pool2d(&x).shape(), vec![1, 1, 1, 1]);
+        global_avg_pool2d(&x).sum().backward();
+        let grad = x.grad().to_list::<f32>();
+        assert!(grad.iter().all(|&g| (g - 1.0 / 15.0).abs() < 1e-7));
+    }
+
+    #[test]
+    fn test_func_lp_pool_and_unpool() {
+        let mut x = Tensor::from_vec(vec![3.0f32, 4.0, 0.0, 0.0], &[1, 1, 2, 2]);
+        x.set_requires_grad(true);
+        let norm = lp_pool2d(&x, 2.0, (2, 2), None, false);
+        assert_vec_near(&norm.to_list::<f32>(), &[5.0], 1e-5);
+        norm.sum().backward();
+        assert_vec_near(&x.grad().to_list::<f32>(), &[0.6, 0.8, 0.0, 0.0], 1e-5);
+
+        let mut x = grid(16, &[1, 1, 4, 4]);
+        x.set_requires_grad(true);
+        let (pooled, indices) = max_pool2d_with_indices(&x, (2, 2), None, (0, 0), (1, 1), false);
+        let restored = max_unpool2d(&pooled, &indices, (2, 2), None, (0, 0), None);
+        let mut expected = vec![0.0f32; 16];
+        for i in [5, 7, 13, 15] {
+            expected[i] = i as f32;
+        }
+        assert_eq!(restored.to_list::<f32>(), expected);
+        (&restored * &grid(16, &[1, 1, 4, 4])).sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), expected);
+        assert!(!max_unpool2d(&pooled, &indices, (2, 2), None, (0, 0), Some((2, 2))).defined());
+    }
+
+    #[test]
+    fn test_func_meta_shape_rules() {
+        let meta = Options::default().device(Device::meta());
+        let x = Tensor::empty_with_options(&[2, 3, 8, 8], meta.clone());
+        let weight = Tensor::empty_with_options(&[4, 3, 3, 3], meta.clone());
+
+        let y = conv2d(&x, &weight, None, (2, 2), (1, 1), (1, 1), 1);
+        assert!(y.is_meta());
+        assert_eq!(y.shape(), vec![2, 4, 4, 4]);
+        let transposed = Tensor::empty_with_options(&[4, 3, 3, 3], meta.clone());
+        assert_eq!(conv_transpose2d(&y, &transposed, None, (2, 2), (1, 1), (1, 1), 1, (1, 1)).shape(), vec![2, 3, 8, 8]);
+
+        let (pooled, indices) = max_pool2d_with_indices(&x, (2, 2), None, (0, 0), (1, 1), false);
+        assert_eq!((pooled.shape(), indices.dtype()), (vec![2, 3, 4, 4], DType::Int64));
+        assert!(indices.is_meta());
+        assert_eq!(avg_pool2d(&x, (2, 2), None, (0, 0), false, true, None).shape(), vec![2, 3, 4, 4]);
+        assert_eq!(adaptive_avg_pool2d(&x, (3, 5)).shape(), vec![2, 3, 3, 5]);
+        assert_eq!(lp_pool2d(&x, 2.0, (4, 4), None, false).shape(), vec![2, 3, 2, 2]);
+        assert_eq!(max_unpool2d(&pooled, &indices, (2, 2), None, (0, 0), None).shape(), vec![2, 3, 8, 8]);
+
+        assert_eq!(batch_norm(&x, None, None, None, None, true, 0.1, 1e-5).shape(), vec![2, 3, 8, 8]);
+        assert_eq!(layer_norm(&x, &[8, 8], None, None, 1e-5).shape(), vec![2, 3, 8, 8]);
+        assert!(group_norm(&x, 3, None, None, 1e-5).is_meta());
+        assert!(rms_norm(&x, &[8], None, None).is_meta());
+    }
+
+    #[test]
+    fn test_func_batch_norm_running_stats() {
+        let x = Tensor::from_vec(vec![1.0f32, 10.0, 2.0, 20.0, 3.0, 30.0, 4.0, 40.0], &[4, 2]);
+        let running_mean = Tensor::zeros(&[2]);
+        let running_var = Tensor::ones(&[2]);
+
+        let y = batch_norm(&x, Some(&running_mean), Some(&running_var), None, None, true, 0.1, 0.0);
+        let scale = 1.0 / 1.25f32.sqrt();
+        assert_vec_near(&y.to_list::<f32>()[..2], &[-1.5 * scale, -1.5 * scale], 1e-5);
+        assert_vec_near(&running_mean.to_list::<f32>(), &[0.25, 2.5], 1e-5);
+        assert_vec_near(&running_var.to_list::<f32>(), &[0.9 + 0.5 / 3.0, 0.9 + 50.0 / 3.0], 1e-4);
+
+        let y = batch_norm(&x, Some(&running_mean), Some(&running_var), None, None, false, 0.1, 0.0);
+        let expected = (1.0 - 0.25) / (0.9f32 + 0.5 / 3.0).sqrt();
+        assert_vec_near(&y.to_list::<f32>()[..1], &[expected], 1e-5);
+        assert_vec_near(&running_mean.to_list::<f32>(), &[0.25, 2.5], 1e-5);
+
+        let single = Tensor::from_vec(vec![1.0f32, 2.0], &[1, 2, 1, 1]);
+        assert!(!batch_norm2d(&single, None, None, None, None, true, 0.1, 1e-5).defined());
+        assert!(!batch_norm(&x, Some(&Tensor::zeros(&[3])), None, None, None, true, 0.1, 1e-5).defined());
+    }
+
+    #[test]
+    fn test_func_normalization_forward() {
+        let x = Tensor::from_vec(vec![1.0f32, 2.0, 3.0, 4.0, 6.0, 8.0], &[2, 3]);
+        let weight = Tensor::from_vec(vec![1.0f32, 2.0, 3.0], &[3]);
+        let bias = Tensor::from_vec(vec![0.0f32, 1.0, 0.0], &[3]);
+        let y = layer_norm(&x, &[3], Some(&weight), Some(&bias), 0.0);
+        let a = 1.5f32.sqrt();
+        assert_vec_near(&y.to_list::<f32>(), &[-a, 1.0, 3.0 * a, -a, 1.0, 3.0 * a], 1e-5);
+        assert!(!layer_norm(&x, &[2], None, None, 1e-5).defined());
+
+        let mut generator = Generator::with_seed(49);
+        let x = Tensor::normal(0.0, 1.0, &[2, 4, 3], Options::default(), Some(&mut generator));
+        assert_vec_near(
+            &group_norm(&x, 1, None, None, 1e-5).to_list::<f32>(),
+            &layer_norm(&x, &[4, 3], None, None, 1e-5).to_list::<f32>(),
+            1e-5,
+        );
+        assert_vec_near(
+            &group_norm(&x, 4, None, None, 1e-5).to_list::<f32>(),
+            &instance_norm(&x, None, None, None, None, true, 0.1, 1e-5).to_list::<f32>(),
+            1e-5,
+        );
+        assert!(!group_norm(&x, 3, None, None, 1e-5).defined());
+
+        let y = rms_norm(&Tensor::from_vec(vec![3.0f32, 4.0], &[1, 2]), &[2], None, Some(0.0));
+        let rms = 12.5f32.sqrt();
+        assert_vec_near(&y.to_list::<f32>(), &[3.0 / rms, 4.0 / rms], 1e-6);
+    }
+
+    fn check_gradient(f: impl Fn(&Tensor) -> Tensor, shape: &[i64], generator: &mut Generator) {
+        let data = Tensor::normal(0.0, 1.0, shape, Options::default(), Some(&mut *generator)).to_list::<f32>();
+        let mut x = Tensor::from_vec(data.clone(), shape);
+        x.set_requires_grad(true);
+        let y = f(&x);
+        let g = Tensor::normal(0.0, 1.0, &y.shape(), Options::default(), Some(generator));
+        (&y * &g).sum().backward();
+        let grad = x.grad().to_list::<f32>();
+
+        let h = 1e-2;
+        for i in 0..data.len() {
+            let mut plus = data.clone();
+            let mut minus = data.clone();
+            plus[i] += h;
+            minus[i] -= h;
+            let numeric = (dot(&f(&Tensor::from_vec(plus, shape)), &g) - dot(&f(&Tensor::from_vec(minus, shape)), &g)) / (2.0 * h);
+            assert!((numeric - grad[i]).abs() < 5e-3 * numeric.abs().max(1.0), "{} vs {}", numeric, grad[i]);
+        }
+    }
+
+    #[test]
+    fn test_func_normalization_backward() {
+        let mut generator = Generator::with_seed(3);
+        let input = Tensor::normal(0.0, 1.0, &[3, 4, 2], Options::default(), Some(&mut generator));
+        let weight = Tensor::normal(1.0, 0.5, &[4], Options::default(), Some(&mut generator));
+        let bias = Tensor::normal(0.0, 0.5, &[4], Options::default(), Some(&mut generator));
+        let running_mean = Tensor::normal(0.0, 1.0, &[4], Options::default(), Some(&mut generator));
+        let running_var = Tensor::uniform(0.5, 2.0, &[4], Options::default(), Some(&mut generator));
+
+        check_gradient(|x| batch_norm(x, None, None, Some(&weight), Some(&bias), true, 0.1, 1e-5), &[3, 4, 2], &mut generator);
+        check_gradient(
+            |x| batch_norm(x, Some(&running_mean), Some(&running_var), Some(&weight), None, false, 0.1, 1e-5),
+            &[3, 4, 2],
+            &mut generator,
+        );
+        check_gradient(|w| batch_norm(&input, None, None, Some(w), Some(&bias), true, 0.1, 1e-5), &[4], &mut generator);
+        check_gradient(|b| batch_norm(&input, None, None, Some(&weight), Some(b), true, 0.1, 1e-5), &[4], &mut generator);
+        check_gradient(|x| instance_norm(x, None, None, Some(&weight), Some(&bias), true, 0.1, 1e-5), &[2, 4, 3], &mut generator);
+        check_gradient(|x| group_norm(x, 2, Some(&weight), Some(&bias), 1e-5), &[2, 4, 3], &mut generator);
+        check_gradient(|w| group_norm(&input, 2, Some(w), Some(&bias), 1e-5), &[4], &mut generator);
+
+        let weight = Tensor::normal(1.0, 0.5, &[2, 3], Options::default(), Some(&mut generator));
+        let input = Tensor::normal(0.0, 1.0, &[3, 2, 3], Options::default(), Some(&mut generator));
+        check_gradient(|x| layer_norm(x, &[2, 3], Some(&weight), None, 1e-5), &[3, 2, 3], &mut generator);
+        check_gradient(|w| layer_norm(&input, &[2, 3], Some(w), None, 1e-5), &[2, 3], &mut generator);
+        check_gradient(|x| rms_norm(x, &[3], Some(&weight.narrow(0, 0, 1).reshape(&[3])), None), &[4, 3], &mut generator);
+        check_gradient(|w| rms_norm(&input, &[2, 3], Some(w), None), &[2, 3], &mut generator);
+    }
+
+    #[test]
+    fn test_func_recurrent_cells() {
+        let x = Tensor::from_vec(vec![1.0f32], &[1, 1]);
+        let h = Tensor::from_vec(vec![0.5f32], &[1, 1]);
+        let c = Tensor::from_vec(vec![-1.0f32], &[1, 1]);
+        let w_ih = Tensor::from_vec(vec![1.0f32, 2.0, 3.0, 4.0], &[4, 1]);
+        let w_hh = Tensor::from_vec(vec![0.0f32, 2.0, -2.0, 2.0], &[4, 1]);
+        let (h1, c1) = lstm_cell(&x, (&h, &c), &w_ih, &w_hh, None, None);
+        let sig = |v: f32| 1.0 / (1.0 + (-v).exp());
+        let expected_c = -sig(3.0) + sig(1.0) * 2.0f32.tanh();
+        assert_vec_near(&c1.to_list::<f32>(), &[expected_c], 1e-6);
+        assert_vec_near(&h1.to_list::<f32>(), &[sig(5.0) * expected_c.tanh()], 1e-6);
+
+        let w_ih = Tensor::from_vec(vec![1.0f32, 2.0, 3.0], &[3, 1]);
+        let w_hh = Tensor::from_vec(vec![1.0f32, -1.0, 2.0], &[3, 1]);
+        let b_hh = Tensor::from_vec(vec![0.0f32, 0.0, 1.0], &[3]);
+        let h1 = gru_cell(&x, &h, &w_ih, &w_hh, None, Some(&b_hh));
+        let (r, z) = (sig(1.5), sig(1.5));
+        let n = (3.0 + r * 2.0f32).tanh();
+        assert_vec_near(&h1.to_list::<f32>(), &[(1.0 - z) * n + z * 0.5], 1e-6);
+        assert_vec_near(&rnn_relu_cell(&x, &h, &w_ih.narrow(0, 0, 1), &w_hh.narrow(0, 1, 1), None, None).to_list::<f32>(), &[0.5], 1e-6);
+
+        let mut generator = Generator::with_seed(50);
+        let w_ih = Tensor::normal(0.0, 0.5, &[8, 3], Options::default(), Some(&mut generator));
+        let w_hh = Tensor::normal(0.0, 0.5, &[8, 2], Options::default(), Some(&mut generator));
+        let b = Tensor::normal(0.0, 0.5, &[8], Options::default(), Some(&mut generator));
+        let h = Tensor::normal(0.0, 1.0, &[2, 2], Options::default(), Some(&mut generator));
+        let c = Tensor::normal(0.0, 1.0, &[2, 2], Options::default(), Some(&mut generator));
+        check_gradient(|x| { let (h, c) = lstm_cell(x, (&h, &c), &w_ih, &w_hh, Some(&b), None); &h + &c }, &[2, 3], &mut generator);
+        check_gradient(|h| gru_cell(&c, h, &w_hh.narrow(0, 0, 6), &w_hh.narrow(0, 2, 6), None, Some(&b.narrow(0, 0, 6))), &[2, 2], &mut generator);
+        check_gradient(|w| rnn_tanh_cell(&h, &c, w, &w_hh.narrow(0, 0, 2), None, None), &[2, 2], &mut generator);
+    }
+
+    #[test]
+    fn test_func_pack_and_pad_sequence() {
+        let a = grid(3, &[3, 1]);
+        let b = Tensor::from_vec(vec![10.0f32], &[1, 1]);
+        let c = Tensor::from_vec(vec![20.0f32, 21.0], &[2, 1]);
+        let padded = pad_sequence(&[Clone::clone(&a), Clone::clone(&b), Clone::clone(&c)], true, -1.0);
+        assert_eq!(padded.shape(), vec![3, 3, 1]);
+        assert_eq!(padded.to_list::<f32>(), vec![0.0, 1.0, 2.0, 10.0, -1.0, -1.0, 20.0, 21.0, -1.0]);
+        let longs = pad_sequence(&[Tensor::from_vec(vec![1i64, 2], &[2]), Tensor::from_vec(vec![3i64], &[1])], false, 0.0);
+        assert_eq!((longs.dtype(), longs.to_list::<i64>()), (DType::Int64, vec![1, 3, 2, 0]));
+        let meta = pad_sequence(&[a.to(Device::meta()), b.to(Device::meta())], false, 0.0);
+        assert!(meta.is_meta());
+        assert_eq!(meta.shape(), vec![3, 2, 1]);
+
+        let packed = pack_padded_sequence(&padded, &[3, 1, 2], true, false).unwrap();
+        assert_eq!(packed.data.to_list::<f32>(), vec![0.0, 20.0, 10.0, 1.0, 21.0, 2.0]);
+        assert_eq!(packed.batch_sizes, vec![3, 2, 1]);
+        assert_eq!(packed.sorted_indices, Some(vec![0, 2, 1]));
+        assert_eq!(packed.unsorted_indices, Some(vec![0, 2, 1]));
+        assert!(pack_padded_sequence(&padded, &[3, 1, 2], true, true).is_err());
+        assert!(pack_padded_sequence(&padded, &[3, 0, 2], true, false).is_err());
+
+        let (unpacked, lengths) = pad_packed_sequence(&packed, true, -1.0, Some(4)).unwrap();
+        assert_eq!(lengths, vec![3, 1, 2]);
+        assert_eq!(unpacked.shape(), vec![3, 4, 1]);
+        assert_eq!(unpacked.narrow(1, 0, 3).to_list::<f32>(), padded.to_list::<f32>());
+        assert!(pad_packed_sequence(&packed, true, 0.0, Some(2)).is_err());
+
+        let mut a = grid(3, &[3, 1]);
+        a.set_requires_grad(true);
+        let packed = pack_sequence(&[Clone::clone(&a), Clone::clone(&c)], true).unwrap();
+        assert_eq!(packed.batch_sizes, vec![2, 2, 1]);
+        (&packed.data * &grid(5, &[5, 1])).sum().backward();
+        assert_eq!(a.grad().to_list::<f32>(), vec![0.0, 2.0, 4.0]);
+    }
 }
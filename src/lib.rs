@@ -5,6 +5,7 @@ pub mod functions;
 pub mod optimizers;
 pub mod data;
 pub mod serialization;
+pub mod nn;
 
 pub use tensor::*;
 pub use autograd::*;
@@ -0,0 +1,368 @@
+use crate::nn::Module;
+use crate::tensor::Tensor;
+use std::ops::{Index, IndexMut};
+
+pub struct Sequential {
+    modules: Vec<Box<dyn Module>>,
+    training: bool,
+}
+
+impl Sequential {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+            training: true,
+        }
+    }
+
+    pub fn with_module(mut self, module: impl Module + 'static) -> Self {
+        self.append(module);
+        self
+    }
+
+    pub fn append(&mut self, module: impl Module + 'static) -> &mut Self {
+        self.modules.push(Box::new(module));
+        self
+    }
+
+    pub fn get(&self, index: usize) -> Option<&dyn Module> {
+        self.modules.get(index).map(|module| module.as_ref())
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut dyn Module> {
+        match self.modules.get_mut(index) {
+            Some(module) => Some(module.as_mut()),
+            None => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+impl Default for Sequential {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for Sequential {
+    type Output = dyn Module;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.modules[index].as_ref()
+    }
+}
+
+impl IndexMut<usize> for Sequential {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.modules[index].as_mut()
+    }
+}
+
+impl Module for Sequential {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.modules
+            .iter()
+            .fold(Clone::clone(input), |output, module| module.forward(&output))
+    }
+
+    fn named_children(&self) -> Vec<(String, &dyn Module)> {
+        indexed_children(&self.modules)
+    }
+
+    fn named_children_mut(&mut self) -> Vec<(String, &mut dyn Module)> {
+        indexed_children_mut(&mut self.modules)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct ModuleList {
+    modules: Vec<Box<dyn Module>>,
+    training: bool,
+}
+
+impl ModuleList {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+            training: true,
+        }
+    }
+
+    pub fn push(&mut self, module: impl Module + 'static) {
+        self.modules.push(Box::new(module));
+    }
+
+    pub fn insert(&mut self, index: usize, module: impl Module + 'static) {
+        self.modules.insert(index, Box::new(module));
+    }
+
+    pub fn get(&self, index: usize) -> Option<&dyn Module> {
+        self.modules.get(index).map(|module| module.as_ref())
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut dyn Module> {
+        match self.modules.get_mut(index) {
+            Some(module) => Some(module.as_mut()),
+            None => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Module> {
+        self.modules.iter().map(|module| module.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+impl Default for ModuleList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for ModuleList {
+    type Output = dyn Module;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.modules[index].as_ref()
+    }
+}
+
+impl IndexMut<usize> for ModuleList {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.modules[index].as_mut()
+    }
+}
+
+impl Module for ModuleList {
+    fn forward(&self, _input: &Tensor) -> Tensor {
+        Tensor::new()
+    }
+
+    fn named_children(&self) -> Vec<(String, &dyn Module)> {
+        indexed_children(&self.modules)
+    }
+
+    fn named_children_mut(&mut self) -> Vec<(String, &mut dyn Module)> {
+        indexed_children_mut(&mut self.modules)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct ModuleDict {
+    modules: Vec<(String, Box<dyn Module>)>,
+    training: bool,
+}
+
+impl ModuleDict {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+            training: true,
+        }
+    }
+
+    pub fn insert(&mut self, key: &str, module: impl Module + 'static) -> Option<Box<dyn Module>> {
+        let module: Box<dyn Module> = Box::new(module);
+        match self.modules.iter_mut().find(|(name, _)| name == key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, module)),
+            None => {
+                self.modules.push((key.to_string(), module));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Box<dyn Module>> {
+        let position = self.modules.iter().position(|(name, _)| name == key)?;
+        Some(self.modules.remove(position).1)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&dyn Module> {
+        self.modules
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, module)| module.as_ref())
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut dyn Module> {
+        match self.modules.iter_mut().find(|(name, _)| name == key) {
+            Some((_, module)) => Some(module.as_mut()),
+            None => None,
+        }
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.modules.iter().any(|(name, _)| name == key)
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.modules.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+}
+
+impl Default for ModuleDict {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<&str> for ModuleDict {
+    type Output = dyn Module;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self.modules.iter().find(|(name, _)| name == key) {
+            Some((_, module)) => module.as_ref(),
+            None => panic!("ModuleDict has no key '{}'", key),
+        }
+    }
+}
+
+impl Module for ModuleDict {
+    fn forward(&self, _input: &Tensor) -> Tensor {
+        Tensor::new()
+    }
+
+    fn named_children(&self) -> Vec<(String, &dyn Module)> {
+        self.modules
+            .iter()
+            .map(|(name, module)| (name.clone(), module.as_ref()))
+            .collect()
+    }
+
+    fn named_children_mut(&mut self) -> Vec<(String, &mut dyn Module)> {
+        self.modules
+            .iter_mut()
+            .map(|(name, module)| (name.clone(), module.as_mut() as &mut dyn Module))
+            .collect()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct ParameterList {
+    parameters: Vec<Tensor>,
+    training: bool,
+}
+
+impl ParameterList {
+    pub fn new() -> Self {
+        Self {
+            parameters: Vec::new(),
+            training: true,
+        }
+    }
+
+    pub fn push(&mut self, parameter: Tensor) {
+        self.parameters.push(parameter);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Tensor> {
+        self.parameters.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tensor> {
+        self.parameters.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+impl Default for ParameterList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<usize> for ParameterList {
+    type Output = Tensor;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.parameters[index]
+    }
+}
+
+impl Module for ParameterList {
+    fn forward(&self, _input: &Tensor) -> Tensor {
+        Tensor::new()
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        self.parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| (index.to_string(), Clone::clone(parameter)))
+            .collect()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+fn indexed_children(modules: &[Box<dyn Module>]) -> Vec<(String, &dyn Module)> {
+    modules
+        .iter()
+        .enumerate()
+        .map(|(index, module)| (index.to_string(), module.as_ref()))
+        .collect()
+}
+
+fn indexed_children_mut(modules: &mut [Box<dyn Module>]) -> Vec<(String, &mut dyn Module)> {
+    modules
+        .iter_mut()
+        .enumerate()
+        .map(|(index, module)| (index.to_string(), module.as_mut() as &mut dyn Module))
+        .collect()
+}
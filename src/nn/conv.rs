@@ -0,0 +1,252 @@
+use crate::functions::{conv_transpose, convolution, Padding};
+use crate::nn::init::calculate_fan_in_and_fan_out;
+use crate::nn::linear::{bias_parameter, weight_parameter};
+use crate::nn::Module;
+use crate::tensor::{Generator, Tensor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvConfig<const N: usize> {
+    pub kernel_size: [i64; N],
+    pub stride: [i64; N],
+    pub padding: Padding<[i64; N]>,
+    pub dilation: [i64; N],
+    pub groups: i64,
+    pub bias: bool,
+}
+
+impl<const N: usize> ConvConfig<N> {
+    pub fn new(kernel_size: [i64; N]) -> Self {
+        Self {
+            kernel_size,
+            stride: [1; N],
+            padding: Padding::Explicit([0; N]),
+            dilation: [1; N],
+            groups: 1,
+            bias: true,
+        }
+    }
+
+    pub fn stride(mut self, stride: [i64; N]) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    pub fn padding(mut self, padding: impl Into<Padding<[i64; N]>>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    pub fn dilation(mut self, dilation: [i64; N]) -> Self {
+        self.dilation = dilation;
+        self
+    }
+
+    pub fn groups(mut self, groups: i64) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub fn bias(mut self, bias: bool) -> Self {
+        self.bias = bias;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvTransposeConfig<const N: usize> {
+    pub kernel_size: [i64; N],
+    pub stride: [i64; N],
+    pub padding: [i64; N],
+    pub output_padding: [i64; N],
+    pub dilation: [i64; N],
+    pub groups: i64,
+    pub bias: bool,
+}
+
+impl<const N: usize> ConvTransposeConfig<N> {
+    pub fn new(kernel_size: [i64; N]) -> Self {
+        Self {
+            kernel_size,
+            stride: [1; N],
+            padding: [0; N],
+            output_padding: [0; N],
+            dilation: [1; N],
+            groups: 1,
+            bias: true,
+        }
+    }
+
+    pub fn stride(mut self, stride: [i64; N]) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    pub fn padding(mut self, padding: [i64; N]) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn output_padding(mut self, output_padding: [i64; N]) -> Self {
+        self.output_padding = output_padding;
+        self
+    }
+
+    pub fn dilation(mut self, dilation: [i64; N]) -> Self {
+        self.dilation = dilation;
+        self
+    }
+
+    pub fn groups(mut self, groups: i64) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    pub fn bias(mut self, bias: bool) -> Self {
+        self.bias = bias;
+        self
+    }
+}
+
+pub struct Conv<const N: usize> {
+    pub in_channels: i64,
+    pub out_channels: i64,
+    pub config: ConvConfig<N>,
+    pub weight: Tensor,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+pub type Conv1d = Conv<1>;
+pub type Conv2d = Conv<2>;
+pub type Conv3d = Conv<3>;
+
+impl<const N: usize> Conv<N> {
+    pub fn new(in_channels: i64, out_channels: i64, config: ConvConfig<N>) -> Self {
+        Self::with_generator(in_channels, out_channels, config, None)
+    }
+
+    pub fn with_generator(
+        in_channels: i64,
+        out_channels: i64,
+        config: ConvConfig<N>,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        let mut shape = vec![out_channels, in_channels / config.groups.max(1)];
+        shape.extend_from_slice(&config.kernel_size);
+        let (weight, bias) = conv_parameters(&shape, out_channels, config.bias, generator);
+        Self {
+            in_channels,
+            out_channels,
+            config,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+}
+
+impl<const N: usize> Module for Conv<N> {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        let config = &self.config;
+        let padding = config.padding.into_vec(|padding| padding.to_vec());
+        convolution(input, &self.weight, self.bias.as_ref(), &config.stride, padding, &config.dilation, config.groups)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named_parameters(&self.weight, self.bias.as_ref())
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct ConvTranspose<const N: usize> {
+    pub in_channels: i64,
+    pub out_channels: i64,
+    pub config: ConvTransposeConfig<N>,
+    pub weight: Tensor,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+pub type ConvTranspose1d = ConvTranspose<1>;
+pub type ConvTranspose2d = ConvTranspose<2>;
+pub type ConvTranspose3d = ConvTranspose<3>;
+
+impl<const N: usize> ConvTranspose<N> {
+    pub fn new(in_channels: i64, out_channels: i64, config: ConvTransposeConfig<N>) -> Self {
+        Self::with_generator(in_channels, out_channels, config, None)
+    }
+
+    pub fn with_generator(
+        in_channels: i64,
+        out_channels: i64,
+        config: ConvTransposeConfig<N>,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        let mut shape = vec![in_channels, out_channels / config.groups.max(1)];
+        shape.extend_from_slice(&config.kernel_size);
+        let (weight, bias) = conv_parameters(&shape, out_channels, config.bias, generator);
+        Self {
+            in_channels,
+            out_channels,
+            config,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+}
+
+impl<const N: usize> Module for ConvTranspose<N> {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        let config = &self.config;
+        conv_transpose(
+            input,
+            &self.weight,
+            self.bias.as_ref(),
+            &config.stride,
+            &config.padding,
+            &config.output_padding,
+            config.groups,
+            &config.dilation,
+        )
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named_parameters(&self.weight, self.bias.as_ref())
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+fn conv_parameters(
+    shape: &[i64],
+    bias_len: i64,
+    bias: bool,
+    mut generator: Option<&mut Generator>,
+) -> (Tensor, Option<Tensor>) {
+    let weight = weight_parameter(shape, generator.as_deref_mut());
+    let fan_in = calculate_fan_in_and_fan_out(&weight).map_or(0, |(fan_in, _)| fan_in);
+    let bias = bias.then(|| bias_parameter(bias_len, fan_in, generator));
+    (weight, bias)
+}
+
+fn named_parameters(weight: &Tensor, bias: Option<&Tensor>) -> Vec<(String, Tensor)> {
+    let mut parameters = vec![("weight".to_string(), Clone::clone(weight))];
+    if let Some(bias) = bias {
+        parameters.push(("bias".to_string(), Clone::clone(bias)));
+    }
+    parameters
+}
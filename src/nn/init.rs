@@ -0,0 +1,267 @@
+use crate::tensor::{with_generator, Generator, Options, Tensor};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nonlinearity {
+    Linear,
+    Conv1d,
+    Conv2d,
+    Conv3d,
+    ConvTranspose1d,
+    ConvTranspose2d,
+    ConvTranspose3d,
+    Sigmoid,
+    Tanh,
+    ReLU,
+    LeakyReLU(f64),
+    SELU,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FanMode {
+    FanIn,
+    FanOut,
+}
+
+pub fn calculate_gain(nonlinearity: Nonlinearity) -> f64 {
+    match nonlinearity {
+        Nonlinearity::Tanh => 5.0 / 3.0,
+        Nonlinearity::ReLU => 2.0f64.sqrt(),
+        Nonlinearity::LeakyReLU(negative_slope) => (2.0 / (1.0 + negative_slope * negative_slope)).sqrt(),
+        Nonlinearity::SELU => 3.0 / 4.0,
+        _ => 1.0,
+    }
+}
+
+pub fn calculate_fan_in_and_fan_out(tensor: &Tensor) -> Result<(i64, i64), String> {
+    if !tensor.defined() {
+        return Err("Cannot compute fans of an undefined tensor".to_string());
+    }
+    let shape = tensor.shape();
+    if shape.len() < 2 {
+        return Err("Fan in and fan out can not be computed for tensor with fewer than 2 dimensions".to_string());
+    }
+
+    let receptive_field: i64 = shape[2..].iter().product();
+    Ok((shape[1] * receptive_field, shape[0] * receptive_field))
+}
+
+pub fn calculate_correct_fan(tensor: &Tensor, mode: FanMode) -> Result<i64, String> {
+    let (fan_in, fan_out) = calculate_fan_in_and_fan_out(tensor)?;
+    Ok(match mode {
+        FanMode::FanIn => fan_in,
+        FanMode::FanOut => fan_out,
+    })
+}
+
+pub fn constant_(tensor: &mut Tensor, value: f64) -> Result<(), String> {
+    match tensor.impl_.as_ref() {
+        Some(impl_) => impl_.write_f64(&vec![value; impl_.numel() as usize]),
+        None => Err("Cannot initialize an undefined tensor".to_string()),
+    }
+}
+
+pub fn zeros_(tensor: &mut Tensor) -> Result<(), String> {
+    constant_(tensor, 0.0)
+}
+
+pub fn ones_(tensor: &mut Tensor) -> Result<(), String> {
+    constant_(tensor, 1.0)
+}
+
+pub fn eye_(tensor: &mut Tensor) -> Result<(), String> {
+    let shape = tensor.shape();
+    if !tensor.defined() || shape.len() != 2 {
+        return Err("Only tensors with 2 dimensions are supported".to_string());
+    }
+
+    let (rows, cols) = (shape[0] as usize, shape[1] as usize);
+    let mut data = vec![0.0; rows * cols];
+    for i in 0..rows.min(cols) {
+        data[i * cols + i] = 1.0;
+    }
+    write(tensor, &data)
+}
+
+pub fn dirac_(tensor: &mut Tensor, groups: i64) -> Result<(), String> {
+    let shape = tensor.shape();
+    if !tensor.defined() || !(3..=5).contains(&shape.len()) {
+        return Err("Only tensors with 3, 4, or 5 dimensions are supported".to_string());
+    }
+    if groups <= 0 || shape[0] % groups != 0 {
+        return Err("dim 0 must be divisible by groups".to_string());
+    }
+
+    let out_channels_per_group = shape[0] / groups;
+    let min_dim = out_channels_per_group.min(shape[1]);
+    let spatial = &shape[2..];
+    let kernel_numel: i64 = spatial.iter().product();
+    let center = spatial.iter().fold(0, |offset, &size| offset * size + size / 2);
+
+    let mut data = vec![0.0; tensor.numel() as usize];
+    for g in 0..groups {
+        for d in 0..min_dim {
+            let out_channel = g * out_channels_per_group + d;
+            data[((out_channel * shape[1] + d) * kernel_numel + center) as usize] = 1.0;
+        }
+    }
+    write(tensor, &data)
+}
+
+pub fn xavier_uniform_(tensor: &mut Tensor, gain: f64, generator: Option<&mut Generator>) -> Result<(), String> {
+    let (fan_in, fan_out) = calculate_fan_in_and_fan_out(tensor)?;
+    let std = gain * (2.0 / (fan_in + fan_out) as f64).sqrt();
+    let bound = 3.0f64.sqrt() * std;
+    uniform(tensor, -bound, bound, generator)
+}
+
+pub fn xavier_normal_(tensor: &mut Tensor, gain: f64, generator: Option<&mut Generator>) -> Result<(), String> {
+    let (fan_in, fan_out) = calculate_fan_in_and_fan_out(tensor)?;
+    let std = gain * (2.0 / (fan_in + fan_out) as f64).sqrt();
+    normal(tensor, 0.0, std, generator)
+}
+
+pub fn kaiming_uniform_(
+    tensor: &mut Tensor,
+    mode: FanMode,
+    nonlinearity: Nonlinearity,
+    generator: Option<&mut Generator>,
+) -> Result<(), String> {
+    if tensor.defined() && tensor.numel() == 0 {
+        return Ok(());
+    }
+    let bound = 3.0f64.sqrt() * kaiming_std(tensor, mode, nonlinearity)?;
+    uniform(tensor, -bound, bound, generator)
+}
+
+pub fn kaiming_normal_(
+    tensor: &mut Tensor,
+    mode: FanMode,
+    nonlinearity: Nonlinearity,
+    generator: Option<&mut Generator>,
+) -> Result<(), String> {
+    if tensor.defined() && tensor.numel() == 0 {
+        return Ok(());
+    }
+    let std = kaiming_std(tensor, mode, nonlinearity)?;
+    normal(tensor, 0.0, std, generator)
+}
+
+pub fn trunc_normal_(
+    tensor: &mut Tensor,
+    mean: f64,
+    std: f64,
+    a: f64,
+    b: f64,
+    generator: Option<&mut Generator>,
+) -> Result<(), String> {
+    if std < 0.0 || b < a {
+        return Err(format!("Invalid truncated normal parameters: std={}, a={}, b={}", std, a, b));
+    }
+    let mut sampled = Clone::clone(tensor);
+    sampled.trunc_normal_(mean, std, a, b, generator)?;
+    Ok(())
+}
+
+pub fn orthogonal_(tensor: &mut Tensor, gain: f64, generator: Option<&mut Generator>) -> Result<(), String> {
+    let shape = tensor.shape();
+    if !tensor.defined() || shape.len() < 2 {
+        return Err("Only tensors with 2 or more dimensions are supported".to_string());
+    }
+    if tensor.numel() == 0 {
+        return Ok(());
+    }
+
+    let rows = shape[0] as usize;
+    let cols = tensor.numel() as usize / rows;
+    let flattened = Tensor::normal(0.0, 1.0, &[rows as i64, cols as i64], Options::default(), generator);
+    let flattened = match flattened.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+        Some(Ok(data)) => data,
+        _ => return Err("Failed to sample orthogonal initialization".to_string()),
+    };
+
+    let (m, n) = (rows.max(cols), rows.min(cols));
+    let column = |j: usize| -> Vec<f64> {
+        (0..m)
+            .map(|i| if rows >= cols { flattened[i * cols + j] } else { flattened[j * cols + i] })
+            .collect()
+    };
+
+    let mut q: Vec<Vec<f64>> = Vec::with_capacity(n);
+    for j in 0..n {
+        let mut v = column(j);
+        for basis in &q {
+            let projection: f64 = basis.iter().zip(v.iter()).map(|(b, x)| b * x).sum();
+            v.iter_mut().zip(basis.iter()).for_each(|(x, b)| *x -= projection * b);
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            v.iter_mut().for_each(|x| *x /= norm);
+        }
+        q.push(v);
+    }
+
+    let mut data = vec![0.0; rows * cols];
+    for (j, basis) in q.iter().enumerate() {
+        for (i, &value) in basis.iter().enumerate() {
+            let (r, c) = if rows >= cols { (i, j) } else { (j, i) };
+            data[r * cols + c] = gain * value;
+        }
+    }
+    write(tensor, &data)
+}
+
+pub fn sparse_(tensor: &mut Tensor, sparsity: f64, std: f64, mut generator: Option<&mut Generator>) -> Result<(), String> {
+    let shape = tensor.shape();
+    if !tensor.defined() || shape.len() != 2 {
+        return Err("Only tensors with 2 dimensions are supported".to_string());
+    }
+    if !(0.0..=1.0).contains(&sparsity) || std < 0.0 {
+        return Err(format!("Invalid sparse initialization parameters: sparsity={}, std={}", sparsity, std));
+    }
+
+    let (rows, cols) = (shape[0] as usize, shape[1] as usize);
+    let num_zeros = (sparsity * rows as f64).ceil() as usize;
+    let sampled = Tensor::normal(0.0, std, &shape, Options::default(), generator.as_deref_mut());
+    let mut data = match sampled.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+        Some(Ok(data)) => data,
+        _ => return Err("Failed to sample sparse initialization".to_string()),
+    };
+
+    with_generator(generator, |rng| {
+        for c in 0..cols {
+            let mut order: Vec<usize> = (0..rows).collect();
+            for i in (1..rows).rev() {
+                order.swap(i, rng.gen_range(0..=i));
+            }
+            for &r in &order[..num_zeros] {
+                data[r * cols + c] = 0.0;
+            }
+        }
+    });
+    write(tensor, &data)
+}
+
+fn kaiming_std(tensor: &Tensor, mode: FanMode, nonlinearity: Nonlinearity) -> Result<f64, String> {
+    let fan = calculate_correct_fan(tensor, mode)?;
+    Ok(calculate_gain(nonlinearity) / (fan as f64).sqrt())
+}
+
+fn uniform(tensor: &mut Tensor, low: f64, high: f64, generator: Option<&mut Generator>) -> Result<(), String> {
+    let mut sampled = Clone::clone(tensor);
+    sampled.uniform_(low, high, generator)?;
+    Ok(())
+}
+
+fn normal(tensor: &mut Tensor, mean: f64, std: f64, generator: Option<&mut Generator>) -> Result<(), String> {
+    let mut sampled = Clone::clone(tensor);
+    sampled.normal_(mean, std, generator)?;
+    Ok(())
+}
+
+fn write(tensor: &Tensor, data: &[f64]) -> Result<(), String> {
+    match tensor.impl_.as_ref() {
+        Some(impl_) => impl_.write_f64(data),
+        None => Err("Cannot initialize an undefined tensor".to_string()),
+    }
+}
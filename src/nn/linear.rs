@@ -0,0 +1,248 @@
+use crate::functions::{bilinear, linear};
+use crate::nn::init::{kaiming_uniform_, FanMode, Nonlinearity};
+use crate::nn::Module;
+use crate::tensor::{Generator, Options, Tensor};
+use std::cell::RefCell;
+
+pub struct Identity {
+    training: bool,
+}
+
+impl Identity {
+    pub fn new() -> Self {
+        Self { training: true }
+    }
+}
+
+impl Default for Identity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Module for Identity {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        Clone::clone(input)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct Linear {
+    pub in_features: i64,
+    pub out_features: i64,
+    pub weight: Tensor,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+impl Linear {
+    pub fn new(in_features: i64, out_features: i64, bias: bool) -> Self {
+        Self::with_generator(in_features, out_features, bias, None)
+    }
+
+    pub fn with_generator(
+        in_features: i64,
+        out_features: i64,
+        bias: bool,
+        mut generator: Option<&mut Generator>,
+    ) -> Self {
+        let weight = weight_parameter(&[out_features, in_features], generator.as_deref_mut());
+        let bias = bias.then(|| bias_parameter(out_features, in_features, generator));
+        Self {
+            in_features,
+            out_features,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+}
+
+impl Module for Linear {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        linear(input, &self.weight, self.bias.as_ref())
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        let mut parameters = vec![("weight".to_string(), Clone::clone(&self.weight))];
+        if let Some(bias) = &self.bias {
+            parameters.push(("bias".to_string(), Clone::clone(bias)));
+        }
+        parameters
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct Bilinear {
+    pub in1_features: i64,
+    pub in2_features: i64,
+    pub out_features: i64,
+    pub weight: Tensor,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+impl Bilinear {
+    pub fn new(in1_features: i64, in2_features: i64, out_features: i64, bias: bool) -> Self {
+        Self::with_generator(in1_features, in2_features, out_features, bias, None)
+    }
+
+    pub fn with_generator(
+        in1_features: i64,
+        in2_features: i64,
+        out_features: i64,
+        bias: bool,
+        mut generator: Option<&mut Generator>,
+    ) -> Self {
+        let bound = uniform_bound(in1_features);
+        let options = Options::default().requires_grad(true);
+        let shape = [out_features, in1_features, in2_features];
+        let weight = Tensor::uniform(-bound, bound, &shape, options, generator.as_deref_mut());
+        let bias = bias.then(|| bias_parameter(out_features, in1_features, generator));
+        Self {
+            in1_features,
+            in2_features,
+            out_features,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+
+    pub fn forward_pair(&self, input1: &Tensor, input2: &Tensor) -> Tensor {
+        bilinear(input1, input2, &self.weight, self.bias.as_ref())
+    }
+}
+
+impl Module for Bilinear {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        if !input.defined() || input.dim() == 0 {
+            return Tensor::new();
+        }
+        if input.shape()[input.dim() as usize - 1] != self.in1_features + self.in2_features {
+            return Tensor::new();
+        }
+        let input1 = input.narrow(-1, 0, self.in1_features);
+        let input2 = input.narrow(-1, self.in1_features, self.in2_features);
+        self.forward_pair(&input1, &input2)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        let mut parameters = vec![("weight".to_string(), Clone::clone(&self.weight))];
+        if let Some(bias) = &self.bias {
+            parameters.push(("bias".to_string(), Clone::clone(bias)));
+        }
+        parameters
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct LazyLinear {
+    pub out_features: i64,
+    bias: bool,
+    inner: RefCell<Option<Linear>>,
+    training: bool,
+}
+
+impl LazyLinear {
+    pub fn new(out_features: i64, bias: bool) -> Self {
+        Self {
+            out_features,
+            bias,
+            inner: RefCell::new(None),
+            training: true,
+        }
+    }
+
+    pub fn in_features(&self) -> Option<i64> {
+        self.inner.borrow().as_ref().map(|inner| inner.in_features)
+    }
+
+    pub fn is_materialized(&self) -> bool {
+        self.inner.borrow().is_some()
+    }
+
+    pub fn materialize(&self, in_features: i64, generator: Option<&mut Generator>) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.is_none() {
+            let mut linear = Linear::with_generator(in_features, self.out_features, self.bias, generator);
+            linear.set_training(self.training);
+            *inner = Some(linear);
+        }
+    }
+}
+
+impl Module for LazyLinear {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        if !input.defined() || input.dim() == 0 {
+            return Tensor::new();
+        }
+        self.materialize(input.shape()[input.dim() as usize - 1], None);
+        match self.inner.borrow().as_ref() {
+            Some(inner) => inner.forward(input),
+            None => Tensor::new(),
+        }
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        self.inner
+            .borrow()
+            .as_ref()
+            .map(|inner| inner.local_parameters())
+            .unwrap_or_default()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        if let Some(inner) = self.inner.get_mut() {
+            inner.set_training(training);
+        }
+    }
+}
+
+fn uniform_bound(fan_in: i64) -> f64 {
+    if fan_in > 0 {
+        1.0 / (fan_in as f64).sqrt()
+    } else {
+        0.0
+    }
+}
+
+pub(super) fn weight_parameter(shape: &[i64], generator: Option<&mut Generator>) -> Tensor {
+    let mut weight = Tensor::empty_with_options(shape, Options::default().requires_grad(true));
+    let gain = Nonlinearity::LeakyReLU(5.0f64.sqrt());
+    if kaiming_uniform_(&mut weight, FanMode::FanIn, gain, generator).is_err() {
+        return Tensor::new();
+    }
+    weight
+}
+
+pub(super) fn bias_parameter(out_features: i64, fan_in: i64, generator: Option<&mut Generator>) -> Tensor {
+    let bound = uniform_bound(fan_in);
+    Tensor::uniform(-bound, bound, &[out_features], Options::default().requires_grad(true), generator)
+}
@@ -0,0 +1,17 @@
+pub mod module;
+pub mod init;
+pub mod linear;
+pub mod conv;
+pub mod container;
+pub mod normalization;
+pub mod rnn;
+
+pub use module::*;
+pub use linear::*;
+pub use conv::*;
+pub use container::*;
+pub use normalization::*;
+pub use rnn::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,155 @@
+use crate::serialization::ModelState;
+use crate::tensor::{backend, Tensor};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IncompatibleKeys {
+    pub missing_keys: Vec<String>,
+    pub unexpected_keys: Vec<String>,
+}
+
+impl IncompatibleKeys {
+    pub fn is_empty(&self) -> bool {
+        self.missing_keys.is_empty() && self.unexpected_keys.is_empty()
+    }
+}
+
+pub trait Module {
+    fn forward(&self, input: &Tensor) -> Tensor;
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        Vec::new()
+    }
+
+    fn local_buffers(&self) -> Vec<(String, Tensor)> {
+        Vec::new()
+    }
+
+    fn named_children(&self) -> Vec<(String, &dyn Module)> {
+        Vec::new()
+    }
+
+    fn named_children_mut(&mut self) -> Vec<(String, &mut dyn Module)> {
+        Vec::new()
+    }
+
+    fn is_training(&self) -> bool {
+        true
+    }
+
+    fn set_training(&mut self, _training: bool) {}
+
+    fn children(&self) -> Vec<&dyn Module> {
+        self.named_children().into_iter().map(|(_, child)| child).collect()
+    }
+
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        let mut parameters = self.local_parameters();
+        for (name, child) in self.named_children() {
+            parameters.extend(prefixed(&name, child.named_parameters()));
+        }
+        parameters
+    }
+
+    fn parameters(&self) -> Vec<Tensor> {
+        self.named_parameters().into_iter().map(|(_, tensor)| tensor).collect()
+    }
+
+    fn named_buffers(&self) -> Vec<(String, Tensor)> {
+        let mut buffers = self.local_buffers();
+        for (name, child) in self.named_children() {
+            buffers.extend(prefixed(&name, child.named_buffers()));
+        }
+        buffers
+    }
+
+    fn buffers(&self) -> Vec<Tensor> {
+        self.named_buffers().into_iter().map(|(_, tensor)| tensor).collect()
+    }
+
+    fn train_mode(&mut self, training: bool) {
+        self.set_training(training);
+        for (_, child) in self.named_children_mut() {
+            child.train_mode(training);
+        }
+    }
+
+    fn train(&mut self) {
+        self.train_mode(true);
+    }
+
+    fn eval(&mut self) {
+        self.train_mode(false);
+    }
+
+    fn zero_grad(&mut self) {
+        for mut parameter in self.parameters() {
+            parameter.zero_grad();
+        }
+    }
+
+
+    fn state_dict(&self) -> ModelState {
+        let mut state = ModelState::new();
+        for (name, tensor) in self.named_parameters().into_iter().chain(self.named_buffers()) {
+            state.add_parameter(name, tensor.detach());
+        }
+        state
+    }
+
+    fn load_state_dict(&mut self, state: &ModelState, strict: bool) -> Result<IncompatibleKeys, String> {
+        let targets: Vec<(String, Tensor)> = self.named_parameters().into_iter().chain(self.named_buffers()).collect();
+        let target_names: HashSet<&str> = targets.iter().map(|(name, _)| name.as_str()).collect();
+
+        let mut keys = IncompatibleKeys::default();
+        let mut errors = Vec::new();
+        let mut copies = Vec::new();
+        for (name, target) in &targets {
+            match state.get_parameter(name) {
+                None => keys.missing_keys.push(name.clone()),
+                Some(source) if !source.defined() || source.shape() != target.shape() => errors.push(format!(
+                    "size mismatch for {}: copying a param with shape {:?} from checkpoint, the shape in current model is {:?}",
+                    name,
+                    source.shape(),
+                    target.shape()
+                )),
+                Some(source) => copies.push((source, target)),
+            }
+        }
+        keys.unexpected_keys = state
+            .parameter_names()
+            .into_iter()
+            .filter(|name| !target_names.contains(name.as_str()))
+            .cloned()
+            .collect();
+        keys.missing_keys.sort();
+        keys.unexpected_keys.sort();
+
+        if strict && !keys.unexpected_keys.is_empty() {
+            errors.insert(0, format!("Unexpected key(s) in state_dict: {}", keys.unexpected_keys.join(", ")));
+        }
+        if strict && !keys.missing_keys.is_empty() {
+            errors.insert(0, format!("Missing key(s) in state_dict: {}", keys.missing_keys.join(", ")));
+        }
+        if !errors.is_empty() {
+            return Err(format!("Error(s) in loading state_dict: {}", errors.join("; ")));
+        }
+
+        for (source, target) in copies {
+            let (source_impl, target_impl) = match (source.impl_.as_ref(), target.impl_.as_ref()) {
+                (Some(source_impl), Some(target_impl)) => (source_impl, target_impl),
+                _ => continue,
+            };
+            let source_backend = backend(source.device())?;
+            backend(target.device())?.copy_from(source_backend.as_ref(), source_impl, target_impl)?;
+        }
+        Ok(keys)
+    }
+}
+
+fn prefixed(prefix: &str, named: Vec<(String, Tensor)>) -> Vec<(String, Tensor)> {
+    named
+        .into_iter()
+        .map(|(name, tensor)| (format!("{}.{}", prefix, name), tensor))
+        .collect()
+}
@@ -0,0 +1,386 @@
+use crate::functions::{batch_norm, group_norm, instance_norm, layer_norm, rms_norm};
+use crate::nn::Module;
+use crate::tensor::{DType, Options, Tensor};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormConfig {
+    pub eps: f64,
+    pub momentum: Option<f64>,
+    pub affine: bool,
+    pub track_running_stats: bool,
+}
+
+impl NormConfig {
+    pub fn new() -> Self {
+        Self {
+            eps: 1e-5,
+            momentum: Some(0.1),
+            affine: true,
+            track_running_stats: true,
+        }
+    }
+
+    pub fn eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    pub fn momentum(mut self, momentum: Option<f64>) -> Self {
+        self.momentum = momentum;
+        self
+    }
+
+    pub fn affine(mut self, affine: bool) -> Self {
+        self.affine = affine;
+        self
+    }
+
+    pub fn track_running_stats(mut self, track_running_stats: bool) -> Self {
+        self.track_running_stats = track_running_stats;
+        self
+    }
+}
+
+impl Default for NormConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct BatchNorm<const N: usize> {
+    pub num_features: i64,
+    pub config: NormConfig,
+    pub weight: Option<Tensor>,
+    pub bias: Option<Tensor>,
+    pub running_mean: Option<Tensor>,
+    pub running_var: Option<Tensor>,
+    pub num_batches_tracked: Option<Tensor>,
+    training: bool,
+}
+
+pub type BatchNorm1d = BatchNorm<1>;
+pub type BatchNorm2d = BatchNorm<2>;
+pub type BatchNorm3d = BatchNorm<3>;
+
+impl<const N: usize> BatchNorm<N> {
+    pub fn new(num_features: i64) -> Self {
+        Self::with_config(num_features, NormConfig::new())
+    }
+
+    pub fn with_config(num_features: i64, config: NormConfig) -> Self {
+        let (weight, bias) = affine_parameters(&[num_features], config.affine, true);
+        let (running_mean, running_var, num_batches_tracked) = running_buffers(num_features, config.track_running_stats);
+        Self {
+            num_features,
+            config,
+            weight,
+            bias,
+            running_mean,
+            running_var,
+            num_batches_tracked,
+            training: true,
+        }
+    }
+
+    pub fn reset_running_stats(&mut self) {
+        reset_running_stats(&self.running_mean, &self.running_var, &self.num_batches_tracked);
+    }
+}
+
+impl<const N: usize> Module for BatchNorm<N> {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        let dim = input.dim();
+        if !input.defined() || !(dim == N as i64 + 2 || (N == 1 && dim == 2)) {
+            return Tensor::new();
+        }
+
+        let tracked = self.num_batches_tracked.as_ref().and_then(|tracked| tracked.impl_.as_ref());
+        let batches = tracked.filter(|_| self.training).map(|tracked| tracked.item::<i64>().unwrap_or(0) + 1);
+        let momentum = match batches {
+            Some(batches) => self.config.momentum.unwrap_or(1.0 / batches as f64),
+            None => self.config.momentum.unwrap_or(0.0),
+        };
+
+        let output = batch_norm(
+            input,
+            self.running_mean.as_ref(),
+            self.running_var.as_ref(),
+            self.weight.as_ref(),
+            self.bias.as_ref(),
+            self.training || self.running_mean.is_none(),
+            momentum,
+            self.config.eps,
+        );
+        if let (Some(tracked), Some(batches)) = (tracked, batches) {
+            if output.defined() {
+                let _ = tracked.write_f64(&[batches as f64]);
+            }
+        }
+        output
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named(&[("weight", &self.weight), ("bias", &self.bias)])
+    }
+
+    fn local_buffers(&self) -> Vec<(String, Tensor)> {
+        named(&[
+            ("running_mean", &self.running_mean),
+            ("running_var", &self.running_var),
+            ("num_batches_tracked", &self.num_batches_tracked),
+        ])
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct InstanceNorm<const N: usize> {
+    pub num_features: i64,
+    pub config: NormConfig,
+    pub weight: Option<Tensor>,
+    pub bias: Option<Tensor>,
+    pub running_mean: Option<Tensor>,
+    pub running_var: Option<Tensor>,
+    pub num_batches_tracked: Option<Tensor>,
+    training: bool,
+}
+
+pub type InstanceNorm1d = InstanceNorm<1>;
+pub type InstanceNorm2d = InstanceNorm<2>;
+pub type InstanceNorm3d = InstanceNorm<3>;
+
+impl<const N: usize> InstanceNorm<N> {
+    pub fn new(num_features: i64) -> Self {
+        Self::with_config(num_features, NormConfig::new().affine(false).track_running_stats(false))
+    }
+
+    pub fn with_config(num_features: i64, config: NormConfig) -> Self {
+        let (weight, bias) = affine_parameters(&[num_features], config.affine, true);
+        let (running_mean, running_var, num_batches_tracked) = running_buffers(num_features, config.track_running_stats);
+        Self {
+            num_features,
+            config,
+            weight,
+            bias,
+            running_mean,
+            running_var,
+            num_batches_tracked,
+            training: true,
+        }
+    }
+
+    pub fn reset_running_stats(&mut self) {
+        reset_running_stats(&self.running_mean, &self.running_var, &self.num_batches_tracked);
+    }
+}
+
+impl<const N: usize> Module for InstanceNorm<N> {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        let dim = input.dim();
+        if !input.defined() || (dim != N as i64 + 1 && dim != N as i64 + 2) {
+            return Tensor::new();
+        }
+        if dim == N as i64 + 1 {
+            return self.forward(&input.unsqueeze(0)).squeeze(Some(0));
+        }
+
+        instance_norm(
+            input,
+            self.running_mean.as_ref(),
+            self.running_var.as_ref(),
+            self.weight.as_ref(),
+            self.bias.as_ref(),
+            self.training || !self.config.track_running_stats,
+            self.config.momentum.unwrap_or(0.0),
+            self.config.eps,
+        )
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named(&[("weight", &self.weight), ("bias", &self.bias)])
+    }
+
+    fn local_buffers(&self) -> Vec<(String, Tensor)> {
+        named(&[
+            ("running_mean", &self.running_mean),
+            ("running_var", &self.running_var),
+            ("num_batches_tracked", &self.num_batches_tracked),
+        ])
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct LayerNorm {
+    pub normalized_shape: Vec<i64>,
+    pub eps: f64,
+    pub weight: Option<Tensor>,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+impl LayerNorm {
+    pub fn new(normalized_shape: &[i64]) -> Self {
+        Self::with_config(normalized_shape, 1e-5, true, true)
+    }
+
+    pub fn with_config(normalized_shape: &[i64], eps: f64, elementwise_affine: bool, bias: bool) -> Self {
+        let (weight, bias) = affine_parameters(normalized_shape, elementwise_affine, bias);
+        Self {
+            normalized_shape: normalized_shape.to_vec(),
+            eps,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+}
+
+impl Module for LayerNorm {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        layer_norm(input, &self.normalized_shape, self.weight.as_ref(), self.bias.as_ref(), self.eps)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named(&[("weight", &self.weight), ("bias", &self.bias)])
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct GroupNorm {
+    pub num_groups: i64,
+    pub num_channels: i64,
+    pub eps: f64,
+    pub weight: Option<Tensor>,
+    pub bias: Option<Tensor>,
+    training: bool,
+}
+
+impl GroupNorm {
+    pub fn new(num_groups: i64, num_channels: i64) -> Self {
+        Self::with_config(num_groups, num_channels, 1e-5, true)
+    }
+
+    pub fn with_config(num_groups: i64, num_channels: i64, eps: f64, affine: bool) -> Self {
+        let (weight, bias) = affine_parameters(&[num_channels], affine, true);
+        Self {
+            num_groups,
+            num_channels,
+            eps,
+            weight,
+            bias,
+            training: true,
+        }
+    }
+}
+
+impl Module for GroupNorm {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        group_norm(input, self.num_groups, self.weight.as_ref(), self.bias.as_ref(), self.eps)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named(&[("weight", &self.weight), ("bias", &self.bias)])
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct RMSNorm {
+    pub normalized_shape: Vec<i64>,
+    pub eps: Option<f64>,
+    pub weight: Option<Tensor>,
+    training: bool,
+}
+
+impl RMSNorm {
+    pub fn new(normalized_shape: &[i64]) -> Self {
+        Self::with_config(normalized_shape, None, true)
+    }
+
+    pub fn with_config(normalized_shape: &[i64], eps: Option<f64>, elementwise_affine: bool) -> Self {
+        let (weight, _) = affine_parameters(normalized_shape, elementwise_affine, false);
+        Self {
+            normalized_shape: normalized_shape.to_vec(),
+            eps,
+            weight,
+            training: true,
+        }
+    }
+}
+
+impl Module for RMSNorm {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        rms_norm(input, &self.normalized_shape, self.weight.as_ref(), self.eps)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named(&[("weight", &self.weight)])
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+fn affine_parameters(shape: &[i64], affine: bool, bias: bool) -> (Option<Tensor>, Option<Tensor>) {
+    let options = Options::default().requires_grad(true);
+    let weight = affine.then(|| Tensor::ones_with_options(shape, options.clone()));
+    let bias = (affine && bias).then(|| Tensor::zeros_with_options(shape, options));
+    (weight, bias)
+}
+
+fn running_buffers(num_features: i64, track_running_stats: bool) -> (Option<Tensor>, Option<Tensor>, Option<Tensor>) {
+    if !track_running_stats {
+        return (None, None, None);
+    }
+    (
+        Some(Tensor::zeros(&[num_features])),
+        Some(Tensor::ones(&[num_features])),
+        Some(Tensor::zeros_with_options(&[], Options::default().dtype(DType::Int64))),
+    )
+}
+
+fn reset_running_stats(running_mean: &Option<Tensor>, running_var: &Option<Tensor>, num_batches_tracked: &Option<Tensor>) {
+    for (buffer, value) in [(running_mean, 0.0), (running_var, 1.0), (num_batches_tracked, 0.0)] {
+        if let Some(impl_) = buffer.as_ref().and_then(|buffer| buffer.impl_.as_ref()) {
+            let _ = impl_.write_f64(&vec![value; impl_.numel() as usize]);
+        }
+    }
+}
+
+fn named(tensors: &[(&str, &Option<Tensor>)]) -> Vec<(String, Tensor)> {
+    tensors
+        .iter()
+        .filter_map(|(name, tensor)| tensor.as_ref().map(|tensor| (name.to_string(), Clone::clone(tensor))))
+        .collect()
+}
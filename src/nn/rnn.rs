@@ -0,0 +1,766 @@
+use crate::functions::recurrent::{index_tensor, split_steps};
+use crate::functions::{dropout, gru_cell, lstm_cell, rnn_relu_cell, rnn_tanh_cell, PackedSequence};
+use crate::nn::Module;
+use crate::tensor::{Generator, Options, Tensor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RnnNonlinearity {
+    Tanh,
+    ReLU,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecurrentConfig {
+    pub num_layers: i64,
+    pub nonlinearity: RnnNonlinearity,
+    pub bias: bool,
+    pub batch_first: bool,
+    pub dropout: f32,
+    pub bidirectional: bool,
+}
+
+impl RecurrentConfig {
+    pub fn new() -> Self {
+        Self {
+            num_layers: 1,
+            nonlinearity: RnnNonlinearity::Tanh,
+            bias: true,
+            batch_first: false,
+            dropout: 0.0,
+            bidirectional: false,
+        }
+    }
+
+    pub fn num_layers(mut self, num_layers: i64) -> Self {
+        self.num_layers = num_layers;
+        self
+    }
+
+    pub fn nonlinearity(mut self, nonlinearity: RnnNonlinearity) -> Self {
+        self.nonlinearity = nonlinearity;
+        self
+    }
+
+    pub fn bias(mut self, bias: bool) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    pub fn batch_first(mut self, batch_first: bool) -> Self {
+        self.batch_first = batch_first;
+        self
+    }
+
+    pub fn dropout(mut self, dropout: f32) -> Self {
+        self.dropout = dropout;
+        self
+    }
+
+    pub fn bidirectional(mut self, bidirectional: bool) -> Self {
+        self.bidirectional = bidirectional;
+        self
+    }
+
+    fn num_directions(&self) -> i64 {
+        if self.bidirectional {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl Default for RecurrentConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct RNNCell {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub nonlinearity: RnnNonlinearity,
+    pub weight_ih: Tensor,
+    pub weight_hh: Tensor,
+    pub bias_ih: Option<Tensor>,
+    pub bias_hh: Option<Tensor>,
+    training: bool,
+}
+
+impl RNNCell {
+    pub fn new(input_size: i64, hidden_size: i64, bias: bool, nonlinearity: RnnNonlinearity) -> Self {
+        Self::with_generator(input_size, hidden_size, bias, nonlinearity, None)
+    }
+
+    pub fn with_generator(
+        input_size: i64,
+        hidden_size: i64,
+        bias: bool,
+        nonlinearity: RnnNonlinearity,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        let (weight_ih, weight_hh, bias_ih, bias_hh) = cell_parameters(input_size, hidden_size, 1, bias, generator);
+        Self {
+            input_size,
+            hidden_size,
+            nonlinearity,
+            weight_ih,
+            weight_hh,
+            bias_ih,
+            bias_hh,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<&Tensor>) -> Tensor {
+        let hx = match initial_state(input, hx, self.input_size, self.hidden_size) {
+            Some(hx) => hx,
+            None => return Tensor::new(),
+        };
+        let cell = match self.nonlinearity {
+            RnnNonlinearity::Tanh => rnn_tanh_cell,
+            RnnNonlinearity::ReLU => rnn_relu_cell,
+        };
+        cell(input, &hx, &self.weight_ih, &self.weight_hh, self.bias_ih.as_ref(), self.bias_hh.as_ref())
+    }
+}
+
+impl Module for RNNCell {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named_parameters(&self.weight_ih, &self.weight_hh, &self.bias_ih, &self.bias_hh)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct LSTMCell {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub weight_ih: Tensor,
+    pub weight_hh: Tensor,
+    pub bias_ih: Option<Tensor>,
+    pub bias_hh: Option<Tensor>,
+    training: bool,
+}
+
+impl LSTMCell {
+    pub fn new(input_size: i64, hidden_size: i64, bias: bool) -> Self {
+        Self::with_generator(input_size, hidden_size, bias, None)
+    }
+
+    pub fn with_generator(input_size: i64, hidden_size: i64, bias: bool, generator: Option<&mut Generator>) -> Self {
+        let (weight_ih, weight_hh, bias_ih, bias_hh) = cell_parameters(input_size, hidden_size, 4, bias, generator);
+        Self {
+            input_size,
+            hidden_size,
+            weight_ih,
+            weight_hh,
+            bias_ih,
+            bias_hh,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<(&Tensor, &Tensor)>) -> (Tensor, Tensor) {
+        let state = (
+            initial_state(input, hx.map(|hx| hx.0), self.input_size, self.hidden_size),
+            initial_state(input, hx.map(|hx| hx.1), self.input_size, self.hidden_size),
+        );
+        match state {
+            (Some(h), Some(c)) => lstm_cell(
+                input,
+                (&h, &c),
+                &self.weight_ih,
+                &self.weight_hh,
+                self.bias_ih.as_ref(),
+                self.bias_hh.as_ref(),
+            ),
+            _ => (Tensor::new(), Tensor::new()),
+        }
+    }
+}
+
+impl Module for LSTMCell {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None).0
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named_parameters(&self.weight_ih, &self.weight_hh, &self.bias_ih, &self.bias_hh)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct GRUCell {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub weight_ih: Tensor,
+    pub weight_hh: Tensor,
+    pub bias_ih: Option<Tensor>,
+    pub bias_hh: Option<Tensor>,
+    training: bool,
+}
+
+impl GRUCell {
+    pub fn new(input_size: i64, hidden_size: i64, bias: bool) -> Self {
+        Self::with_generator(input_size, hidden_size, bias, None)
+    }
+
+    pub fn with_generator(input_size: i64, hidden_size: i64, bias: bool, generator: Option<&mut Generator>) -> Self {
+        let (weight_ih, weight_hh, bias_ih, bias_hh) = cell_parameters(input_size, hidden_size, 3, bias, generator);
+        Self {
+            input_size,
+            hidden_size,
+            weight_ih,
+            weight_hh,
+            bias_ih,
+            bias_hh,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<&Tensor>) -> Tensor {
+        match initial_state(input, hx, self.input_size, self.hidden_size) {
+            Some(hx) => gru_cell(input, &hx, &self.weight_ih, &self.weight_hh, self.bias_ih.as_ref(), self.bias_hh.as_ref()),
+            None => Tensor::new(),
+        }
+    }
+}
+
+impl Module for GRUCell {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None)
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        named_parameters(&self.weight_ih, &self.weight_hh, &self.bias_ih, &self.bias_hh)
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct RNN {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub config: RecurrentConfig,
+    cells: Vec<RNNCell>,
+    training: bool,
+}
+
+impl RNN {
+    pub fn new(input_size: i64, hidden_size: i64) -> Self {
+        Self::with_config(input_size, hidden_size, RecurrentConfig::new())
+    }
+
+    pub fn with_config(input_size: i64, hidden_size: i64, config: RecurrentConfig) -> Self {
+        Self::with_generator(input_size, hidden_size, config, None)
+    }
+
+    pub fn with_generator(
+        input_size: i64,
+        hidden_size: i64,
+        config: RecurrentConfig,
+        mut generator: Option<&mut Generator>,
+    ) -> Self {
+        let cells = layer_input_sizes(input_size, hidden_size, &config)
+            .map(|size| {
+                RNNCell::with_generator(size, hidden_size, config.bias, config.nonlinearity, generator.as_deref_mut())
+            })
+            .collect();
+        Self {
+            input_size,
+            hidden_size,
+            config,
+            cells,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<&Tensor>) -> (Tensor, Tensor) {
+        let (output, mut state) = self.stack().forward_padded(input, hx.map(|hx| vec![Clone::clone(hx)]));
+        (output, state.swap_remove(0))
+    }
+
+    pub fn forward_packed(&self, input: &PackedSequence, hx: Option<&Tensor>) -> (PackedSequence, Tensor) {
+        let (output, mut state) = self.stack().forward_packed(input, hx.map(|hx| vec![Clone::clone(hx)]));
+        (output, state.swap_remove(0))
+    }
+
+    fn stack(&self) -> Stack<'_, RNNCell> {
+        Stack {
+            cells: &self.cells,
+            input_size: self.input_size,
+            hidden_size: self.hidden_size,
+            config: &self.config,
+            training: self.training,
+            state_size: 1,
+        }
+    }
+}
+
+impl Module for RNN {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None).0
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        self.stack().named_parameters()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct LSTM {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub config: RecurrentConfig,
+    cells: Vec<LSTMCell>,
+    training: bool,
+}
+
+impl LSTM {
+    pub fn new(input_size: i64, hidden_size: i64) -> Self {
+        Self::with_config(input_size, hidden_size, RecurrentConfig::new())
+    }
+
+    pub fn with_config(input_size: i64, hidden_size: i64, config: RecurrentConfig) -> Self {
+        Self::with_generator(input_size, hidden_size, config, None)
+    }
+
+    pub fn with_generator(
+        input_size: i64,
+        hidden_size: i64,
+        config: RecurrentConfig,
+        mut generator: Option<&mut Generator>,
+    ) -> Self {
+        let cells = layer_input_sizes(input_size, hidden_size, &config)
+            .map(|size| LSTMCell::with_generator(size, hidden_size, config.bias, generator.as_deref_mut()))
+            .collect();
+        Self {
+            input_size,
+            hidden_size,
+            config,
+            cells,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<(&Tensor, &Tensor)>) -> (Tensor, (Tensor, Tensor)) {
+        let hx = hx.map(|(h, c)| vec![Clone::clone(h), Clone::clone(c)]);
+        let (output, state) = self.stack().forward_padded(input, hx);
+        (output, into_pair(state))
+    }
+
+    pub fn forward_packed(
+        &self,
+        input: &PackedSequence,
+        hx: Option<(&Tensor, &Tensor)>,
+    ) -> (PackedSequence, (Tensor, Tensor)) {
+        let hx = hx.map(|(h, c)| vec![Clone::clone(h), Clone::clone(c)]);
+        let (output, state) = self.stack().forward_packed(input, hx);
+        (output, into_pair(state))
+    }
+
+    fn stack(&self) -> Stack<'_, LSTMCell> {
+        Stack {
+            cells: &self.cells,
+            input_size: self.input_size,
+            hidden_size: self.hidden_size,
+            config: &self.config,
+            training: self.training,
+            state_size: 2,
+        }
+    }
+}
+
+impl Module for LSTM {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None).0
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        self.stack().named_parameters()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+pub struct GRU {
+    pub input_size: i64,
+    pub hidden_size: i64,
+    pub config: RecurrentConfig,
+    cells: Vec<GRUCell>,
+    training: bool,
+}
+
+impl GRU {
+    pub fn new(input_size: i64, hidden_size: i64) -> Self {
+        Self::with_config(input_size, hidden_size, RecurrentConfig::new())
+    }
+
+    pub fn with_config(input_size: i64, hidden_size: i64, config: RecurrentConfig) -> Self {
+        Self::with_generator(input_size, hidden_size, config, None)
+    }
+
+    pub fn with_generator(
+        input_size: i64,
+        hidden_size: i64,
+        config: RecurrentConfig,
+        mut generator: Option<&mut Generator>,
+    ) -> Self {
+        let cells = layer_input_sizes(input_size, hidden_size, &config)
+            .map(|size| GRUCell::with_generator(size, hidden_size, config.bias, generator.as_deref_mut()))
+            .collect();
+        Self {
+            input_size,
+            hidden_size,
+            config,
+            cells,
+            training: true,
+        }
+    }
+
+    pub fn forward_with_state(&self, input: &Tensor, hx: Option<&Tensor>) -> (Tensor, Tensor) {
+        let (output, mut state) = self.stack().forward_padded(input, hx.map(|hx| vec![Clone::clone(hx)]));
+        (output, state.swap_remove(0))
+    }
+
+    pub fn forward_packed(&self, input: &PackedSequence, hx: Option<&Tensor>) -> (PackedSequence, Tensor) {
+        let (output, mut state) = self.stack().forward_packed(input, hx.map(|hx| vec![Clone::clone(hx)]));
+        (output, state.swap_remove(0))
+    }
+
+    fn stack(&self) -> Stack<'_, GRUCell> {
+        Stack {
+            cells: &self.cells,
+            input_size: self.input_size,
+            hidden_size: self.hidden_size,
+            config: &self.config,
+            training: self.training,
+            state_size: 1,
+        }
+    }
+}
+
+impl Module for GRU {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        self.forward_with_state(input, None).0
+    }
+
+    fn local_parameters(&self) -> Vec<(String, Tensor)> {
+        self.stack().named_parameters()
+    }
+
+    fn is_training(&self) -> bool {
+        self.training
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+}
+
+trait Recurrent: Module {
+    fn step(&self, input: &Tensor, state: &[Tensor]) -> Vec<Tensor>;
+}
+
+impl Recurrent for RNNCell {
+    fn step(&self, input: &Tensor, state: &[Tensor]) -> Vec<Tensor> {
+        vec![self.forward_with_state(input, Some(&state[0]))]
+    }
+}
+
+impl Recurrent for LSTMCell {
+    fn step(&self, input: &Tensor, state: &[Tensor]) -> Vec<Tensor> {
+        let (h, c) = self.forward_with_state(input, Some((&state[0], &state[1])));
+        vec![h, c]
+    }
+}
+
+impl Recurrent for GRUCell {
+    fn step(&self, input: &Tensor, state: &[Tensor]) -> Vec<Tensor> {
+        vec![self.forward_with_state(input, Some(&state[0]))]
+    }
+}
+
+struct Stack<'a, C: Recurrent> {
+    cells: &'a [C],
+    input_size: i64,
+    hidden_size: i64,
+    config: &'a RecurrentConfig,
+    training: bool,
+    state_size: usize,
+}
+
+impl<C: Recurrent> Stack<'_, C> {
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        let directions = self.config.num_directions() as usize;
+        let mut parameters = Vec::new();
+        for (index, cell) in self.cells.iter().enumerate() {
+            let reverse = if index % directions == 1 { "_reverse" } else { "" };
+            let suffix = format!("_l{}{}", index / directions, reverse);
+            for (name, parameter) in cell.local_parameters() {
+                parameters.push((format!("{}{}", name, suffix), parameter));
+            }
+        }
+        parameters
+    }
+
+    fn forward_padded(&self, input: &Tensor, hx: Option<Vec<Tensor>>) -> (Tensor, Vec<Tensor>) {
+        let failed = || (Tensor::new(), vec![Tensor::new(); self.state_size]);
+        if self.cells.is_empty() || !input.defined() || !(2..=3).contains(&input.dim()) {
+            return failed();
+        }
+        let batched = input.dim() == 3;
+        let input = match (batched, self.config.batch_first) {
+            (false, _) => input.unsqueeze(1),
+            (true, true) => input.transpose(0, 1),
+            (true, false) => Clone::clone(input),
+        };
+        let shape = input.shape();
+        if shape[0] == 0 || shape[2] != self.input_size {
+            return failed();
+        }
+
+        let hx = hx.map(|hx| if batched { hx } else { hx.iter().map(|h| h.unsqueeze(1)).collect() });
+        let initial = match self.initial_states(hx, shape[1]) {
+            Some(initial) => initial,
+            None => return failed(),
+        };
+        let steps = (0..shape[0]).map(|t| input.narrow(0, t, 1).squeeze(Some(0))).collect();
+        let (outputs, state) = self.run(steps, &vec![shape[1]; shape[0] as usize], initial);
+
+        let output = Tensor::stack(&outputs, 0);
+        if !batched {
+            return (output.squeeze(Some(1)), state.iter().map(|h| h.squeeze(Some(1))).collect());
+        }
+        let output = if self.config.batch_first { output.transpose(0, 1) } else { output };
+        (output, state)
+    }
+
+    fn forward_packed(&self, input: &PackedSequence, hx: Option<Vec<Tensor>>) -> (PackedSequence, Vec<Tensor>) {
+        let failed = || {
+            let output = PackedSequence {
+                data: Tensor::new(),
+                batch_sizes: Vec::new(),
+                sorted_indices: None,
+                unsorted_indices: None,
+            };
+            (output, vec![Tensor::new(); self.state_size])
+        };
+        let data = &input.data;
+        if self.cells.is_empty() || !data.defined() || data.dim() != 2 || data.shape()[1] != self.input_size || input.batch_sizes.is_empty() {
+            return failed();
+        }
+
+        let hx = match (hx, &input.sorted_indices) {
+            (Some(hx), Some(sorted)) => Some(hx.iter().map(|h| h.index_select(1, &index_tensor(sorted))).collect()),
+            (hx, _) => hx,
+        };
+        let initial = match self.initial_states(hx, input.batch_sizes[0]) {
+            Some(initial) => initial,
+            None => return failed(),
+        };
+        let (outputs, state) = self.run(split_steps(data, &input.batch_sizes), &input.batch_sizes, initial);
+
+        let state = match &input.unsorted_indices {
+            Some(unsorted) => state.iter().map(|h| h.index_select(1, &index_tensor(unsorted))).collect(),
+            None => state,
+        };
+        let output = PackedSequence {
+            data: Tensor::cat(&outputs, 0),
+            batch_sizes: input.batch_sizes.clone(),
+            sorted_indices: input.sorted_indices.clone(),
+            unsorted_indices: input.unsorted_indices.clone(),
+        };
+        (output, state)
+    }
+
+    fn initial_states(&self, hx: Option<Vec<Tensor>>, batch_size: i64) -> Option<Vec<Tensor>> {
+        let shape = [self.cells.len() as i64, batch_size, self.hidden_size];
+        match hx {
+            Some(hx) if hx.iter().all(|h| h.defined() && h.shape() == shape) => Some(hx),
+            Some(_) => None,
+            None => Some(vec![Tensor::zeros(&shape); self.state_size]),
+        }
+    }
+
+    fn run(&self, mut steps: Vec<Tensor>, batch_sizes: &[i64], initial: Vec<Tensor>) -> (Vec<Tensor>, Vec<Tensor>) {
+        let directions = self.config.num_directions() as usize;
+        let mut finals: Vec<Vec<Tensor>> = vec![Vec::new(); self.state_size];
+        let layers = self.cells.chunks(directions).enumerate();
+        for (layer, cells) in layers {
+            if layer > 0 && self.training && self.config.dropout > 0.0 {
+                steps = steps.iter().map(|step| dropout(step, self.config.dropout, true)).collect();
+            }
+
+            let mut outputs: Vec<Vec<Tensor>> = Vec::with_capacity(directions);
+            for (direction, cell) in cells.iter().enumerate() {
+                let index = (layer * directions + direction) as i64;
+                let h0: Vec<Tensor> = initial.iter().map(|h| h.narrow(0, index, 1).squeeze(Some(0))).collect();
+                let (output, state) = if direction == 0 {
+                    run_forward(cell, &steps, batch_sizes, h0)
+                } else {
+                    run_reverse(cell, &steps, batch_sizes, h0)
+                };
+                outputs.push(output);
+                finals.iter_mut().zip(state).for_each(|(finals, h)| finals.push(h));
+            }
+
+            steps = (0..steps.len())
+                .map(|t| match outputs.as_slice() {
+                    [forward] => Clone::clone(&forward[t]),
+                    directions => Tensor::cat(&directions.iter().map(|output| Clone::clone(&output[t])).collect::<Vec<_>>(), 1),
+                })
+                .collect();
+        }
+        (steps, finals.iter().map(|finals| Tensor::stack(finals, 0)).collect())
+    }
+}
+
+fn run_forward<C: Recurrent>(cell: &C, steps: &[Tensor], batch_sizes: &[i64], h0: Vec<Tensor>) -> (Vec<Tensor>, Vec<Tensor>) {
+    let mut state = h0;
+    let mut finished: Vec<Vec<Tensor>> = vec![Vec::new(); state.len()];
+    let mut outputs = Vec::with_capacity(steps.len());
+    for (step, &batch_size) in steps.iter().zip(batch_sizes.iter()) {
+        let rows = state[0].shape()[0];
+        if batch_size < rows {
+            for (h, finished) in state.iter_mut().zip(finished.iter_mut()) {
+                finished.push(h.narrow(0, batch_size, rows - batch_size));
+                *h = h.narrow(0, 0, batch_size);
+            }
+        }
+        state = cell.step(step, &state);
+        outputs.push(Clone::clone(&state[0]));
+    }
+
+    let state = state
+        .into_iter()
+        .zip(finished)
+        .map(|(h, finished)| {
+            if finished.is_empty() {
+                h
+            } else {
+                Tensor::cat(&std::iter::once(h).chain(finished.into_iter().rev()).collect::<Vec<_>>(), 0)
+            }
+        })
+        .collect();
+    (outputs, state)
+}
+
+fn run_reverse<C: Recurrent>(cell: &C, steps: &[Tensor], batch_sizes: &[i64], h0: Vec<Tensor>) -> (Vec<Tensor>, Vec<Tensor>) {
+    let last = batch_sizes[batch_sizes.len() - 1];
+    let mut state: Vec<Tensor> = h0.iter().map(|h| h.narrow(0, 0, last)).collect();
+    let mut outputs = vec![Tensor::new(); steps.len()];
+    for t in (0..steps.len()).rev() {
+        let rows = state[0].shape()[0];
+        if batch_sizes[t] > rows {
+            for (h, initial) in state.iter_mut().zip(h0.iter()) {
+                *h = Tensor::cat(&[Clone::clone(h), initial.narrow(0, rows, batch_sizes[t] - rows)], 0);
+            }
+        }
+        state = cell.step(&steps[t], &state);
+        outputs[t] = Clone::clone(&state[0]);
+    }
+    (outputs, state)
+}
+
+fn layer_input_sizes(input_size: i64, hidden_size: i64, config: &RecurrentConfig) -> impl Iterator<Item = i64> {
+    let directions = config.num_directions();
+    (0..config.num_layers.max(0) * directions)
+        .map(move |index| if index < directions { input_size } else { hidden_size * directions })
+}
+
+fn initial_state(input: &Tensor, hx: Option<&Tensor>, input_size: i64, hidden_size: i64) -> Option<Tensor> {
+    if !input.defined() || !(1..=2).contains(&input.dim()) || input.shape()[input.dim() as usize - 1] != input_size {
+        return None;
+    }
+    let mut shape = input.shape();
+    let last = shape.len() - 1;
+    shape[last] = hidden_size;
+    match hx {
+        Some(hx) if hx.defined() && hx.shape() == shape => Some(Clone::clone(hx)),
+        Some(_) => None,
+        None => Some(Tensor::zeros(&shape)),
+    }
+}
+
+fn cell_parameters(
+    input_size: i64,
+    hidden_size: i64,
+    gates: i64,
+    bias: bool,
+    mut generator: Option<&mut Generator>,
+) -> (Tensor, Tensor, Option<Tensor>, Option<Tensor>) {
+    let bound = if hidden_size > 0 { 1.0 / (hidden_size as f64).sqrt() } else { 0.0 };
+    let mut parameter = |shape: &[i64]| {
+        Tensor::uniform(-bound, bound, shape, Options::default().requires_grad(true), generator.as_deref_mut())
+    };
+    let weight_ih = parameter(&[gates * hidden_size, input_size]);
+    let weight_hh = parameter(&[gates * hidden_size, hidden_size]);
+    let (bias_ih, bias_hh) = if bias {
+        (Some(parameter(&[gates * hidden_size])), Some(parameter(&[gates * hidden_size])))
+    } else {
+        (None, None)
+    };
+    (weight_ih, weight_hh, bias_ih, bias_hh)
+}
+
+fn named_parameters(
+    weight_ih: &Tensor,
+    weight_hh: &Tensor,
+    bias_ih: &Option<Tensor>,
+    bias_hh: &Option<Tensor>,
+) -> Vec<(String, Tensor)> {
+    let mut parameters = vec![
+        ("weight_ih".to_string(), Clone::clone(weight_ih)),
+        ("weight_hh".to_string(), Clone::clone(weight_hh)),
+    ];
+    for (name, bias) in [("bias_ih", bias_ih), ("bias_hh", bias_hh)] {
+        if let Some(bias) = bias {
+            parameters.push((name.to_string(), Clone::clone(bias)));
+        }
+    }
+    parameters
+}
+
+fn into_pair(mut state: Vec<Tensor>) -> (Tensor, Tensor) {
+    let c = state.pop().unwrap_or_default();
+    let h = state.pop().unwrap_or_default();
+    (h, c)
+}
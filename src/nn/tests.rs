@@ -0,0 +1,633 @@
+#![allow(unused_imports, unused_variables, dead_code, clippy::module_inception, clippy::excessive_precision)]
+
+use super::*;
+use crate::optimizers::{Optimizer, SGD};
+use crate::functions::{pack_sequence, pad_packed_sequence, Padding, PackedSequence};
+use crate::serialization::ModelState;
+use crate::tensor::{Generator, Options, Tensor};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scale {
+        weight: Tensor,
+        running: Tensor,
+        training: bool,
+    }
+
+    impl Scale {
+        fn new(value: f32) -> Self {
+            Self {
+                weight: Tensor::full_with_options(&[2], value as f64, Options::default().requires_grad(true)),
+                running: Tensor::zeros(&[2]),
+                training: true,
+            }
+        }
+    }
+
+    impl Module for Scale {
+        fn forward(&self, input: &Tensor) -> Tensor {
+            input * &self.weight
+        }
+
+        fn local_parameters(&self) -> Vec<(String, Tensor)> {
+            vec![("weight".to_string(), Clone::clone(&self.weight))]
+        }
+
+        fn local_buffers(&self) -> Vec<(String, Tensor)> {
+            vec![("running".to_string(), Clone::clone(&self.running))]
+        }
+
+        fn is_training(&self) -> bool {
+            self.training
+        }
+
+        fn set_training(&mut self, training: bool) {
+            self.training = training;
+        }
+    }
+
+    struct Net {
+        first: Scale,
+        second: Scale,
+        bias: Tensor,
+        training: bool,
+    }
+
+    impl Module for Net {
+        fn forward(&self, input: &Tensor) -> Tensor {
+            &self.second.forward(&self.first.forward(input)) + &self.bias
+        }
+
+        fn local_parameters(&self) -> Vec<(String, Tensor)> {
+            vec![("bias".to_string(), Clone::clone(&self.bias))]
+        }
+
+        fn named_children(&self) -> Vec<(String, &dyn Module)> {
+            vec![("first".to_string(), &self.first as &dyn Module), ("second".to_string(), &self.second)]
+        }
+
+        fn named_children_mut(&mut self) -> Vec<(String, &mut dyn Module)> {
+            vec![("first".to_string(), &mut self.first as &mut dyn Module), ("second".to_string(), &mut self.second)]
+        }
+
+        fn is_training(&self) -> bool {
+            self.training
+        }
+
+        fn set_training(&mut self, training: bool) {
+            self.training = training;
+        }
+    }
+
+    fn net() -> Net {
+        Net {
+            first: Scale::new(2.0),
+            second: Scale::new(3.0),
+            bias: Tensor::zeros_with_options(&[2], Options::default().requires_grad(true)),
+            training: true,
+        }
+    }
+
+    #[test]
+    fn test_named_parameters_and_buffers() {
+        let model = net();
+        let names: Vec<String> = model.named_parameters().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["bias", "first.weight", "second.weight"]);
+        assert_eq!(model.parameters().len(), 3);
+
+        let buffers: Vec<String> = model.named_buffers().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(buffers, vec!["first.running", "second.running"]);
+        assert_eq!(model.buffers().len(), 2);
+        assert_eq!(model.children().len(), 2);
+        assert!(model.children()[0].named_children().is_empty());
+    }
+
+    #[test]
+    fn test_train_eval_propagates() {
+        let mut model = net();
+        model.eval();
+        assert!(!model.is_training());
+        assert!(model.children().iter().all(|child| !child.is_training()));
+        model.train();
+        assert!(model.is_training() && model.second.is_training());
+    }
+
+    #[test]
+    fn test_parameters_feed_optimizer() {
+        let mut model = net();
+        let input = Tensor::from_slice(&[1.0f32, -1.0], &[2]);
+        let mut optimizer = SGD::with_lr(model.parameters(), 0.1);
+
+        model.forward(&input).sum().backward();
+        assert_eq!(model.first.weight.grad().to_list::<f32>(), vec![3.0, -3.0]);
+        optimizer.step();
+        assert_eq!(model.first.weight.to_list::<f32>(), vec![1.7, 2.3]);
+        assert_eq!(model.bias.to_list::<f32>(), vec![-0.1, -0.1]);
+
+        model.zero_grad();
+        let grads = model.parameters().iter().map(|p| p.grad().to_list::<f32>()).collect::<Vec<_>>();
+        assert!(grads.iter().all(|grad| grad.iter().all(|&g| g == 0.0)));
+    }
+
+    #[test]
+    fn test_state_dict_round_trip() {
+        let source = net();
+        source.first.running.impl_.as_ref().unwrap().write_f64(&[5.0, 6.0]).unwrap();
+        let state = source.state_dict();
+        let mut names: Vec<&String> = state.parameter_names();
+        names.sort();
+        assert_eq!(names, vec!["bias", "first.running", "first.weight", "second.running", "second.weight"]);
+
+        let path = std::env::temp_dir().join("rusted_torch_state_dict.bin");
+        state.save_to_file(&path).unwrap();
+        let loaded = ModelState::load_from_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let mut target = Net {
+            first: Scale::new(0.0),
+            second: Scale::new(0.0),
+            bias: Tensor::ones(&[2]),
+            training: true,
+        };
+        let held = Clone::clone(&target.first.weight);
+        let keys = target.load_state_dict(&loaded, true).unwrap();
+        assert!(keys.is_empty());
+        assert_eq!(held.to_list::<f32>(), vec![2.0, 2.0]);
+        assert_eq!(target.second.weight.to_list::<f32>(), vec![3.0, 3.0]);
+        assert_eq!(target.first.running.to_list::<f32>(), vec![5.0, 6.0]);
+        assert_eq!(target.bias.to_list::<f32>(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_load_state_dict_reports_incompatible_keys() {
+        let mut state = net().state_dict();
+        state.remove_parameter("second.weight");
+        state.add_parameter("extra".to_string(), Tensor::ones(&[1]));
+
+        let mut model = Net {
+            first: Scale::new(0.0),
+            second: Scale::new(7.0),
+            bias: Tensor::ones(&[2]),
+            training: true,
+        };
+        let error = model.load_state_dict(&state, true).unwrap_err();
+        assert!(error.contains("Missing key(s) in state_dict: second.weight"));
+        assert!(error.contains("Unexpected key(s) in state_dict: extra"));
+        assert_eq!(model.first.weight.to_list::<f32>(), vec![0.0, 0.0]);
+
+        let keys = model.load_state_dict(&state, false).unwrap();
+        assert_eq!(keys.missing_keys, vec!["second.weight"]);
+        assert_eq!(keys.unexpected_keys, vec!["extra"]);
+        assert_eq!(model.first.weight.to_list::<f32>(), vec![2.0, 2.0]);
+        assert_eq!(model.second.weight.to_list::<f32>(), vec![7.0, 7.0]);
+
+        state.add_parameter("bias".to_string(), Tensor::ones(&[3]));
+        let error = model.load_state_dict(&state, false).unwrap_err();
+        assert!(error.contains("size mismatch for bias"));
+        assert!(error.contains("[3]") && error.contains("[2]"));
+    }
+
+    #[test]
+    fn test_linear_module() {
+        let mut layer = Linear::with_generator(4, 3, true, Some(&mut Generator::with_seed(7)));
+        let again = Linear::with_generator(4, 3, true, Some(&mut Generator::with_seed(7)));
+        assert_eq!(layer.weight.shape(), vec![3, 4]);
+        assert_eq!(layer.weight.to_list::<f32>(), again.weight.to_list::<f32>());
+        assert!(layer.weight.to_list::<f32>().iter().all(|w| w.abs() <= 0.5));
+        assert!(layer.bias.as_ref().unwrap().to_list::<f32>().iter().all(|b| b.abs() <= 0.5));
+        assert!(layer.weight.requires_grad());
+
+        let names: Vec<String> = layer.named_parameters().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["weight", "bias"]);
+        assert_eq!(Linear::new(4, 3, false).parameters().len(), 1);
+
+        let empty_batch = Linear::new(4, 3, true).forward(&Tensor::zeros(&[0, 4]));
+        assert!(empty_batch.defined());
+        assert_eq!(empty_batch.shape(), vec![0, 3]);
+
+        let output = layer.forward(&Tensor::ones(&[2, 5, 4]));
+        assert_eq!(output.shape(), vec![2, 5, 3]);
+        output.sum().backward();
+        assert_eq!(layer.weight.grad().to_list::<f32>(), vec![10.0; 12]);
+
+        layer.eval();
+        assert!(!layer.is_training());
+    }
+
+    #[test]
+    fn test_bilinear_identity_and_lazy_linear() {
+        let bilinear = Bilinear::with_generator(2, 3, 4, true, Some(&mut Generator::with_seed(1)));
+        let input1 = Tensor::from_vec(vec![1.0f32, -2.0], &[1, 2]);
+        let input2 = Tensor::from_vec(vec![0.5f32, 3.0, -1.0], &[1, 3]);
+        let pair = bilinear.forward_pair(&input1, &input2);
+        assert_eq!(pair.shape(), vec![1, 4]);
+        let joined = bilinear.forward(&Tensor::cat(&[input1, input2], 1));
+        assert_eq!(joined.to_list::<f32>(), pair.to_list::<f32>());
+
+        let input = Tensor::from_vec(vec![1.0f32, 2.0, 3.0], &[3]);
+        assert_eq!(Identity::new().forward(&input).to_list::<f32>(), vec![1.0, 2.0, 3.0]);
+
+        let lazy = LazyLinear::new(3, true);
+        assert!(lazy.parameters().is_empty());
+        assert_eq!(lazy.in_features(), None);
+        let output = lazy.forward(&Tensor::ones(&[2, 5]));
+        assert_eq!(output.shape(), vec![2, 3]);
+        assert_eq!(lazy.in_features(), Some(5));
+        let names: Vec<String> = lazy.named_parameters().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["weight", "bias"]);
+        assert_eq!(lazy.parameters()[0].shape(), vec![3, 5]);
+        assert_eq!(lazy.forward(&Tensor::ones(&[1, 5])).shape(), vec![1, 3]);
+    }
+
+    fn names(module: &dyn Module) -> Vec<String> {
+        module.named_parameters().into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut generator = Generator::with_seed(3);
+        let mut model = Sequential::new()
+            .with_module(Linear::with_generator(3, 4, true, Some(&mut generator)))
+            .with_module(Identity::new());
+        model.append(Linear::with_generator(4, 2, false, Some(&mut generator)));
+        assert_eq!(model.len(), 3);
+        assert_eq!(names(&model), vec!["0.weight", "0.bias", "2.weight"]);
+
+        let input = Tensor::ones(&[5, 3]);
+        let expected = model[2].forward(&model[0].forward(&input));
+        let output = model.forward(&input);
+        assert_eq!(output.shape(), vec![5, 2]);
+        assert_eq!(output.to_list::<f32>(), expected.to_list::<f32>());
+        assert!(model.get(3).is_none());
+
+        model.eval();
+        assert!(!model.is_training());
+        assert!(model.children().iter().all(|child| !child.is_training()));
+        model[1].train();
+        assert!(model[1].is_training());
+    }
+
+    #[test]
+    fn test_module_list_dict_and_parameter_list() {
+        let mut layers = ModuleList::new();
+        layers.push(Linear::new(2, 2, true));
+        layers.push(Linear::new(2, 1, false));
+        assert!(!layers.forward(&Tensor::ones(&[2])).defined());
+        assert_eq!(layers.iter().count(), 2);
+
+        let mut extras = ParameterList::new();
+        extras.push(Tensor::zeros_with_options(&[3], Options::default().requires_grad(true)));
+        extras.push(Tensor::ones(&[1]));
+        assert_eq!(names(&extras), vec!["0", "1"]);
+        assert_eq!(extras[1].to_list::<f32>(), vec![1.0]);
+
+        let mut model = ModuleDict::new();
+        model.insert("encoder", layers);
+        model.insert("extras", extras);
+        model.insert("head", Identity::new());
+        assert!(model.insert("head", Linear::new(1, 1, true)).is_some());
+        assert_eq!(model.keys(), vec!["encoder", "extras", "head"]);
+        assert_eq!(
+            names(&model),
+            vec!["encoder.0.weight", "encoder.0.bias", "encoder.1.weight", "extras.0", "extras.1", "head.weight", "head.bias"]
+        );
+        assert_eq!(model["encoder"].children().len(), 2);
+
+        let state = model.state_dict();
+        let mut restored = ModuleDict::new();
+        let mut layers = ModuleList::new();
+        layers.push(Linear::new(2, 2, true));
+        layers.push(Linear::new(2, 1, false));
+        restored.insert("encoder", layers);
+        let keys = restored.load_state_dict(&state, false).unwrap();
+        assert_eq!(keys.unexpected_keys, vec!["extras.0", "extras.1", "head.bias", "head.weight"]);
+        assert_eq!(
+            restored.named_parameters()[0].1.to_list::<f32>(),
+            model.named_parameters()[0].1.to_list::<f32>()
+        );
+
+        assert!(model.remove("extras").is_some());
+        assert!(!model.contains_key("extras"));
+        assert_eq!(model.len(), 2);
+    }
+
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "Expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_init_fans_and_gain() {
+        let conv = Tensor::zeros(&[8, 4, 3, 3]);
+        assert_eq!(init::calculate_fan_in_and_fan_out(&conv).unwrap(), (36, 72));
+        assert_eq!(init::calculate_correct_fan(&conv, init::FanMode::FanOut).unwrap(), 72);
+        assert!(init::calculate_fan_in_and_fan_out(&Tensor::zeros(&[3])).is_err());
+
+        assert_eq!(init::calculate_gain(init::Nonlinearity::Conv2d), 1.0);
+        assert_eq!(init::calculate_gain(init::Nonlinearity::Tanh), 5.0 / 3.0);
+        assert_eq!(init::calculate_gain(init::Nonlinearity::ReLU), 2.0f64.sqrt());
+        assert_eq!(init::calculate_gain(init::Nonlinearity::SELU), 0.75);
+        assert_near(init::calculate_gain(init::Nonlinearity::LeakyReLU(5.0f64.sqrt())), (1.0f64 / 3.0).sqrt(), 1e-12);
+    }
+
+    #[test]
+    fn test_init_random_distributions() {
+        let mut weight = Tensor::zeros(&[200, 300]);
+        init::xavier_uniform_(&mut weight, 2.0, Some(&mut Generator::with_seed(0))).unwrap();
+        let bound = 2.0 * (6.0f64 / 500.0).sqrt();
+        let values = weight.to_list::<f32>();
+        assert!(values.iter().all(|&v| (v as f64).abs() <= bound));
+        assert!(values.iter().any(|&v| (v as f64).abs() > 0.9 * bound));
+
+        let mut again = Tensor::zeros(&[200, 300]);
+        init::xavier_uniform_(&mut again, 2.0, Some(&mut Generator::with_seed(0))).unwrap();
+        assert_eq!(again.to_list::<f32>(), values);
+
+        let std = |tensor: &Tensor| {
+            let values: Vec<f64> = tensor.to_list::<f32>().iter().map(|&v| v as f64).collect();
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+        };
+        init::xavier_normal_(&mut weight, 1.0, Some(&mut Generator::with_seed(1))).unwrap();
+        assert_near(std(&weight), (2.0f64 / 500.0).sqrt(), 0.002);
+        init::kaiming_normal_(&mut weight, init::FanMode::FanIn, init::Nonlinearity::ReLU, Some(&mut Generator::with_seed(2))).unwrap();
+        assert_near(std(&weight), (2.0f64 / 300.0).sqrt(), 0.003);
+        init::kaiming_uniform_(&mut weight, init::FanMode::FanOut, init::Nonlinearity::ReLU, Some(&mut Generator::with_seed(3))).unwrap();
+        assert!(weight.to_list::<f32>().iter().all(|&v| (v as f64).abs() <= (6.0f64 / 200.0).sqrt()));
+
+        init::trunc_normal_(&mut weight, 0.0, 1.0, -0.5, 0.25, Some(&mut Generator::with_seed(4))).unwrap();
+        assert!(weight.to_list::<f32>().iter().all(|&v| (-0.5..=0.25).contains(&v)));
+        assert!(init::trunc_normal_(&mut weight, 0.0, 1.0, 1.0, -1.0, None).is_err());
+
+        let mut sparse = Tensor::zeros(&[10, 4]);
+        init::sparse_(&mut sparse, 0.25, 1.0, Some(&mut Generator::with_seed(5))).unwrap();
+        let values = sparse.to_list::<f32>();
+        for c in 0..4 {
+            assert_eq!((0..10).filter(|r| values[r * 4 + c] == 0.0).count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_init_orthogonal() {
+        for shape in [[3, 5], [5, 3]] {
+            let mut weight = Tensor::zeros(&shape);
+            init::orthogonal_(&mut weight, 2.0, Some(&mut Generator::with_seed(9))).unwrap();
+            let w = weight.to_list::<f32>();
+            let (rows, cols) = (shape[0] as usize, shape[1] as usize);
+            let small = rows.min(cols);
+            for i in 0..small {
+                for j in 0..small {
+                    let dot: f32 = if rows <= cols {
+                        (0..cols).map(|k| w[i * cols + k] * w[j * cols + k]).sum()
+                    } else {
+                        (0..rows).map(|k| w[k * cols + i] * w[k * cols + j]).sum()
+                    };
+                    assert_near(dot as f64, if i == j { 4.0 } else { 0.0 }, 1e-4);
+                }
+            }
+        }
+        assert!(init::orthogonal_(&mut Tensor::zeros(&[4]), 1.0, None).is_err());
+    }
+
+    #[test]
+    fn test_init_deterministic_patterns() {
+        let layer = Linear::new(3, 2, true);
+        let mut weight = Clone::clone(&layer.weight);
+        init::constant_(&mut weight, 0.5).unwrap();
+        assert_eq!(layer.named_parameters()[0].1.to_list::<f32>(), vec![0.5; 6]);
+        init::zeros_(&mut weight).unwrap();
+        assert_eq!(layer.weight.to_list::<f32>(), vec![0.0; 6]);
+        init::ones_(&mut weight).unwrap();
+        assert_eq!(layer.weight.to_list::<f32>(), vec![1.0; 6]);
+        init::eye_(&mut weight).unwrap();
+        assert_eq!(layer.weight.to_list::<f32>(), vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        assert!(init::eye_(&mut Tensor::zeros(&[2, 2, 2])).is_err());
+
+        let mut kernel = Tensor::ones(&[4, 2, 3]);
+        init::dirac_(&mut kernel, 2).unwrap();
+        let values = kernel.to_list::<f32>();
+        let ones: Vec<usize> = (0..values.len()).filter(|&i| values[i] == 1.0).collect();
+        assert_eq!(ones, vec![1, 4 + 6, 12 + 1, 18 + 4]);
+        assert!(init::dirac_(&mut kernel, 3).is_err());
+    }
+
+    #[test]
+    fn test_conv_modules() {
+        let mut generator = Generator::with_seed(12);
+        let conv = Conv2d::with_generator(4, 8, ConvConfig::new([3, 3]).padding(Padding::Same).groups(2), Some(&mut generator));
+        assert_eq!(conv.weight.shape(), vec![8, 2, 3, 3]);
+        let bound = 1.0 / (18.0f32).sqrt();
+        assert!(conv.bias.as_ref().unwrap().to_list::<f32>().iter().all(|b| b.abs() <= bound));
+        let output = conv.forward(&Tensor::ones(&[2, 4, 5, 6]));
+        assert_eq!(output.shape(), vec![2, 8, 5, 6]);
+        output.sum().backward();
+        assert_eq!(conv.bias.as_ref().unwrap().grad().to_list::<f32>(), vec![60.0; 8]);
+        assert_eq!(conv.weight.grad().shape(), vec![8, 2, 3, 3]);
+
+        let conv1d = Conv1d::new(3, 5, ConvConfig::new([4]).stride([2]).bias(false));
+        assert_eq!(conv1d.parameters().len(), 1);
+        assert_eq!(conv1d.forward(&Tensor::ones(&[1, 3, 10])).shape(), vec![1, 5, 4]);
+        let conv3d = Conv3d::new(2, 2, ConvConfig::new([1, 3, 3]).padding([0, 1, 1]).dilation([1, 1, 1]));
+        assert_eq!(conv3d.forward(&Tensor::ones(&[1, 2, 3, 4, 4])).shape(), vec![1, 2, 3, 4, 4]);
+
+        let up = ConvTranspose2d::new(4, 6, ConvTransposeConfig::new([3, 3]).stride([2, 2]).padding([1, 1]).output_padding([1, 1]).groups(2));
+        assert_eq!(up.weight.shape(), vec![4, 3, 3, 3]);
+        assert_eq!(up.bias.as_ref().unwrap().shape(), vec![6]);
+        assert_eq!(up.forward(&Tensor::ones(&[1, 4, 5, 7])).shape(), vec![1, 6, 10, 14]);
+        let up1d = ConvTranspose1d::new(2, 2, ConvTransposeConfig::new([2]).stride([2]));
+        assert_eq!(up1d.forward(&Tensor::ones(&[1, 2, 3])).shape(), vec![1, 2, 6]);
+        let up3d = ConvTranspose3d::new(1, 1, ConvTransposeConfig::new([2, 2, 2]));
+        assert_eq!(names(&up3d), vec!["weight", "bias"]);
+        assert_eq!(up3d.forward(&Tensor::ones(&[1, 1, 2, 2, 2])).shape(), vec![1, 1, 3, 3, 3]);
+    }
+
+    fn buffer_names(module: &dyn Module) -> Vec<String> {
+        module.named_buffers().into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn test_batch_norm_module() {
+        let mut bn = BatchNorm1d::new(2);
+        assert_eq!(names(&bn), vec!["weight", "bias"]);
+        assert_eq!(buffer_names(&bn), vec!["running_mean", "running_var", "num_batches_tracked"]);
+
+        let x = Tensor::from_vec(vec![1.0f32, 10.0, 2.0, 20.0, 3.0, 30.0, 4.0, 40.0], &[4, 2]);
+        let y = bn.forward(&x);
+        assert_near(y.to_list::<f32>()[0] as f64, -1.5 / 1.25f64.sqrt(), 1e-4);
+        let running_mean = bn.running_mean.as_ref().unwrap();
+        assert_eq!(running_mean.to_list::<f32>(), vec![0.25, 2.5]);
+        assert_eq!(bn.num_batches_tracked.as_ref().unwrap().item::<i64>(), 1);
+
+        let single = Tensor::from_vec(vec![1.0f32, 10.0], &[1, 2]);
+        assert!(!bn.forward(&single).defined());
+        assert!(!bn.forward(&Tensor::ones(&[4, 3])).defined());
+        assert_eq!(bn.num_batches_tracked.as_ref().unwrap().item::<i64>(), 1);
+        assert_eq!(running_mean.to_list::<f32>(), vec![0.25, 2.5]);
+
+        bn.eval();
+        let y = bn.forward(&x);
+        assert_near(y.to_list::<f32>()[0] as f64, 0.75 / (0.9f64 + 0.5 / 3.0 + 1e-5).sqrt(), 1e-4);
+        assert_eq!(bn.num_batches_tracked.as_ref().unwrap().item::<i64>(), 1);
+
+        let state = bn.state_dict();
+        let mut restored = BatchNorm1d::new(2);
+        assert!(restored.load_state_dict(&state, true).unwrap().is_empty());
+        assert_eq!(restored.running_mean.as_ref().unwrap().to_list::<f32>(), vec![0.25, 2.5]);
+        assert_eq!(restored.num_batches_tracked.as_ref().unwrap().item::<i64>(), 1);
+        restored.reset_running_stats();
+        assert_eq!(restored.running_var.as_ref().unwrap().to_list::<f32>(), vec![1.0, 1.0]);
+
+        let cumulative = BatchNorm2d::with_config(1, NormConfig::new().momentum(None));
+        cumulative.forward(&Tensor::full(&[2, 1, 2, 2], 2.0));
+        cumulative.forward(&Tensor::full(&[2, 1, 2, 2], 4.0));
+        assert_eq!(cumulative.running_mean.as_ref().unwrap().to_list::<f32>(), vec![3.0]);
+        assert!(!cumulative.forward(&Tensor::zeros(&[2, 1, 2])).defined());
+
+        let mut untracked = BatchNorm3d::with_config(1, NormConfig::new().affine(false).track_running_stats(false));
+        assert!(untracked.parameters().is_empty() && untracked.buffers().is_empty());
+        untracked.eval();
+        let y = untracked.forward(&Tensor::from_vec(vec![1.0f32, 3.0], &[2, 1, 1, 1, 1]));
+        assert_near(y.to_list::<f32>()[1] as f64, 1.0, 1e-4);
+    }
+
+    #[test]
+    fn test_norm_modules() {
+        let mut generator = Generator::with_seed(49);
+        let x = Tensor::normal(0.0, 1.0, &[2, 4, 3], Options::default(), Some(&mut generator));
+
+        let layer = LayerNorm::new(&[4, 3]);
+        let y = layer.forward(&x);
+        let first: Vec<f32> = y.to_list::<f32>()[..12].to_vec();
+        assert_near(first.iter().sum::<f32>() as f64, 0.0, 1e-4);
+        assert_near(first.iter().map(|v| v * v).sum::<f32>() as f64 / 12.0, 1.0, 1e-3);
+        y.sum().backward();
+        assert_eq!(layer.bias.as_ref().unwrap().grad().to_list::<f32>(), vec![2.0; 12]);
+        assert_eq!(names(&LayerNorm::with_config(&[3], 1e-5, true, false)), vec!["weight"]);
+
+        let group = GroupNorm::new(2, 4);
+        assert_eq!(group.forward(&x).shape(), vec![2, 4, 3]);
+        assert!(!GroupNorm::new(3, 4).forward(&x).defined());
+
+        let instance = InstanceNorm1d::new(4);
+        assert!(instance.parameters().is_empty() && instance.buffers().is_empty());
+        let unbatched = x.narrow(0, 0, 1).squeeze(Some(0));
+        assert_eq!(instance.forward(&unbatched).to_list::<f32>(), instance.forward(&x).to_list::<f32>()[..12].to_vec());
+
+        let tracked = InstanceNorm1d::with_config(4, NormConfig::new().affine(false));
+        tracked.forward(&x);
+        assert!(tracked.running_mean.as_ref().unwrap().to_list::<f32>().iter().any(|&m| m != 0.0));
+
+        let rms = RMSNorm::new(&[3]);
+        let y = rms.forward(&Tensor::from_vec(vec![3.0f32, 4.0, 0.0], &[1, 3]));
+        let scale = (25.0f64 / 3.0).sqrt();
+        assert_near(y.to_list::<f32>()[1] as f64, 4.0 / scale, 1e-5);
+        assert_eq!(names(&rms), vec!["weight"]);
+    }
+
+    #[test]
+    fn test_recurrent_cells_match_layers() {
+        let mut generator = Generator::with_seed(50);
+        let x = Tensor::normal(0.0, 1.0, &[4, 2, 3], Options::default(), Some(&mut generator));
+        let h0 = Tensor::normal(0.0, 1.0, &[1, 2, 5], Options::default(), Some(&mut generator));
+        let c0 = Tensor::normal(0.0, 1.0, &[1, 2, 5], Options::default(), Some(&mut generator));
+
+        let lstm = LSTM::with_generator(3, 5, RecurrentConfig::new(), Some(&mut Generator::with_seed(1)));
+        let cell = LSTMCell::with_generator(3, 5, true, Some(&mut Generator::with_seed(1)));
+        assert_eq!(names(&lstm), vec!["weight_ih_l0", "weight_hh_l0", "bias_ih_l0", "bias_hh_l0"]);
+        let (output, (h_n, c_n)) = lstm.forward_with_state(&x, Some((&h0, &c0)));
+        assert_eq!(output.shape(), vec![4, 2, 5]);
+
+        let (mut h, mut c) = (h0.squeeze(Some(0)), c0.squeeze(Some(0)));
+        for t in 0..4 {
+            (h, c) = cell.forward_with_state(&x.narrow(0, t, 1).squeeze(Some(0)), Some((&h, &c)));
+            assert_eq!(output.narrow(0, t, 1).squeeze(Some(0)).to_list::<f32>(), h.to_list::<f32>());
+        }
+        assert_eq!(h_n.to_list::<f32>(), h.to_list::<f32>());
+        assert_eq!(c_n.to_list::<f32>(), c.to_list::<f32>());
+
+        let config = RecurrentConfig::new().nonlinearity(RnnNonlinearity::ReLU).bias(false);
+        let rnn = RNN::with_generator(3, 5, config, Some(&mut Generator::with_seed(2)));
+        let cell = RNNCell::with_generator(3, 5, false, RnnNonlinearity::ReLU, Some(&mut Generator::with_seed(2)));
+        let mut h = Tensor::zeros(&[2, 5]);
+        for t in 0..4 {
+            h = cell.forward_with_state(&x.narrow(0, t, 1).squeeze(Some(0)), Some(&h));
+        }
+        assert_eq!(rnn.forward_with_state(&x, None).1.squeeze(Some(0)).to_list::<f32>(), h.to_list::<f32>());
+        assert_eq!(GRUCell::new(3, 5, true).forward(&Tensor::zeros(&[3])).shape(), vec![5]);
+        assert!(!GRUCell::new(3, 5, true).forward(&Tensor::zeros(&[2, 4])).defined());
+    }
+
+    #[test]
+    fn test_recurrent_layer_options() {
+        let mut generator = Generator::with_seed(50);
+        let config = RecurrentConfig::new().num_layers(2).bidirectional(true).dropout(0.5);
+        let mut gru = GRU::with_generator(3, 4, config, Some(&mut generator));
+        assert_eq!(names(&gru).len(), 16);
+        assert_eq!(names(&gru)[4], "weight_ih_l0_reverse");
+        let weights = gru.named_parameters();
+        assert_eq!(weights.iter().find(|(name, _)| name == "weight_ih_l1").unwrap().1.shape(), vec![12, 8]);
+
+        let x = Tensor::normal(0.0, 1.0, &[5, 2, 3], Options::default(), Some(&mut generator));
+        let (output, h_n) = gru.forward_with_state(&x, None);
+        assert_eq!(output.shape(), vec![5, 2, 8]);
+        assert_eq!(h_n.shape(), vec![4, 2, 4]);
+        assert_eq!(output.narrow(0, 4, 1).narrow(2, 0, 4).to_list::<f32>(), h_n.narrow(0, 2, 1).to_list::<f32>());
+        assert_eq!(output.narrow(0, 0, 1).narrow(2, 4, 4).to_list::<f32>(), h_n.narrow(0, 3, 1).to_list::<f32>());
+
+        gru.eval();
+        let eval = gru.forward(&x);
+        assert_eq!(eval.to_list::<f32>(), gru.forward(&x).to_list::<f32>());
+        gru.config.batch_first = true;
+        let transposed = gru.forward(&x.transpose(0, 1));
+        assert_eq!(transposed.transpose(0, 1).to_list::<f32>(), eval.to_list::<f32>());
+        let unbatched = gru.forward(&x.narrow(1, 1, 1).squeeze(Some(1)));
+        assert_eq!(unbatched.to_list::<f32>(), eval.narrow(1, 1, 1).to_list::<f32>());
+        assert!(!gru.forward_with_state(&x, Some(&Tensor::zeros(&[2, 5, 4]))).0.defined());
+
+        let empty = LSTM::with_config(3, 4, RecurrentConfig::new().num_layers(0));
+        let (output, (h_n, c_n)) = empty.forward_with_state(&x, None);
+        assert!(!output.defined() && !h_n.defined() && !c_n.defined());
+        let packed = pack_sequence(&[Clone::clone(&x).narrow(1, 0, 1).squeeze(Some(1))], true).unwrap();
+        assert!(!empty.forward_packed(&packed, None).0.data.defined());
+    }
+
+    #[test]
+    fn test_recurrent_packed_sequences() {
+        let mut generator = Generator::with_seed(50);
+        let config = RecurrentConfig::new().bidirectional(true);
+        let lstm = LSTM::with_generator(2, 3, config, Some(&mut generator));
+        let lengths = [2, 4, 3];
+        let sequences: Vec<Tensor> = lengths
+            .iter()
+            .map(|&length| Tensor::normal(0.0, 1.0, &[length, 2], Options::default(), Some(&mut generator)))
+            .collect();
+
+        let packed = pack_sequence(&sequences, false).unwrap();
+        let (output, (h_n, _)) = lstm.forward_packed(&packed, None);
+        let (padded, output_lengths) = pad_packed_sequence(&output, false, 0.0, None).unwrap();
+        assert_eq!(output_lengths, lengths.to_vec());
+        assert_eq!(padded.shape(), vec![4, 3, 6]);
+
+        for (index, sequence) in sequences.iter().enumerate() {
+            let (expected, (h, _)) = lstm.forward_with_state(sequence, None);
+            let actual = padded.narrow(1, index as i64, 1).narrow(0, 0, lengths[index]).squeeze(Some(1));
+            for (a, e) in actual.to_list::<f32>().iter().zip(expected.to_list::<f32>().iter()) {
+                assert_near(*a as f64, *e as f64, 1e-5);
+            }
+            let final_state = h_n.narrow(1, index as i64, 1).squeeze(Some(1));
+            for (a, e) in final_state.to_list::<f32>().iter().zip(h.to_list::<f32>().iter()) {
+                assert_near(*a as f64, *e as f64, 1e-5);
+            }
+        }
+
+        let mut x = Tensor::normal(0.0, 1.0, &[9, 2], Options::default(), Some(&mut generator));
+        x.set_requires_grad(true);
+        let input = PackedSequence { data: Clone::clone(&x), ..packed };
+        let (output, (h_n, c_n)) = lstm.forward_packed(&input, None);
+        (&output.data.sum() + &(&h_n.sum() + &c_n.sum())).sum().backward();
+        assert_eq!(x.grad().shape(), vec![9, 2]);
+        assert!(x.grad().to_list::<f32>().iter().all(|g| g.is_finite() && *g != 0.0));
+        assert!(lstm.parameters().iter().all(|p| p.grad().defined()));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::autograd::NoGradGuard;
 use crate::tensor::Tensor;
 use crate::optimizers::Optimizer;
 use std::collections::HashMap;
@@ -94,22 +95,22 @@ impl AdamW {
         let exp_avg = self.exp_avg.get_mut(&param_id).unwrap();
         let exp_avg_sq = self.exp_avg_sq.get_mut(&param_id).unwrap();
         
-        *exp_avg = exp_avg.clone() * &Tensor::scalar(self.config.beta1) + 
-                   &(&d_p * &Tensor::scalar(1.0 - self.config.beta1));
+        *exp_avg = &*exp_avg * self.config.beta1 + 
+                   &(&d_p * (1.0 - self.config.beta1));
         
         let grad_squared = &d_p * &d_p;
-        *exp_avg_sq = exp_avg_sq.clone() * &Tensor::scalar(self.config.beta2) + 
-                      &(&grad_squared * &Tensor::scalar(1.0 - self.config.beta2));
+        *exp_avg_sq = &*exp_avg_sq * self.config.beta2 + 
+                      &(&grad_squared * (1.0 - self.config.beta2));
         
-        let denom = sqrt_tensor(&(exp_avg_sq.clone() + &Tensor::scalar(self.config.eps)));
+        let denom = sqrt_tensor(&(&*exp_avg_sq + self.config.eps));
         
         let bias_correction1 = 1.0 - self.config.beta1.powi(self.step_count as i32 + 1);
         let bias_correction2 = 1.0 - self.config.beta2.powi(self.step_count as i32 + 1);
         let step_size = self.config.lr * (bias_correction2.sqrt() / bias_correction1);
         
-        let adam_update = &(exp_avg.clone() / &denom) * &Tensor::scalar(-step_size);
+        let adam_update = &(&*exp_avg / &denom) * -step_size;
         
-        let weight_decay_update = param * &Tensor::scalar(-self.config.lr * self.config.weight_decay);
+        let weight_decay_update = param * (-self.config.lr * self.config.weight_decay);
         
         Some(&adam_update + &weight_decay_update)
     }
@@ -117,6 +118,7 @@ impl AdamW {
 
 impl Optimizer for AdamW {
     fn step(&mut self) {
+        let _guard = NoGradGuard::new();
         self.step_count += 1;
         
         let mut param_data = Vec::new();
@@ -143,7 +145,7 @@ impl Optimizer for AdamW {
         for (group_idx, param_idx, update) in updates {
             if let Some(param_group) = self.param_groups.get_mut(group_idx) {
                 if let Some(param) = param_group.get_mut(param_idx) {
-                    *param = &*param + &update;
+                    *param += &update;
                 }
             }
         }
@@ -1,3 +1,4 @@
+use crate::autograd::NoGradGuard;
 use crate::tensor::Tensor;
 use crate::optimizers::Optimizer;
 use std::collections::HashMap;
@@ -78,16 +79,16 @@ impl SGD {
         let mut d_p = grad.clone();
         
         if self.config.weight_decay != 0.0 {
-            d_p = &d_p + &(param * &Tensor::scalar(self.config.weight_decay));
+            d_p = &d_p + &(param * self.config.weight_decay);
         }
         
         if self.config.momentum != 0.0 {
             if let Some(buf) = self.momentum_buffers.get(&param_id) {
-                let mut momentum_buf = buf * &Tensor::scalar(self.config.momentum);
-                momentum_buf = &momentum_buf + &(&d_p * &Tensor::scalar(1.0 - self.config.dampening));
+                let mut momentum_buf = buf * self.config.momentum;
+                momentum_buf = &momentum_buf + &(&d_p * (1.0 - self.config.dampening));
                 
                 if self.config.nesterov {
-                    d_p = &d_p + &(&momentum_buf * &Tensor::scalar(self.config.momentum));
+                    d_p = &d_p + &(&momentum_buf * self.config.momentum);
                 } else {
                     d_p = momentum_buf.clone();
                 }
@@ -100,13 +101,14 @@ impl SGD {
             }
         }
         
-        let update = &d_p * &Tensor::scalar(-self.config.lr);
+        let update = &d_p * -self.config.lr;
         Some(update)
     }
 }
 
 impl Optimizer for SGD {
     fn step(&mut self) {
+        let _guard = NoGradGuard::new();
         let mut param_data = Vec::new();
         
         for (group_idx, param_group) in self.param_groups.iter().enumerate() {
@@ -131,7 +133,7 @@ impl Optimizer for SGD {
         for (group_idx, param_idx, update) in updates {
             if let Some(param_group) = self.param_groups.get_mut(group_idx) {
                 if let Some(param) = param_group.get_mut(param_idx) {
-                    *param = &*param + &update;
+                    *param += &update;
                 }
             }
         }
@@ -1,8 +1,9 @@
-use crate::tensor::Tensor;
+use crate::tensor::{DType, MapMode, Storage, Tensor};
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Write, BufReader, BufWriter};
+use std::io::{Read, Write, BufReader, BufWriter, Cursor};
 use std::path::Path;
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub struct ModelState {
@@ -102,7 +103,57 @@ impl ModelState {
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
         let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
         let mut reader = BufReader::new(file);
-        
+
+        Self::read_state(&mut reader, |reader, shape, data_len| {
+            let mut data = Vec::with_capacity(data_len);
+            for _ in 0..data_len {
+                let mut value_bytes = [0u8; 4];
+                reader.read_exact(&mut value_bytes).map_err(|e| format!("Failed to read data value: {}", e))?;
+                data.push(f32::from_le_bytes(value_bytes));
+            }
+
+            let options = crate::tensor::Options::default().dtype(DType::Float32);
+            crate::tensor::TensorImpl::new_from_data(&data, shape, options).map(|impl_| Tensor {
+                impl_: Some(Rc::new(impl_)),
+            })
+        })
+    }
+
+    pub fn load_from_file_mmap<P: AsRef<Path>>(path: P, mode: MapMode) -> Result<Self, String> {
+        let storage = Rc::new(Storage::from_file(path, 0, None, mode)?);
+        let bytes = unsafe { std::slice::from_raw_parts(storage.data_ptr::<u8>() as *const u8, storage.size()) };
+        let mut cursor = Cursor::new(bytes);
+
+        Self::read_state(&mut cursor, |cursor, shape, data_len| {
+            let start = cursor.position() as usize;
+            let end = start + data_len * 4;
+            if end > bytes.len() {
+                return Err("Failed to read data value: unexpected end of file".to_string());
+            }
+            cursor.set_position(end as u64);
+
+            if shape.iter().product::<i64>() as usize != data_len {
+                return Err(format!("Data length {} doesn't match shape {:?}", data_len, shape));
+            }
+            if cfg!(target_endian = "little") && start.is_multiple_of(4) {
+                let tensor = Tensor::from_storage(Rc::clone(&storage), (start / 4) as i64, shape, DType::Float32);
+                if tensor.defined() {
+                    return Ok(tensor);
+                }
+            }
+
+            let data: Vec<f32> = bytes[start..end]
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect();
+            Ok(Tensor::from_vec(data, shape))
+        })
+    }
+
+    fn read_state<R: Read>(
+        reader: &mut R,
+        mut read_data: impl FnMut(&mut R, &[i64], usize) -> Result<Tensor, String>,
+    ) -> Result<Self, String> {
         let mut header = [0u8; 8];
         reader.read_exact(&mut header).map_err(|e| format!("Failed to read header: {}", e))?;
         if &header != b"RTORCH01" {
@@ -163,20 +214,8 @@ impl ModelState {
             reader.read_exact(&mut data_len_bytes).map_err(|e| format!("Failed to read data length: {}", e))?;
             let data_len = u32::from_le_bytes(data_len_bytes) as usize;
             
-            let mut data = Vec::with_capacity(data_len);
-            for _ in 0..data_len {
-                let mut value_bytes = [0u8; 4];
-                reader.read_exact(&mut value_bytes).map_err(|e| format!("Failed to read data value: {}", e))?;
-                data.push(f32::from_le_bytes(value_bytes));
-            }
-            
-            let options = crate::tensor::Options::default().dtype(crate::tensor::DType::Float32);
-            let tensor = match crate::tensor::TensorImpl::new_from_data(&data, &shape, options) {
-                Ok(impl_) => Tensor {
-                    impl_: Some(std::rc::Rc::new(impl_)),
-                },
-                Err(e) => return Err(format!("Failed to create tensor '{}': {}", name, e)),
-            };
+            let tensor = read_data(reader, &shape, data_len)
+                .map_err(|e| format!("Failed to create tensor '{}': {}", name, e))?;
             
             state.add_parameter(name, tensor);
         }
@@ -0,0 +1,162 @@
+use std::alloc::{alloc, dealloc, Layout};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+
+const ALIGNMENT: usize = 8;
+const MIN_BLOCK_SIZE: usize = 512;
+const LARGE_BLOCK_SIZE: usize = 1 << 20;
+const DEFAULT_CACHE_LIMIT: usize = 0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub allocated_bytes: usize,
+    pub reserved_bytes: usize,
+    pub peak_allocated_bytes: usize,
+    pub peak_reserved_bytes: usize,
+    pub num_allocs: usize,
+    pub num_cache_hits: usize,
+}
+
+struct CachingAllocator {
+    free_blocks: HashMap<usize, Vec<usize>>,
+    cached_bytes: usize,
+    cache_limit: usize,
+    stats: MemoryStats,
+}
+
+impl CachingAllocator {
+    fn new() -> Self {
+        Self {
+            free_blocks: HashMap::new(),
+            cached_bytes: 0,
+            cache_limit: DEFAULT_CACHE_LIMIT,
+            stats: MemoryStats::default(),
+        }
+    }
+
+    fn allocate(&mut self, size: usize, block_size: usize) -> Result<NonNull<u8>, String> {
+        let cached = self.free_blocks.get_mut(&block_size).and_then(|blocks| blocks.pop());
+        let ptr = match cached {
+            Some(address) => {
+                self.cached_bytes -= block_size;
+                self.stats.num_cache_hits += 1;
+                address as *mut u8
+            }
+            None => {
+                let ptr = unsafe { alloc(block_layout(block_size)?) };
+                if ptr.is_null() {
+                    return Err("Failed to allocate memory".to_string());
+                }
+                self.stats.reserved_bytes += block_size;
+                self.stats.peak_reserved_bytes = self.stats.peak_reserved_bytes.max(self.stats.reserved_bytes);
+                ptr
+            }
+        };
+
+        self.stats.num_allocs += 1;
+        self.stats.allocated_bytes += size;
+        self.stats.peak_allocated_bytes = self.stats.peak_allocated_bytes.max(self.stats.allocated_bytes);
+        NonNull::new(ptr).ok_or_else(|| "Failed to allocate memory".to_string())
+    }
+
+    fn release(&mut self, ptr: NonNull<u8>, size: usize, block_size: usize) {
+        self.stats.allocated_bytes = self.stats.allocated_bytes.saturating_sub(size);
+        if self.cached_bytes + block_size <= self.cache_limit {
+            self.free_blocks.entry(block_size).or_default().push(ptr.as_ptr() as usize);
+            self.cached_bytes += block_size;
+            return;
+        }
+
+        free_block(ptr.as_ptr(), block_size);
+        self.stats.reserved_bytes = self.stats.reserved_bytes.saturating_sub(block_size);
+    }
+
+    fn trim(&mut self, limit: usize) {
+        let mut sizes: Vec<usize> = self.free_blocks.keys().copied().collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        for size in sizes {
+            let blocks = self.free_blocks.entry(size).or_default();
+            while self.cached_bytes > limit {
+                match blocks.pop() {
+                    Some(address) => {
+                        free_block(address as *mut u8, size);
+                        self.cached_bytes -= size;
+                        self.stats.reserved_bytes = self.stats.reserved_bytes.saturating_sub(size);
+                    }
+                    None => break,
+                }
+            }
+        }
+        self.free_blocks.retain(|_, blocks| !blocks.is_empty());
+    }
+}
+
+impl Drop for CachingAllocator {
+    fn drop(&mut self) {
+        self.trim(0);
+    }
+}
+
+thread_local! {
+    static ALLOCATOR: RefCell<CachingAllocator> = RefCell::new(CachingAllocator::new());
+}
+
+pub(crate) fn block_size(size: usize) -> usize {
+    if size <= LARGE_BLOCK_SIZE {
+        size.next_power_of_two().max(MIN_BLOCK_SIZE)
+    } else {
+        size.div_ceil(LARGE_BLOCK_SIZE) * LARGE_BLOCK_SIZE
+    }
+}
+
+pub(crate) fn allocate(size: usize) -> Result<(NonNull<u8>, usize), String> {
+    let block_size = block_size(size);
+    let ptr = ALLOCATOR.with(|allocator| allocator.borrow_mut().allocate(size, block_size))?;
+    Ok((ptr, block_size))
+}
+
+pub(crate) fn release(ptr: NonNull<u8>, size: usize, block_size: usize) {
+    let released = ALLOCATOR.try_with(|allocator| allocator.borrow_mut().release(ptr, size, block_size));
+    if released.is_err() {
+        free_block(ptr.as_ptr(), block_size);
+    }
+}
+
+pub fn memory_stats() -> MemoryStats {
+    ALLOCATOR.with(|allocator| allocator.borrow().stats)
+}
+
+pub fn reset_peak_memory_stats() {
+    ALLOCATOR.with(|allocator| {
+        let stats = &mut allocator.borrow_mut().stats;
+        stats.peak_allocated_bytes = stats.allocated_bytes;
+        stats.peak_reserved_bytes = stats.reserved_bytes;
+    });
+}
+
+pub fn set_cache_limit(bytes: usize) {
+    ALLOCATOR.with(|allocator| {
+        let mut allocator = allocator.borrow_mut();
+        allocator.cache_limit = bytes;
+        allocator.trim(bytes);
+    });
+}
+
+pub fn cache_limit() -> usize {
+    ALLOCATOR.with(|allocator| allocator.borrow().cache_limit)
+}
+
+pub fn empty_cache() {
+    ALLOCATOR.with(|allocator| allocator.borrow_mut().trim(0));
+}
+
+fn block_layout(block_size: usize) -> Result<Layout, String> {
+    Layout::from_size_align(block_size, ALIGNMENT).map_err(|e| format!("Invalid layout: {}", e))
+}
+
+fn free_block(ptr: *mut u8, block_size: usize) {
+    if let Ok(layout) = block_layout(block_size) {
+        unsafe { dealloc(ptr, layout) };
+    }
+}
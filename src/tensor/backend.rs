@@ -0,0 +1,400 @@
+use crate::tensor::broadcasting::broadcast_shapes;
+use crate::tensor::tensor::reduction_dtype;
+use crate::tensor::{DType, Device, DeviceType, Options, Storage, Tensor, TensorImpl};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    Sum,
+    Prod,
+    Mean,
+    Max,
+    Min,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Xor,
+    IsClose { rtol: f64, atol: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Sqrt,
+    Log,
+    Sin,
+    Cos,
+    Tanh,
+    Sigmoid,
+    Relu,
+    Gelu,
+    Silu,
+    LeakyRelu { negative_slope: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Remainder,
+    FloorDivide,
+    Maximum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    LogicalNot,
+    IsNan,
+    IsInf,
+    IsFinite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaneSelect {
+    Sort { descending: bool },
+    TopK { k: usize, largest: bool, sorted: bool },
+    Kth { k: usize },
+    Mode,
+    Cummax,
+}
+
+impl LaneSelect {
+    pub fn out_size(self, lane_size: usize) -> usize {
+        match self {
+            Self::Sort { .. } | Self::Cummax => lane_size,
+            Self::TopK { k, .. } => k,
+            Self::Kth { .. } | Self::Mode => 1,
+        }
+    }
+}
+
+pub type UnaryKernel = fn(&Tensor, UnaryOp) -> Tensor;
+pub type BinaryKernel = fn(&Tensor, &Tensor, BinaryOp) -> Tensor;
+pub type MatmulKernel = fn(&Tensor, &Tensor) -> Tensor;
+pub type ReduceKernel = fn(&Tensor, Reduction) -> Tensor;
+pub type CompareKernel = fn(&Tensor, &Tensor, Comparison) -> Tensor;
+pub type PredicateKernel = fn(&Tensor, Predicate) -> Tensor;
+pub type SortKernel = fn(&Tensor, i64, LaneSelect) -> Tensor;
+pub type IndexKernel = fn(&Tensor, i64, &Tensor) -> Tensor;
+
+#[derive(Clone, Copy)]
+pub struct KernelTable {
+    pub unary: UnaryKernel,
+    pub binary: BinaryKernel,
+    pub matmul: MatmulKernel,
+    pub reduce: ReduceKernel,
+    pub compare: CompareKernel,
+    pub predicate: PredicateKernel,
+    pub sort: SortKernel,
+    pub gather: IndexKernel,
+    pub index_select: IndexKernel,
+}
+
+pub trait Backend {
+    fn device_type(&self) -> DeviceType;
+
+    fn allocate(&self, size: usize, device: Device) -> Result<Storage, String>;
+
+    fn read(&self, src: &TensorImpl) -> Result<Vec<f64>, String>;
+
+    fn write(&self, dst: &TensorImpl, data: &[f64]) -> Result<(), String>;
+
+    fn kernels(&self) -> KernelTable;
+
+    fn copy_from(&self, source: &dyn Backend, src: &TensorImpl, dst: &TensorImpl) -> Result<(), String> {
+        let data = source.read(src)?;
+        self.write(dst, &data)
+    }
+}
+
+pub struct CpuBackend;
+
+impl Backend for CpuBackend {
+    fn device_type(&self) -> DeviceType {
+        DeviceType::CPU
+    }
+
+    fn allocate(&self, size: usize, _device: Device) -> Result<Storage, String> {
+        Storage::cpu(size)
+    }
+
+    fn read(&self, src: &TensorImpl) -> Result<Vec<f64>, String> {
+        src.to_f64_list()
+    }
+
+    fn write(&self, dst: &TensorImpl, data: &[f64]) -> Result<(), String> {
+        dst.write_f64(data)
+    }
+
+    fn kernels(&self) -> KernelTable {
+        KernelTable {
+            unary: Tensor::unary_cpu,
+            binary: Tensor::binary_cpu,
+            matmul: Tensor::matmul_cpu,
+            reduce: Tensor::reduce_cpu,
+            compare: Tensor::compare_cpu,
+            predicate: Tensor::predicate_cpu,
+            sort: Tensor::sort_cpu,
+            gather: Tensor::gather_cpu,
+            index_select: Tensor::index_select_cpu,
+        }
+    }
+}
+
+pub struct MetaBackend;
+
+impl Backend for MetaBackend {
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Meta
+    }
+
+    fn allocate(&self, size: usize, _device: Device) -> Result<Storage, String> {
+        Ok(Storage::meta(size))
+    }
+
+    fn read(&self, _src: &TensorImpl) -> Result<Vec<f64>, String> {
+        Err("Cannot read data of a meta tensor".to_string())
+    }
+
+    fn write(&self, _dst: &TensorImpl, _data: &[f64]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn kernels(&self) -> KernelTable {
+        KernelTable {
+            unary: meta_unary,
+            binary: meta_binary,
+            matmul: meta_matmul,
+            reduce: meta_reduce,
+            compare: meta_compare,
+            predicate: meta_predicate,
+            sort: meta_sort,
+            gather: meta_gather,
+            index_select: meta_index_select,
+        }
+    }
+
+    fn copy_from(&self, _source: &dyn Backend, _src: &TensorImpl, _dst: &TensorImpl) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    static BACKENDS: RefCell<HashMap<DeviceType, Rc<dyn Backend>>> = RefCell::new(default_backends());
+}
+
+fn default_backends() -> HashMap<DeviceType, Rc<dyn Backend>> {
+    let mut backends: HashMap<DeviceType, Rc<dyn Backend>> = HashMap::new();
+    backends.insert(DeviceType::CPU, Rc::new(CpuBackend));
+    backends.insert(DeviceType::Meta, Rc::new(MetaBackend));
+    backends
+}
+
+pub fn register_backend(backend: Rc<dyn Backend>) -> Option<Rc<dyn Backend>> {
+    BACKENDS.with(|backends| backends.borrow_mut().insert(backend.device_type(), backend))
+}
+
+pub fn unregister_backend(device_type: DeviceType) -> Option<Rc<dyn Backend>> {
+    BACKENDS.with(|backends| backends.borrow_mut().remove(&device_type))
+}
+
+pub fn backend(device: Device) -> Result<Rc<dyn Backend>, String> {
+    BACKENDS.with(|backends| {
+        backends
+            .borrow()
+            .get(&device.device_type)
+            .cloned()
+            .ok_or_else(|| format!("No backend registered for device {}", device))
+    })
+}
+
+pub(crate) fn dispatch_device(tensors: &[&Tensor]) -> Option<Device> {
+    let mut device = None;
+    for tensor in tensors {
+        let impl_ = tensor.impl_.as_ref()?;
+        if impl_.device().is_cpu() && impl_.dim() == 0 {
+            continue;
+        }
+        match device {
+            None => device = Some(impl_.device()),
+            Some(current) if current != impl_.device() => return None,
+            _ => {}
+        }
+    }
+    Some(device.unwrap_or_else(Device::cpu))
+}
+
+impl Tensor {
+    pub fn to(&self, device: Device) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        if impl_.device() == device {
+            return Clone::clone(self);
+        }
+
+        let (source, target) = match (backend(impl_.device()), backend(device)) {
+            (Ok(source), Ok(target)) => (source, target),
+            _ => return Self::new(),
+        };
+        let options = Options::default().dtype(impl_.dtype()).device(device);
+        let copy = match TensorImpl::new(impl_.shape(), options) {
+            Ok(copy) => copy,
+            Err(_) => return Self::new(),
+        };
+        if target.copy_from(source.as_ref(), impl_, &copy).is_err() {
+            return Self::new();
+        }
+
+        let source_device = impl_.device();
+        Self::new_from_impl(Rc::new(copy)).with_grad_fn("ToCopyBackward0", &[self], move |grad| {
+            vec![grad.to(source_device)]
+        })
+    }
+
+    pub fn cpu(&self) -> Self {
+        self.to(Device::cpu())
+    }
+
+    pub fn is_meta(&self) -> bool {
+        self.defined() && self.device().is_meta()
+    }
+}
+
+pub(crate) fn meta_shape_rule(inputs: &[&Tensor], shape: &[i64], dtype: DType) -> Option<Tensor> {
+    match dispatch_device(inputs) {
+        Some(device) if device.is_meta() => Some(meta_tensor_of(shape, dtype)),
+        _ => None,
+    }
+}
+
+fn meta_tensor(shape: &[i64]) -> Tensor {
+    meta_tensor_of(shape, DType::Float32)
+}
+
+fn meta_tensor_of(shape: &[i64], dtype: DType) -> Tensor {
+    Tensor::empty_with_options(shape, Options::default().dtype(dtype).device(Device::meta()))
+}
+
+fn meta_unary(input: &Tensor, _op: UnaryOp) -> Tensor {
+    if !input.defined() {
+        return Tensor::new();
+    }
+    meta_tensor(&input.shape())
+}
+
+fn meta_binary(lhs: &Tensor, rhs: &Tensor, _op: BinaryOp) -> Tensor {
+    meta_binary_shape(lhs, rhs)
+}
+
+pub(crate) fn meta_binary_shape(lhs: &Tensor, rhs: &Tensor) -> Tensor {
+    if !lhs.defined() || !rhs.defined() {
+        return Tensor::new();
+    }
+    match broadcast_shapes(&lhs.shape(), &rhs.shape()) {
+        Ok(shape) => meta_tensor(&shape),
+        Err(_) => Tensor::new(),
+    }
+}
+
+fn meta_matmul(lhs: &Tensor, rhs: &Tensor) -> Tensor {
+    if !lhs.defined() || !rhs.defined() {
+        return Tensor::new();
+    }
+    let (a, b) = (lhs.shape(), rhs.shape());
+    if a.len() != 2 || b.len() != 2 || a[1] != b[0] {
+        return Tensor::new();
+    }
+    meta_tensor(&[a[0], b[1]])
+}
+
+fn meta_reduce(input: &Tensor, reduction: Reduction) -> Tensor {
+    if !input.defined() {
+        return Tensor::new();
+    }
+    if matches!(reduction, Reduction::Max | Reduction::Min) && input.numel() == 0 {
+        return Tensor::new();
+    }
+    let options = Options::default().dtype(reduction_dtype(input.dtype(), reduction)).device(Device::meta());
+    Tensor::empty_with_options(&[], options)
+}
+
+fn meta_compare(lhs: &Tensor, rhs: &Tensor, _op: Comparison) -> Tensor {
+    if !lhs.defined() || !rhs.defined() {
+        return Tensor::new();
+    }
+    match broadcast_shapes(&lhs.shape(), &rhs.shape()) {
+        Ok(shape) => meta_tensor_of(&shape, DType::Bool),
+        Err(_) => Tensor::new(),
+    }
+}
+
+fn meta_predicate(input: &Tensor, _op: Predicate) -> Tensor {
+    if !input.defined() {
+        return Tensor::new();
+    }
+    meta_tensor_of(&input.shape(), DType::Bool)
+}
+
+fn meta_sort(input: &Tensor, dim: i64, select: LaneSelect) -> Tensor {
+    let mut shape = input.shape();
+    match meta_dim(input, dim) {
+        Some(d) if !shape.is_empty() => shape[d] = select.out_size(shape[d] as usize) as i64,
+        Some(_) => {}
+        None => return Tensor::new(),
+    }
+    meta_tensor_of(&shape, DType::Int64)
+}
+
+fn meta_gather(input: &Tensor, dim: i64, index: &Tensor) -> Tensor {
+    let d = match meta_dim(input, dim) {
+        Some(d) if is_index(index) => d,
+        _ => return Tensor::new(),
+    };
+    let (shape, index_shape) = (input.shape(), index.shape());
+    if index_shape.len() != shape.len().max(1) && !(shape.is_empty() && index_shape.is_empty()) {
+        return Tensor::new();
+    }
+    if shape.iter().zip(index_shape.iter()).enumerate().any(|(i, (&size, &index_size))| i != d && index_size > size) {
+        return Tensor::new();
+    }
+    meta_tensor_of(&index_shape, input.dtype())
+}
+
+fn meta_index_select(input: &Tensor, dim: i64, index: &Tensor) -> Tensor {
+    let d = match meta_dim(input, dim) {
+        Some(d) if is_index(index) && index.dim() <= 1 && input.dim() > 0 => d,
+        _ => return Tensor::new(),
+    };
+    let mut shape = input.shape();
+    shape[d] = index.numel();
+    meta_tensor_of(&shape, input.dtype())
+}
+
+fn meta_dim(input: &Tensor, dim: i64) -> Option<usize> {
+    if !input.defined() {
+        return None;
+    }
+    let ndim = input.dim().max(1);
+    let d = if dim < 0 { dim + ndim } else { dim };
+    (0..ndim).contains(&d).then_some(d as usize)
+}
+
+fn is_index(index: &Tensor) -> bool {
+    index.defined() && matches!(index.dtype(), DType::Int64 | DType::Int32)
+}
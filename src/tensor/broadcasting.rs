@@ -1,4 +1,4 @@
-use crate::tensor::{Tensor, TensorImpl, Options, DType};
+use crate::tensor::{Tensor, TensorImpl, TensorIterator, Options, DType};
 use std::rc::Rc;
 
 pub fn is_broadcastable(shape1: &[i64], shape2: &[i64]) -> bool {
@@ -26,47 +26,33 @@ pub fn broadcast_shapes(shape1: &[i64], shape2: &[i64]) -> Result<Vec<i64>, Stri
     for i in 0..max_dims {
         let dim1 = if i < shape1.len() { shape1[shape1.len() - 1 - i] } else { 1 };
         let dim2 = if i < shape2.len() { shape2[shape2.len() - 1 - i] } else { 1 };
-        result_shape.push(dim1.max(dim2));
+        result_shape.push(if dim1 == 1 { dim2 } else { dim1 });
     }
     
     result_shape.reverse();
     Ok(result_shape)
 }
 
-pub fn broadcast_tensor_data(data: &[f32], from_shape: &[i64], to_shape: &[i64]) -> Result<Vec<f32>, String> {
+pub fn broadcast_tensor_data<T: Copy>(data: &[T], from_shape: &[i64], to_shape: &[i64]) -> Result<Vec<T>, String> {
     if from_shape == to_shape {
         return Ok(data.to_vec());
     }
-    
-    let total_elements = to_shape.iter().product::<i64>() as usize;
-    let mut result = Vec::with_capacity(total_elements);
-    
-    let from_strides = compute_strides(from_shape);
-    let to_strides = compute_strides(to_shape);
-    
-    for i in 0..total_elements {
-        let mut from_idx = 0;
-        let mut temp_i = i;
-        
-        for (dim_idx, &to_stride) in to_strides.iter().enumerate() {
-            let coord = temp_i / to_stride as usize;
-            temp_i %= to_stride as usize;
-            
-            let from_dim_offset = to_shape.len() - from_shape.len();
-            if dim_idx >= from_dim_offset {
-                let from_dim_idx = dim_idx - from_dim_offset;
-                let from_coord = if from_shape[from_dim_idx] == 1 { 0 } else { coord };
-                from_idx += from_coord * from_strides[from_dim_idx] as usize;
-            }
-        }
-        
-        if from_idx < data.len() {
-            result.push(data[from_idx]);
-        } else {
-            return Err("Index out of bounds during broadcasting".to_string());
-        }
+
+    if data.len() < from_shape.iter().product::<i64>() as usize {
+        return Err("Index out of bounds during broadcasting".to_string());
     }
-    
+
+    let from_strides = TensorIterator::broadcast_strides(from_shape, &compute_strides(from_shape), to_shape)?;
+    let iter = TensorIterator::new(to_shape, &[from_strides])?;
+
+    let mut result = Vec::with_capacity(iter.numel());
+    iter.for_each_run(|offsets, len, inner| {
+        let base = offsets[0];
+        for i in 0..len as isize {
+            result.push(data[(base + i * inner[0]) as usize]);
+        }
+    });
+
     Ok(result)
 }
 
@@ -123,3 +109,44 @@ pub fn broadcast_tensors(tensor1: &Tensor, tensor2: &Tensor) -> Result<(Tensor,
     
     Ok((broadcasted1, broadcasted2))
 }
+
+impl Tensor {
+    pub fn sum_to_size(&self, shape: &[i64]) -> Tensor {
+        if !self.defined() {
+            return Tensor::new();
+        }
+
+        let self_shape = self.shape();
+        if self_shape == shape {
+            return Clone::clone(self);
+        }
+
+        let data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Tensor::new(),
+        };
+        let target_strides = match TensorIterator::broadcast_strides(shape, &compute_strides(shape), &self_shape) {
+            Ok(strides) => strides,
+            Err(_) => return Tensor::new(),
+        };
+        let iter = match TensorIterator::new(&self_shape, &[compute_strides(&self_shape), target_strides]) {
+            Ok(iter) => iter,
+            Err(_) => return Tensor::new(),
+        };
+
+        let mut result = vec![0.0f64; shape.iter().product::<i64>() as usize];
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                result[(offsets[1] + i * inner[1]) as usize] += data[(offsets[0] + i * inner[0]) as usize];
+            }
+        });
+
+        let options = Options::default().dtype(self.dtype()).device(self.device());
+        match TensorImpl::new_from_f64(&result, shape, options) {
+            Ok(impl_) => Tensor {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Tensor::new(),
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use crate::tensor::broadcasting::broadcast_shapes;
+use crate::tensor::iterator::Elements;
+use crate::tensor::{
+    backend, dispatch_device, meta_shape_rule, Comparison, DType, Options, Predicate, Tensor, TensorImpl,
+    TensorIterator,
+};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+impl Tensor {
+    pub fn eq(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Eq)
+    }
+
+    pub fn ne(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Ne)
+    }
+
+    pub fn lt(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Lt)
+    }
+
+    pub fn le(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Le)
+    }
+
+    pub fn gt(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Gt)
+    }
+
+    pub fn ge(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Ge)
+    }
+
+    pub fn logical_and(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::And)
+    }
+
+    pub fn logical_or(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Or)
+    }
+
+    pub fn logical_xor(&self, other: &Self) -> Self {
+        self.compare_op(other, Comparison::Xor)
+    }
+
+    pub fn logical_not(&self) -> Self {
+        self.predicate_op(Predicate::LogicalNot)
+    }
+
+    pub fn isnan(&self) -> Self {
+        self.predicate_op(Predicate::IsNan)
+    }
+
+    pub fn isinf(&self) -> Self {
+        self.predicate_op(Predicate::IsInf)
+    }
+
+    pub fn isfinite(&self) -> Self {
+        self.predicate_op(Predicate::IsFinite)
+    }
+
+    pub fn isclose(&self, other: &Self, rtol: f64, atol: f64) -> Self {
+        self.compare_op(other, Comparison::IsClose { rtol, atol })
+    }
+
+    pub fn allclose(&self, other: &Self, rtol: f64, atol: f64) -> bool {
+        let close = self.isclose(other, rtol, atol);
+        if !close.defined() {
+            return false;
+        }
+        close.to_list::<u8>().iter().all(|&v| v != 0)
+    }
+
+    pub fn masked_fill(&self, mask: &Self, value: f32) -> Self {
+        let (impl_, mask_impl) = match (self.impl_.as_ref(), mask.impl_.as_ref()) {
+            (Some(impl_), Some(mask_impl)) => (impl_, mask_impl),
+            _ => return Self::new(),
+        };
+
+        let shape = impl_.shape().to_vec();
+        if let Some(output) = meta_shape_rule(&[self, mask], &shape, impl_.dtype()) {
+            return output;
+        }
+        let mask_strides = match TensorIterator::broadcast_strides(mask_impl.shape(), mask_impl.strides(), &shape) {
+            Ok(strides) => strides,
+            Err(_) => return Self::new(),
+        };
+        let mut output = match impl_.contiguous_copy() {
+            Ok(output) => output,
+            Err(_) => return Self::new(),
+        };
+        output.set_requires_grad(false);
+        let (out, mask_elements) = match (Elements::from_impl(&output), Elements::from_impl(mask_impl)) {
+            (Some(out), Some(mask_elements)) => (out, mask_elements),
+            _ => return Self::new(),
+        };
+
+        let out_strides = TensorIterator::contiguous_strides(&shape);
+        let iter = match TensorIterator::new(&shape, &[out_strides, mask_strides]) {
+            Ok(iter) => iter,
+            Err(_) => return Self::new(),
+        };
+        iter.for_each_run(|offsets, len, inner| unsafe {
+            for i in 0..len as isize {
+                if mask_elements.read(offsets[1] + i * inner[1]) != 0.0 {
+                    out.write(offsets[0] + i * inner[0], value as f64);
+                }
+            }
+        });
+
+        let mask = mask.detach();
+        Self::new_from_impl(Rc::new(output)).with_grad_fn("MaskedFillBackward0", &[self], move |grad| {
+            vec![grad.masked_fill(&mask, 0.0)]
+        })
+    }
+
+    pub fn nonzero(&self) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+
+        let data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        let shape = self.shape();
+        let ndim = shape.len();
+
+        let mut indices = Vec::new();
+        let mut count = 0i64;
+        for (flat_idx, &value) in data.iter().enumerate() {
+            if value == 0.0 {
+                continue;
+            }
+            let mut coords = vec![0i64; ndim];
+            let mut remaining = flat_idx as i64;
+            for d in (0..ndim).rev() {
+                coords[d] = remaining % shape[d];
+                remaining /= shape[d];
+            }
+            indices.extend(coords);
+            count += 1;
+        }
+
+        let options = Options::default().dtype(DType::Int64);
+        match TensorImpl::new_from_data(&indices, &[count, ndim as i64], options) {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn count_nonzero(&self) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+        if let Some(count) = meta_shape_rule(&[self], &[], DType::Int64) {
+            return count;
+        }
+
+        match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => Self::scalar(data.iter().filter(|&&v| v != 0.0).count() as i64),
+            _ => Self::new(),
+        }
+    }
+
+    fn compare_op(&self, other: &Self, op: Comparison) -> Self {
+        match dispatch_device(&[self, other]).map(backend) {
+            Some(Ok(backend)) => (backend.kernels().compare)(self, other, op),
+            _ => Self::new(),
+        }
+    }
+
+    fn predicate_op(&self, op: Predicate) -> Self {
+        match self.impl_.as_ref().map(|impl_| backend(impl_.device())) {
+            Some(Ok(backend)) => (backend.kernels().predicate)(self, op),
+            _ => Self::new(),
+        }
+    }
+
+    pub(crate) fn compare_cpu(&self, other: &Self, op: Comparison) -> Self {
+        let (lhs_impl, rhs_impl) = match (self.impl_.as_ref(), other.impl_.as_ref()) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return Self::new(),
+        };
+
+        let result_shape = match broadcast_shapes(lhs_impl.shape(), rhs_impl.shape()) {
+            Ok(shape) => shape,
+            Err(_) => return Self::new(),
+        };
+        let (lhs, rhs) = match (Elements::from_impl(lhs_impl), Elements::from_impl(rhs_impl)) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return Self::new(),
+        };
+
+        let lhs_strides =
+            match TensorIterator::broadcast_strides(lhs_impl.shape(), lhs_impl.strides(), &result_shape) {
+                Ok(strides) => strides,
+                Err(_) => return Self::new(),
+            };
+        let rhs_strides =
+            match TensorIterator::broadcast_strides(rhs_impl.shape(), rhs_impl.strides(), &result_shape) {
+                Ok(strides) => strides,
+                Err(_) => return Self::new(),
+            };
+        let out_strides = TensorIterator::contiguous_strides(&result_shape);
+
+        let iter = match TensorIterator::new(&result_shape, &[out_strides, lhs_strides, rhs_strides]) {
+            Ok(iter) => iter,
+            Err(_) => return Self::new(),
+        };
+
+        let integral = lhs.is_integral() && rhs.is_integral();
+        let mut result_data = vec![0u8; iter.numel()];
+        iter.for_each_run(|offsets, len, inner| unsafe {
+            for i in 0..len as isize {
+                let (a, b) = (offsets[1] + i * inner[1], offsets[2] + i * inner[2]);
+                let result = if integral {
+                    op.integers(lhs.read_i64(a), rhs.read_i64(b))
+                } else {
+                    op.floats(lhs.read(a), rhs.read(b))
+                };
+                result_data[(offsets[0] + i * inner[0]) as usize] = result as u8;
+            }
+        });
+
+        Self::from_bool_data(&result_data, &result_shape)
+    }
+
+    pub(crate) fn predicate_cpu(&self, op: Predicate) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+
+        let data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        let result_data: Vec<u8> = data.iter().map(|&a| op.holds(a) as u8).collect();
+
+        Self::from_bool_data(&result_data, &self.shape())
+    }
+
+    fn from_bool_data(data: &[u8], shape: &[i64]) -> Self {
+        let options = Options::default().dtype(DType::Bool);
+        match TensorImpl::new_from_data(data, shape, options) {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+}
+
+impl Comparison {
+    fn floats(self, a: f64, b: f64) -> bool {
+        match self {
+            Self::And | Self::Or | Self::Xor => self.truth(a != 0.0, b != 0.0),
+            Self::IsClose { rtol, atol } => {
+                a == b || (a.is_finite() && b.is_finite() && (a - b).abs() <= atol + rtol * b.abs())
+            }
+            Self::Ne => a != b,
+            _ => a.partial_cmp(&b).is_some_and(|ordering| self.holds(ordering)),
+        }
+    }
+
+    fn integers(self, a: i64, b: i64) -> bool {
+        match self {
+            Self::And | Self::Or | Self::Xor => self.truth(a != 0, b != 0),
+            Self::IsClose { rtol, atol } => {
+                a == b || (a as i128 - b as i128).unsigned_abs() as f64 <= atol + rtol * (b as f64).abs()
+            }
+            _ => self.holds(a.cmp(&b)),
+        }
+    }
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Ne => ordering != Ordering::Equal,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Le => ordering != Ordering::Greater,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Ge => ordering != Ordering::Less,
+            _ => false,
+        }
+    }
+
+    fn truth(self, a: bool, b: bool) -> bool {
+        match self {
+            Self::And => a && b,
+            Self::Or => a || b,
+            _ => a != b,
+        }
+    }
+}
+
+impl Predicate {
+    fn holds(self, a: f64) -> bool {
+        match self {
+            Self::LogicalNot => a == 0.0,
+            Self::IsNan => a.is_nan(),
+            Self::IsInf => a.is_infinite(),
+            Self::IsFinite => a.is_finite(),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::tensor::{Options, Tensor};
+
+impl Tensor {
+    pub fn cat(tensors: &[Tensor], dim: i64) -> Self {
+        if tensors.is_empty() || tensors.iter().any(|tensor| !tensor.defined()) {
+            return Self::new();
+        }
+
+        let parts: Vec<&Tensor> = tensors
+            .iter()
+            .filter(|tensor| !(tensor.dim() == 1 && tensor.numel() == 0))
+            .collect();
+        let first = &tensors[0];
+        let options = Options::default().dtype(first.dtype()).device(first.device());
+        if parts.is_empty() {
+            return Self::empty_with_options(&[0], options);
+        }
+
+        let reference = parts[0].shape();
+        let ndim = reference.len() as i64;
+        let d = if dim < 0 { dim + ndim } else { dim };
+        if reference.is_empty() || d < 0 || d >= ndim {
+            return Self::new();
+        }
+        let d = d as usize;
+
+        let mut out_shape = reference.clone();
+        out_shape[d] = 0;
+        for part in &parts {
+            let shape = part.shape();
+            let matches = shape.len() == reference.len()
+                && shape.iter().zip(reference.iter()).enumerate().all(|(i, (a, b))| i == d || a == b);
+            if !matches {
+                return Self::new();
+            }
+            out_shape[d] += shape[d];
+        }
+
+        let sizes: Vec<i64> = parts.iter().map(|part| part.shape()[d]).collect();
+        let result = if parts.iter().all(|part| part.is_meta()) {
+            Self::empty_with_options(&out_shape, options)
+        } else {
+            Self::cat_data(&parts, &reference, d, &sizes, &out_shape, options)
+        };
+
+        result.with_grad_fn("CatBackward0", &parts, move |grad| {
+            let mut start = 0;
+            sizes
+                .iter()
+                .map(|&size| {
+                    let piece = grad.narrow(d as i64, start, size);
+                    start += size;
+                    piece
+                })
+                .collect()
+        })
+    }
+
+    fn cat_data(
+        parts: &[&Tensor],
+        reference: &[i64],
+        d: usize,
+        sizes: &[i64],
+        out_shape: &[i64],
+        options: Options,
+    ) -> Self {
+        let mut lists = Vec::with_capacity(parts.len());
+        for part in parts {
+            match part.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+                Some(Ok(data)) => lists.push(data),
+                _ => return Self::new(),
+            }
+        }
+
+        let outer: i64 = reference[..d].iter().product();
+        let inner: i64 = reference[d + 1..].iter().product();
+        let mut data = Vec::with_capacity(out_shape.iter().product::<i64>() as usize);
+        for o in 0..outer as usize {
+            for (list, &size) in lists.iter().zip(sizes.iter()) {
+                let chunk = (size * inner) as usize;
+                data.extend_from_slice(&list[o * chunk..(o + 1) * chunk]);
+            }
+        }
+
+        Self::from_f64_data(&data, out_shape, options)
+    }
+
+    pub fn stack(tensors: &[Tensor], dim: i64) -> Self {
+        if tensors.is_empty() || tensors.iter().any(|tensor| !tensor.defined()) {
+            return Self::new();
+        }
+
+        let shape = tensors[0].shape();
+        if tensors.iter().any(|tensor| tensor.shape() != shape) {
+            return Self::new();
+        }
+
+        let ndim = shape.len() as i64 + 1;
+        let d = if dim < 0 { dim + ndim } else { dim };
+        if d < 0 || d >= ndim {
+            return Self::new();
+        }
+
+        let expanded: Vec<Tensor> = tensors.iter().map(|tensor| tensor.unsqueeze(d)).collect();
+        Self::cat(&expanded, d)
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt;
 pub enum DeviceType {
     CPU = 0,
     CUDA = 1,
+    Meta = 2,
 }
 
 pub type DeviceIndex = i8;
@@ -27,6 +28,10 @@ impl Device {
         Self::new(DeviceType::CUDA, index)
     }
 
+    pub fn meta() -> Self {
+        Self::new(DeviceType::Meta, 0)
+    }
+
     pub fn is_cpu(&self) -> bool {
         self.device_type == DeviceType::CPU
     }
@@ -34,6 +39,10 @@ impl Device {
     pub fn is_cuda(&self) -> bool {
         self.device_type == DeviceType::CUDA
     }
+
+    pub fn is_meta(&self) -> bool {
+        self.device_type == DeviceType::Meta
+    }
 }
 
 impl Default for Device {
@@ -47,6 +56,7 @@ impl fmt::Display for Device {
         match self.device_type {
             DeviceType::CPU => write!(f, "CPU"),
             DeviceType::CUDA => write!(f, "CUDA:{}", self.index),
+            DeviceType::Meta => write!(f, "meta"),
         }
     }
 }
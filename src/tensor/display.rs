@@ -0,0 +1,242 @@
+use crate::tensor::{DType, Tensor};
+use std::cell::RefCell;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    pub precision: usize,
+    pub threshold: usize,
+    pub edgeitems: usize,
+    pub linewidth: usize,
+    pub sci_mode: Option<bool>,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            precision: 4,
+            threshold: 1000,
+            edgeitems: 3,
+            linewidth: 80,
+            sci_mode: None,
+        }
+    }
+}
+
+thread_local! {
+    static PRINT_OPTIONS: RefCell<PrintOptions> = RefCell::new(PrintOptions::default());
+}
+
+pub fn set_printoptions(options: PrintOptions) {
+    PRINT_OPTIONS.with(|current| *current.borrow_mut() = options);
+}
+
+pub fn get_printoptions() -> PrintOptions {
+    PRINT_OPTIONS.with(|current| current.borrow().clone())
+}
+
+const PREFIX: &str = "tensor(";
+
+enum ElementFormat {
+    Bool,
+    Integer,
+    IntegralFloat,
+    Fixed(usize),
+    Scientific(usize),
+}
+
+impl ElementFormat {
+    fn new(dtype: DType, values: &[f64], options: &PrintOptions) -> Self {
+        match dtype {
+            DType::Bool => return Self::Bool,
+            DType::Int32 | DType::Int64 => return Self::Integer,
+            _ => {}
+        }
+
+        let finite: Vec<f64> = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .map(|v| v.abs())
+            .collect();
+        let nonzero: Vec<f64> = finite.iter().copied().filter(|&v| v != 0.0).collect();
+        let max = nonzero.iter().copied().fold(0.0, f64::max);
+        let min = nonzero.iter().copied().fold(f64::INFINITY, f64::min);
+
+        let integral = finite.iter().all(|v| v.fract() == 0.0);
+        let sci_mode = options.sci_mode.unwrap_or_else(|| {
+            if integral {
+                max > 1e8
+            } else {
+                !nonzero.is_empty() && (max / min > 1000.0 || max > 1e8 || min < 1e-4)
+            }
+        });
+        if sci_mode {
+            Self::Scientific(options.precision)
+        } else if integral {
+            Self::IntegralFloat
+        } else {
+            Self::Fixed(options.precision)
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self {
+            Self::Bool => if value != 0.0 { "True" } else { "False" }.to_string(),
+            Self::Integer => format!("{}", value as i64),
+            _ if value.is_nan() => "nan".to_string(),
+            _ if value.is_infinite() => if value > 0.0 { "inf" } else { "-inf" }.to_string(),
+            Self::IntegralFloat => format!("{:.0}.", value),
+            Self::Fixed(precision) => format!("{:.*}", precision, value),
+            Self::Scientific(precision) => {
+                let formatted = format!("{:.*e}", precision, value);
+                match formatted.split_once('e') {
+                    Some((mantissa, exponent)) => {
+                        let exponent: i32 = exponent.parse().unwrap_or(0);
+                        let sign = if exponent < 0 { '-' } else { '+' };
+                        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+                    }
+                    None => formatted,
+                }
+            }
+        }
+    }
+}
+
+struct Printer<'a> {
+    data: &'a [f64],
+    shape: &'a [i64],
+    strides: Vec<usize>,
+    summarize: bool,
+    options: &'a PrintOptions,
+}
+
+impl Printer<'_> {
+    fn visible_indices(&self, size: usize) -> Vec<Option<usize>> {
+        let edge = self.options.edgeitems;
+        if self.summarize && size > 2 * edge {
+            (0..edge)
+                .map(Some)
+                .chain(std::iter::once(None))
+                .chain((size - edge..size).map(Some))
+                .collect()
+        } else {
+            (0..size).map(Some).collect()
+        }
+    }
+
+    fn visible_values(&self, dim: usize, offset: usize, out: &mut Vec<f64>) {
+        if dim == self.shape.len() {
+            out.push(self.data[offset]);
+            return;
+        }
+        for index in self.visible_indices(self.shape[dim] as usize).into_iter().flatten() {
+            self.visible_values(dim + 1, offset + index * self.strides[dim], out);
+        }
+    }
+
+    fn format_dim(&self, dim: usize, offset: usize, indent: usize, format: &dyn Fn(f64) -> String) -> String {
+        let indices = self.visible_indices(self.shape[dim] as usize);
+
+        if dim == self.shape.len() - 1 {
+            let items: Vec<String> = indices
+                .iter()
+                .map(|index| match index {
+                    Some(i) => format(self.data[offset + i * self.strides[dim]]),
+                    None => "...".to_string(),
+                })
+                .collect();
+            let width = items.iter().map(|item| item.len()).max().unwrap_or(0);
+            let per_line = (self.options.linewidth.saturating_sub(indent) / (width + 2)).max(1);
+            let lines: Vec<String> = items.chunks(per_line).map(|chunk| chunk.join(", ")).collect();
+            return format!("[{}]", lines.join(&format!(",\n{}", " ".repeat(indent + 1))));
+        }
+
+        let separator = format!(
+            ",{}\n{}",
+            "\n".repeat(self.shape.len() - dim - 2),
+            " ".repeat(indent + 1)
+        );
+        let blocks: Vec<String> = indices
+            .iter()
+            .map(|index| match index {
+                Some(i) => self.format_dim(dim + 1, offset + i * self.strides[dim], indent + 1, format),
+                None => "...".to_string(),
+            })
+            .collect();
+        format!("[{}]", blocks.join(&separator))
+    }
+}
+
+impl Tensor {
+    fn format_data(&self, options: &PrintOptions) -> Result<String, String> {
+        let impl_ = self.impl_.as_ref().ok_or("undefined tensor")?;
+        let data = impl_.to_f64_list()?;
+        let shape = self.shape();
+
+        let mut strides = vec![1usize; shape.len()];
+        for d in (0..shape.len().saturating_sub(1)).rev() {
+            strides[d] = strides[d + 1] * shape[d + 1] as usize;
+        }
+        let printer = Printer {
+            data: &data,
+            shape: &shape,
+            strides,
+            summarize: data.len() > options.threshold,
+            options,
+        };
+
+        let mut visible = Vec::new();
+        printer.visible_values(0, 0, &mut visible);
+        let element_format = ElementFormat::new(self.dtype(), &visible, options);
+        let format = |value: f64| element_format.format(value);
+
+        if shape.is_empty() {
+            return Ok(format(data[0]));
+        }
+        if data.is_empty() {
+            return Ok("[]".to_string());
+        }
+
+        let width = visible.iter().map(|&v| format(v).len()).max().unwrap_or(0);
+        let padded = move |value: f64| format!("{:>width$}", format(value), width = width);
+        Ok(printer.format_dim(0, 0, PREFIX.len(), &padded))
+    }
+}
+
+impl fmt::Display for Tensor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.defined() {
+            return write!(f, "{}undefined)", PREFIX);
+        }
+
+        let options = get_printoptions();
+        let body = if self.is_meta() {
+            "...".to_string()
+        } else {
+            self.format_data(&options)
+                .unwrap_or_else(|_| "<data unavailable>".to_string())
+        };
+
+        let mut suffixes = Vec::new();
+        if self.is_meta() || (self.numel() == 0 && self.dim() != 1) {
+            suffixes.push(format!("size={:?}", self.shape()));
+        }
+        if !self.device().is_cpu() {
+            suffixes.push(format!("device={}", self.device()));
+        }
+        if !matches!(self.dtype(), DType::Float32 | DType::Int64 | DType::Bool) {
+            suffixes.push(format!("dtype={}", self.dtype()));
+        }
+        if let Some(grad_fn) = self.grad_fn() {
+            suffixes.push(format!("grad_fn=<{}>", grad_fn.name()));
+        } else if self.requires_grad() {
+            suffixes.push("requires_grad=True".to_string());
+        }
+
+        write!(f, "{}{}", PREFIX, body)?;
+        for suffix in suffixes {
+            write!(f, ", {}", suffix)?;
+        }
+        write!(f, ")")
+    }
+}
@@ -68,6 +68,75 @@ pub fn check_dtype_match<T: TypeToDType>(dtype: DType) -> Result<(), String> {
     }
 }
 
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) & 0x1) as u32;
+    let exp = ((bits >> 10) & 0x1f) as u32;
+    let mant = (bits & 0x3ff) as u32;
+
+    let out = if exp == 0 {
+        if mant == 0 {
+            sign << 31
+        } else {
+            // Subnormal half: renormalise into a regular single-precision value.
+            let mut e = 127 - 15 + 1;
+            let mut m = mant;
+            while m & 0x400 == 0 {
+                m <<= 1;
+                e -= 1;
+            }
+            (sign << 31) | (e << 23) | ((m & 0x3ff) << 13)
+        }
+    } else if exp == 0x1f {
+        (sign << 31) | (0xff << 23) | (mant << 13)
+    } else {
+        (sign << 31) | ((exp + 127 - 15) << 23) | (mant << 13)
+    };
+    f32::from_bits(out)
+}
+
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        let nan_bit = if mant != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan_bit;
+    }
+
+    let half_exp = exp - 127 + 15;
+    if half_exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exp <= 0 {
+        if half_exp < -10 {
+            return sign;
+        }
+        let m = mant | 0x80_0000;
+        let shift = (14 - half_exp) as u32;
+        let round = (m >> (shift - 1)) & 1;
+        return sign | (((m >> shift) + round) as u16);
+    }
+
+    let round = (mant >> 12) & 1;
+    let out = ((half_exp as u32) << 10) | (mant >> 13);
+    sign | ((out + round) as u16)
+}
+
+pub fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+pub fn f32_to_bf16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        return ((bits >> 16) | 0x40) as u16;
+    }
+    let rounding_bias = 0x7fff + ((bits >> 16) & 1);
+    ((bits + rounding_bias) >> 16) as u16
+}
+
 pub type Array1d<T> = Vec<T>;
 pub type Array2d<T> = Vec<Vec<T>>;
 pub type Array3d<T> = Vec<Vec<Vec<T>>>;
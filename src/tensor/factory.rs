@@ -0,0 +1,165 @@
+use crate::tensor::{DType, FillValue, Options, Tensor, TensorImpl};
+use std::rc::Rc;
+
+impl Tensor {
+    pub fn full<V: FillValue>(shape: &[i64], value: V) -> Self {
+        Self::full_with_options(shape, value, Options::default())
+    }
+
+    pub fn full_with_options<V: FillValue>(shape: &[i64], value: V, options: Options) -> Self {
+        if shape.iter().any(|&dim| dim < 0) {
+            return Self::new();
+        }
+        let numel: usize = shape.iter().product::<i64>() as usize;
+        if options.dtype != DType::Int64 {
+            return Self::from_f64_data(&vec![value.to_f64(); numel], shape, options);
+        }
+        match TensorImpl::new_from_data(&vec![value.to_i64(); numel], shape, options) {
+            Ok(impl_) => Self::new_from_impl(Rc::new(impl_)),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn arange(start: f64, end: f64, step: f64) -> Self {
+        Self::arange_with_options(start, end, step, Options::default())
+    }
+
+    pub fn arange_with_options(start: f64, end: f64, step: f64, options: Options) -> Self {
+        if step == 0.0 || !start.is_finite() || !end.is_finite() || !step.is_finite() {
+            return Self::new();
+        }
+        let steps = ((end - start) / step).ceil().max(0.0) as usize;
+        let data: Vec<f64> = (0..steps).map(|i| start + i as f64 * step).collect();
+        Self::from_f64_data(&data, &[steps as i64], options)
+    }
+
+    pub fn linspace_with_options(start: f64, end: f64, steps: usize, options: Options) -> Self {
+        let step_size = if steps > 1 { (end - start) / (steps - 1) as f64 } else { 0.0 };
+        let data: Vec<f64> = (0..steps)
+            .map(|i| start + i as f64 * step_size)
+            .collect();
+        Self::from_f64_data(&data, &[steps as i64], options)
+    }
+
+    pub fn logspace(start: f64, end: f64, steps: usize, base: f64) -> Self {
+        Self::logspace_with_options(start, end, steps, base, Options::default())
+    }
+
+    pub fn logspace_with_options(
+        start: f64,
+        end: f64,
+        steps: usize,
+        base: f64,
+        options: Options,
+    ) -> Self {
+        let step_size = if steps > 1 { (end - start) / (steps - 1) as f64 } else { 0.0 };
+        let data: Vec<f64> = (0..steps)
+            .map(|i| base.powf(start + i as f64 * step_size))
+            .collect();
+        Self::from_f64_data(&data, &[steps as i64], options)
+    }
+
+    pub fn eye(n: i64) -> Self {
+        Self::eye_with_options(n, n, Options::default())
+    }
+
+    pub fn eye_with_options(n: i64, m: i64, options: Options) -> Self {
+        if n < 0 || m < 0 {
+            return Self::new();
+        }
+        let mut data = vec![0.0; (n * m) as usize];
+        for i in 0..n.min(m) {
+            data[(i * m + i) as usize] = 1.0;
+        }
+        Self::from_f64_data(&data, &[n, m], options)
+    }
+
+    pub fn empty_like(other: &Self) -> Self {
+        match other.impl_.as_ref() {
+            Some(impl_) => Self::empty_with_options(impl_.shape(), impl_.options().no_grad()),
+            None => Self::new(),
+        }
+    }
+
+    pub fn full_like<V: FillValue>(other: &Self, value: V) -> Self {
+        match other.impl_.as_ref() {
+            Some(impl_) => Self::full_with_options(impl_.shape(), value, impl_.options().no_grad()),
+            None => Self::new(),
+        }
+    }
+
+    pub fn tril(&self, diagonal: i64) -> Self {
+        self.triangular(move |row, col| col - row <= diagonal)
+            .with_grad_fn("TrilBackward0", &[self], move |grad| vec![grad.tril(diagonal)])
+    }
+
+    pub fn triu(&self, diagonal: i64) -> Self {
+        self.triangular(move |row, col| col - row >= diagonal)
+            .with_grad_fn("TriuBackward0", &[self], move |grad| vec![grad.triu(diagonal)])
+    }
+
+    pub fn meshgrid(tensors: &[Self], indexing: &str) -> Vec<Self> {
+        if indexing != "ij" && indexing != "xy" {
+            return Vec::new();
+        }
+        if tensors.iter().any(|t| !t.defined() || t.dim() > 1) {
+            return Vec::new();
+        }
+
+        let mut shape: Vec<i64> = tensors.iter().map(|t| t.numel()).collect();
+        let swap = indexing == "xy" && shape.len() >= 2;
+        if swap {
+            shape.swap(0, 1);
+        }
+        let numel: usize = shape.iter().product::<i64>() as usize;
+
+        let mut grids = Vec::with_capacity(tensors.len());
+        for (i, tensor) in tensors.iter().enumerate() {
+            let values = match tensor.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+                Some(Ok(data)) => data,
+                _ => return Vec::new(),
+            };
+            let dim = match (swap, i) {
+                (true, 0) => 1,
+                (true, 1) => 0,
+                _ => i,
+            };
+            let inner: i64 = shape[dim + 1..].iter().product();
+            let data: Vec<f64> = (0..numel as i64)
+                .map(|flat| values[((flat / inner) % shape[dim]) as usize])
+                .collect();
+
+            let options = Options::default().dtype(tensor.dtype()).device(tensor.device());
+            let grid = Self::from_f64_data(&data, &shape, options);
+            if !grid.defined() {
+                return Vec::new();
+            }
+            grids.push(grid);
+        }
+        grids
+    }
+
+    fn triangular<F: Fn(i64, i64) -> bool>(&self, keep: F) -> Self {
+        if !self.defined() || self.dim() < 2 {
+            return Self::new();
+        }
+
+        let shape = self.shape();
+        let rows = shape[shape.len() - 2];
+        let cols = shape[shape.len() - 1];
+        let dropped: Vec<f64> = (0..rows * cols)
+            .map(|flat| if keep(flat / cols, flat % cols) { 0.0 } else { 1.0 })
+            .collect();
+        let mask = Self::from_f64_data(&dropped, &[rows, cols], Options::default().dtype(DType::Bool).device(self.device()));
+        self.detach().masked_fill(&mask, 0.0)
+    }
+
+    pub(crate) fn from_f64_data(data: &[f64], shape: &[i64], options: Options) -> Self {
+        match TensorImpl::new_from_f64(data, shape, options) {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use rand::RngCore;
+use std::cell::RefCell;
+
+pub const DEFAULT_SEED: u64 = 67280421310721;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generator {
+    initial_seed: u64,
+    state: [u64; 4],
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut generator = Self {
+            initial_seed: seed,
+            state: [0; 4],
+        };
+        generator.manual_seed(seed);
+        generator
+    }
+
+    pub fn manual_seed(&mut self, seed: u64) -> &mut Self {
+        let mut sm = seed;
+        for word in self.state.iter_mut() {
+            *word = splitmix64(&mut sm);
+        }
+        self.initial_seed = seed;
+        self
+    }
+
+    pub fn seed(&mut self) -> u64 {
+        let seed = rand::random::<u64>();
+        self.manual_seed(seed);
+        seed
+    }
+
+    pub fn initial_seed(&self) -> u64 {
+        self.initial_seed
+    }
+
+    pub fn get_state(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(40);
+        bytes.extend_from_slice(&self.initial_seed.to_le_bytes());
+        for word in &self.state {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn set_state(&mut self, state: &[u8]) -> Result<(), String> {
+        if state.len() != 40 {
+            return Err(format!(
+                "Invalid generator state: expected 40 bytes, got {}",
+                state.len()
+            ));
+        }
+
+        let word = |i: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&state[i * 8..(i + 1) * 8]);
+            u64::from_le_bytes(bytes)
+        };
+
+        let words = [word(1), word(2), word(3), word(4)];
+        if words.iter().all(|&w| w == 0) {
+            return Err("Invalid generator state: all-zero state".to_string());
+        }
+
+        self.initial_seed = word(0);
+        self.state = words;
+        Ok(())
+    }
+
+    fn step(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for Generator {
+    fn next_u32(&mut self) -> u32 {
+        (self.step() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.step().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+thread_local! {
+    static DEFAULT_GENERATOR: RefCell<Generator> = RefCell::new(Generator::new());
+}
+
+pub fn with_default_generator<R>(f: impl FnOnce(&mut Generator) -> R) -> R {
+    DEFAULT_GENERATOR.with(|generator| f(&mut generator.borrow_mut()))
+}
+
+pub fn with_generator<R>(
+    generator: Option<&mut Generator>,
+    f: impl FnOnce(&mut Generator) -> R,
+) -> R {
+    match generator {
+        Some(generator) => f(generator),
+        None => with_default_generator(f),
+    }
+}
+
+pub fn manual_seed(seed: u64) {
+    with_default_generator(|generator| {
+        generator.manual_seed(seed);
+    });
+}
+
+pub fn seed() -> u64 {
+    with_default_generator(|generator| generator.seed())
+}
+
+pub fn initial_seed() -> u64 {
+    with_default_generator(|generator| generator.initial_seed())
+}
+
+pub fn get_rng_state() -> Vec<u8> {
+    with_default_generator(|generator| generator.get_state())
+}
+
+pub fn set_rng_state(state: &[u8]) -> Result<(), String> {
+    with_default_generator(|generator| generator.set_state(state))
+}
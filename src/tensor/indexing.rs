@@ -0,0 +1,517 @@
+use crate::tensor::{backend, dispatch_device, DType, Options, Tensor, TensorIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScatterReduce {
+    Sum,
+    Mean,
+    Amax,
+    Amin,
+    Prod,
+}
+
+impl ScatterReduce {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Self::Sum),
+            "mean" => Some(Self::Mean),
+            "amax" => Some(Self::Amax),
+            "amin" => Some(Self::Amin),
+            "prod" => Some(Self::Prod),
+            _ => None,
+        }
+    }
+}
+
+impl Tensor {
+    pub fn gather(&self, dim: i64, index: &Tensor) -> Self {
+        let result = match dispatch_device(&[self, index]).map(backend) {
+            Some(Ok(backend)) => (backend.kernels().gather)(self, dim, index),
+            _ => Self::new(),
+        };
+
+        let (shape, index) = (self.shape(), index.detach());
+        result.with_grad_fn("GatherBackward0", &[self], move |grad| {
+            let zeros = Tensor::zeros_with_options(&shape, Options::default().dtype(grad.dtype()));
+            vec![zeros.scatter_add(dim, &index, grad)]
+        })
+    }
+
+    pub fn scatter(&self, dim: i64, index: &Tensor, src: &Tensor) -> Self {
+        self.scatter_with(dim, index, src, "ScatterBackward0", None, true)
+    }
+
+    pub fn scatter_add(&self, dim: i64, index: &Tensor, src: &Tensor) -> Self {
+        self.scatter_with(dim, index, src, "ScatterAddBackward0", Some(ScatterReduce::Sum), true)
+    }
+
+    pub fn scatter_reduce(
+        &self,
+        dim: i64,
+        index: &Tensor,
+        src: &Tensor,
+        reduce: &str,
+        include_self: bool,
+    ) -> Self {
+        match ScatterReduce::from_name(reduce) {
+            Some(reduce) => {
+                self.scatter_with(dim, index, src, "ScatterReduceBackward0", Some(reduce), include_self)
+            }
+            None => Self::new(),
+        }
+    }
+
+    pub fn index_select(&self, dim: i64, index: &Tensor) -> Self {
+        let result = match dispatch_device(&[self, index]).map(backend) {
+            Some(Ok(backend)) => (backend.kernels().index_select)(self, dim, index),
+            _ => Self::new(),
+        };
+
+        let (shape, index) = (self.shape(), index.detach());
+        result.with_grad_fn("IndexSelectBackward0", &[self], move |grad| {
+            let zeros = Tensor::zeros_with_options(&shape, Options::default().dtype(grad.dtype()));
+            vec![zeros.index_add(dim, &index, grad, 1.0)]
+        })
+    }
+
+    pub fn index_add(&self, dim: i64, index: &Tensor, source: &Tensor, alpha: f64) -> Self {
+        let result = self.index_update(dim, index, source, |current, value| current + alpha * value);
+        let index = index.detach();
+        result.with_grad_fn("IndexAddBackward0", &[self, source], move |grad| {
+            vec![Clone::clone(grad), &grad.index_select(dim, &index) * alpha]
+        })
+    }
+
+    pub fn index_copy(&self, dim: i64, index: &Tensor, source: &Tensor) -> Self {
+        let result = self.index_update(dim, index, source, |_, value| value);
+        let index = index.detach();
+        let source_shape = source.shape();
+        result.with_grad_fn("IndexCopyBackward0", &[self, source], move |grad| {
+            let zeros = Tensor::zeros_with_options(&source_shape, Options::default().dtype(grad.dtype()));
+            vec![grad.index_copy(dim, &index, &zeros), grad.index_select(dim, &index)]
+        })
+    }
+
+    pub fn take(&self, index: &Tensor) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+        let data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        let (indices, index_shape) = match index_values(index) {
+            Some(index) => index,
+            None => return Self::new(),
+        };
+
+        let numel = data.len() as i64;
+        let mut flat = Vec::with_capacity(indices.len());
+        for &i in &indices {
+            let i = if i < 0 { i + numel } else { i };
+            if i < 0 || i >= numel {
+                return Self::new();
+            }
+            flat.push(i);
+        }
+
+        let result: Vec<f64> = flat.iter().map(|&i| data[i as usize]).collect();
+        let shape = self.shape();
+        let flat_index = Tensor::from_vec(flat, &[indices.len() as i64]);
+        self.indexing_output(&result, &index_shape)
+            .with_grad_fn("TakeBackward0", &[self], move |grad| {
+                let zeros = Tensor::zeros_with_options(&[numel], Options::default().dtype(grad.dtype()));
+                vec![zeros.scatter_add(0, &flat_index, &grad.reshape(&[-1])).reshape(&shape)]
+            })
+    }
+
+    pub fn take_along_dim(&self, indices: &Tensor, dim: Option<i64>) -> Self {
+        let dim = match dim {
+            Some(dim) => dim,
+            None => return self.reshape(&[-1]).take(&indices.reshape(&[-1])),
+        };
+        if !self.defined() || !indices.defined() || self.dim() != indices.dim() {
+            return Self::new();
+        }
+        let d = match normalize_dim(dim, self.dim()) {
+            Some(d) => d,
+            None => return Self::new(),
+        };
+
+        let mut self_shape = self.shape();
+        let mut index_shape = indices.shape();
+        for i in 0..self_shape.len() {
+            if i == d {
+                continue;
+            }
+            let size = match (self_shape[i], index_shape[i]) {
+                (a, b) if a == b || b == 1 => a,
+                (1, b) => b,
+                _ => return Self::new(),
+            };
+            self_shape[i] = size;
+            index_shape[i] = size;
+        }
+        self.expand(&self_shape).gather(dim, &indices.expand(&index_shape))
+    }
+
+    pub(crate) fn gather_cpu(&self, dim: i64, index: &Tensor) -> Self {
+        let (data, shape, dim) = match self.indexing_input(dim) {
+            Some(input) => input,
+            None => return Self::new(),
+        };
+        let (indices, index_shape) = match index_values(index) {
+            Some(index) => index,
+            None => return Self::new(),
+        };
+        match scatter_offsets(&shape, dim, &indices, &index_shape) {
+            Ok(offsets) => self.take_offsets(&data, &offsets, &index_shape),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub(crate) fn index_select_cpu(&self, dim: i64, index: &Tensor) -> Self {
+        let (data, shape, dim) = match self.indexing_input(dim) {
+            Some(input) => input,
+            None => return Self::new(),
+        };
+        let indices = match index_vector(index) {
+            Some(indices) => indices,
+            None => return Self::new(),
+        };
+        match select_offsets(&shape, dim, &indices) {
+            Ok((offsets, selected_shape)) => self.take_offsets(&data, &offsets, &selected_shape),
+            Err(_) => Self::new(),
+        }
+    }
+
+    fn scatter_with(
+        &self,
+        dim: i64,
+        index: &Tensor,
+        src: &Tensor,
+        name: &'static str,
+        reduce: Option<ScatterReduce>,
+        include_self: bool,
+    ) -> Self {
+        let (data, shape, d) = match self.indexing_input(dim) {
+            Some(input) => input,
+            None => return Self::new(),
+        };
+        let (indices, index_shape) = match index_values(index) {
+            Some(index) => index,
+            None => return Self::new(),
+        };
+        let src_data = match src.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        let targets = match scatter_offsets(&shape, d, &indices, &index_shape) {
+            Ok(offsets) => offsets,
+            Err(_) => return Self::new(),
+        };
+        let sources = match aligned_offsets(&src.shape(), &index_shape) {
+            Ok(offsets) => offsets,
+            Err(_) => return Self::new(),
+        };
+
+        let mut result = data.clone();
+        let mut counts = vec![if include_self { 1usize } else { 0 }; result.len()];
+        for (&target, &source) in targets.iter().zip(sources.iter()) {
+            let value = src_data[source];
+            result[target] = match reduce {
+                None => value,
+                Some(_) if counts[target] == 0 => value,
+                Some(ScatterReduce::Sum) | Some(ScatterReduce::Mean) => result[target] + value,
+                Some(ScatterReduce::Amax) => result[target].max(value),
+                Some(ScatterReduce::Amin) => result[target].min(value),
+                Some(ScatterReduce::Prod) => result[target] * value,
+            };
+            counts[target] += 1;
+        }
+        if reduce == Some(ScatterReduce::Mean) {
+            for (value, &count) in result.iter_mut().zip(counts.iter()) {
+                if count > 1 {
+                    *value /= count as f64;
+                }
+            }
+        }
+
+        let output = self.indexing_output(&result, &shape);
+        let (input, source, out) = (self.detach(), src.detach(), output.detach());
+        output.with_grad_fn(name, &[self, src], move |grad| {
+            let grad_data = grad.to_list_f64();
+            let out_data = out.to_list_f64();
+            let input_data = input.to_list_f64();
+            let src_data = source.to_list_f64();
+
+            let mut grad_self = grad_data.clone();
+            let mut grad_src = vec![0.0; src_data.len()];
+            let scattered: std::collections::HashSet<usize> = targets.iter().copied().collect();
+
+            match reduce {
+                None => {
+                    for &target in &scattered {
+                        grad_self[target] = 0.0;
+                    }
+                    let mut last = std::collections::HashMap::new();
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        last.insert(target, source);
+                    }
+                    for (&target, &source) in &last {
+                        grad_src[source] = grad_data[target];
+                    }
+                }
+                Some(ScatterReduce::Sum) => {
+                    if !include_self {
+                        for &target in &scattered {
+                            grad_self[target] = 0.0;
+                        }
+                    }
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        grad_src[source] = grad_data[target];
+                    }
+                }
+                Some(ScatterReduce::Mean) => {
+                    for &target in &scattered {
+                        let count = counts[target] as f64;
+                        grad_self[target] = if include_self { grad_data[target] / count } else { 0.0 };
+                    }
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        grad_src[source] = grad_data[target] / counts[target] as f64;
+                    }
+                }
+                Some(ScatterReduce::Amax) | Some(ScatterReduce::Amin) => {
+                    let mut ties = vec![0usize; out_data.len()];
+                    for &target in &scattered {
+                        if include_self && input_data[target] == out_data[target] {
+                            ties[target] += 1;
+                        }
+                    }
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        if src_data[source] == out_data[target] {
+                            ties[target] += 1;
+                        }
+                    }
+                    for &target in &scattered {
+                        let selected = include_self && input_data[target] == out_data[target];
+                        grad_self[target] = if selected { grad_data[target] / ties[target] as f64 } else { 0.0 };
+                    }
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        if src_data[source] == out_data[target] {
+                            grad_src[source] = grad_data[target] / ties[target] as f64;
+                        }
+                    }
+                }
+                Some(ScatterReduce::Prod) => {
+                    let mut groups: Vec<Vec<usize>> = vec![Vec::new(); out_data.len()];
+                    for (&target, &source) in targets.iter().zip(sources.iter()) {
+                        groups[target].push(source);
+                    }
+                    for &target in &scattered {
+                        let values: Vec<f64> = groups[target].iter().map(|&source| src_data[source]).collect();
+                        grad_self[target] = if include_self {
+                            grad_data[target] * values.iter().product::<f64>()
+                        } else {
+                            0.0
+                        };
+                        let base = if include_self { input_data[target] } else { 1.0 };
+                        for (&source, others) in groups[target].iter().zip(products_excluding_each(&values)) {
+                            grad_src[source] = grad_data[target] * base * others;
+                        }
+                    }
+                }
+            }
+
+            let options = Options::default().dtype(grad.dtype());
+            vec![
+                Tensor::from_f64_data(&grad_self, &input.shape(), options.clone()),
+                Tensor::from_f64_data(&grad_src, &source.shape(), options),
+            ]
+        })
+    }
+
+    fn index_update<F: Fn(f64, f64) -> f64>(&self, dim: i64, index: &Tensor, source: &Tensor, update: F) -> Self {
+        let (data, shape, d) = match self.indexing_input(dim) {
+            Some(input) => input,
+            None => return Self::new(),
+        };
+        let indices = match index_vector(index) {
+            Some(indices) => indices,
+            None => return Self::new(),
+        };
+        let (offsets, selected_shape) = match select_offsets(&shape, d, &indices) {
+            Ok(selected) => selected,
+            Err(_) => return Self::new(),
+        };
+        if source.shape() != selected_shape {
+            return Self::new();
+        }
+        let source_data = match source.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+
+        let mut result = data;
+        for (&offset, &value) in offsets.iter().zip(source_data.iter()) {
+            result[offset] = update(result[offset], value);
+        }
+        self.indexing_output(&result, &shape)
+    }
+
+    fn indexing_input(&self, dim: i64) -> Option<(Vec<f64>, Vec<i64>, usize)> {
+        let impl_ = self.impl_.as_ref()?;
+        let data = impl_.to_f64_list().ok()?;
+        let shape = self.shape();
+        let dim = normalize_dim(dim, shape.len() as i64)?;
+        Some((data, shape, dim))
+    }
+
+    fn indexing_output(&self, data: &[f64], shape: &[i64]) -> Self {
+        let options = Options::default().dtype(self.dtype()).device(self.device());
+        Self::from_f64_data(data, shape, options)
+    }
+
+    fn take_offsets(&self, data: &[f64], offsets: &[usize], shape: &[i64]) -> Self {
+        if self.dtype() == DType::Int64 {
+            let values = self.to_list::<i64>();
+            let taken: Vec<i64> = offsets.iter().map(|&offset| values[offset]).collect();
+            return Self::from_vec(taken, shape);
+        }
+        let result: Vec<f64> = offsets.iter().map(|&offset| data[offset]).collect();
+        self.indexing_output(&result, shape)
+    }
+
+    fn to_list_f64(&self) -> Vec<f64> {
+        match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn normalize_dim(dim: i64, ndim: i64) -> Option<usize> {
+    let dim = if dim < 0 { dim + ndim.max(1) } else { dim };
+    if dim < 0 || dim >= ndim.max(1) {
+        return None;
+    }
+    Some(dim as usize)
+}
+
+fn index_values(index: &Tensor) -> Option<(Vec<i64>, Vec<i64>)> {
+    let impl_ = index.impl_.as_ref()?;
+    if !matches!(impl_.dtype(), DType::Int64 | DType::Int32) {
+        return None;
+    }
+    let values = impl_.to_f64_list().ok()?;
+    Some((values.iter().map(|&v| v as i64).collect(), index.shape()))
+}
+
+fn index_vector(index: &Tensor) -> Option<Vec<i64>> {
+    let (values, shape) = index_values(index)?;
+    if shape.len() > 1 {
+        return None;
+    }
+    Some(values)
+}
+
+fn unravel(mut flat: usize, shape: &[i64], coords: &mut [i64]) {
+    for d in (0..shape.len()).rev() {
+        let size = shape[d].max(1) as usize;
+        coords[d] = (flat % size) as i64;
+        flat /= size;
+    }
+}
+
+fn scatter_offsets(shape: &[i64], dim: usize, indices: &[i64], index_shape: &[i64]) -> Result<Vec<usize>, String> {
+    if index_shape.len() != shape.len().max(1) && !(shape.is_empty() && index_shape.is_empty()) {
+        return Err("Index tensor must have the same number of dimensions as input".to_string());
+    }
+    for (d, (&size, &index_size)) in shape.iter().zip(index_shape.iter()).enumerate() {
+        if d != dim && index_size > size {
+            return Err(format!("Index size {} exceeds input size {} in dimension {}", index_size, size, d));
+        }
+    }
+
+    let strides = TensorIterator::contiguous_strides(shape);
+    let dim_size = shape.get(dim).copied().unwrap_or(1);
+    let mut coords = vec![0i64; index_shape.len()];
+    let mut offsets = Vec::with_capacity(indices.len());
+    for (flat, &index) in indices.iter().enumerate() {
+        if index < 0 || index >= dim_size {
+            return Err(format!("Index {} is out of bounds for dimension {} with size {}", index, dim, dim_size));
+        }
+        unravel(flat, index_shape, &mut coords);
+        let offset: i64 = coords
+            .iter()
+            .zip(strides.iter())
+            .enumerate()
+            .map(|(d, (&c, &s))| if d == dim { index * s } else { c * s })
+            .sum();
+        offsets.push(offset as usize);
+    }
+    Ok(offsets)
+}
+
+fn aligned_offsets(shape: &[i64], index_shape: &[i64]) -> Result<Vec<usize>, String> {
+    if shape.len() != index_shape.len() || index_shape.iter().zip(shape.iter()).any(|(&i, &s)| i > s) {
+        return Err(format!("Index shape {:?} does not fit source shape {:?}", index_shape, shape));
+    }
+
+    let strides = TensorIterator::contiguous_strides(shape);
+    let numel: i64 = index_shape.iter().product();
+    let mut coords = vec![0i64; index_shape.len()];
+    Ok((0..numel as usize)
+        .map(|flat| {
+            unravel(flat, index_shape, &mut coords);
+            coords.iter().zip(strides.iter()).map(|(&c, &s)| c * s).sum::<i64>() as usize
+        })
+        .collect())
+}
+
+fn select_offsets(shape: &[i64], dim: usize, indices: &[i64]) -> Result<(Vec<usize>, Vec<i64>), String> {
+    if shape.is_empty() {
+        return Err("Cannot index a scalar tensor along a dimension".to_string());
+    }
+    let dim_size = shape[dim];
+    let mut resolved = Vec::with_capacity(indices.len());
+    for &index in indices {
+        let index = if index < 0 { index + dim_size } else { index };
+        if index < 0 || index >= dim_size {
+            return Err(format!("Index {} is out of bounds for dimension {} with size {}", index, dim, dim_size));
+        }
+        resolved.push(index);
+    }
+
+    let mut selected_shape = shape.to_vec();
+    selected_shape[dim] = resolved.len() as i64;
+    let strides = TensorIterator::contiguous_strides(shape);
+    let numel: i64 = selected_shape.iter().product();
+    let mut coords = vec![0i64; selected_shape.len()];
+    let offsets = (0..numel as usize)
+        .map(|flat| {
+            unravel(flat, &selected_shape, &mut coords);
+            coords
+                .iter()
+                .zip(strides.iter())
+                .enumerate()
+                .map(|(d, (&c, &s))| if d == dim { resolved[c as usize] * s } else { c * s })
+                .sum::<i64>() as usize
+        })
+        .collect();
+    Ok((offsets, selected_shape))
+}
+
+fn products_excluding_each(values: &[f64]) -> Vec<f64> {
+    let mut excluded = vec![1.0; values.len()];
+    let mut prefix = 1.0;
+    for (slot, &value) in excluded.iter_mut().zip(values) {
+        *slot = prefix;
+        prefix *= value;
+    }
+    let mut suffix = 1.0;
+    for (slot, &value) in excluded.iter_mut().zip(values).rev() {
+        *slot *= suffix;
+        suffix *= value;
+    }
+    excluded
+}
@@ -0,0 +1,186 @@
+use crate::tensor::{
+    check_dtype_match, DType, MapMode, Options, Storage, Tensor, TensorImpl, TypeToDType,
+};
+use ndarray::{ArrayD, ArrayViewD, IxDyn, ShapeBuilder};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub struct BorrowedTensor<'a> {
+    tensor: Tensor,
+    alive: Arc<AtomicBool>,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl BorrowedTensor<'_> {
+    pub fn defined(&self) -> bool {
+        self.tensor.defined()
+    }
+
+    pub fn shape(&self) -> Vec<i64> {
+        self.tensor.shape()
+    }
+
+    pub fn dtype(&self) -> DType {
+        self.tensor.dtype()
+    }
+
+    pub fn numel(&self) -> i64 {
+        self.tensor.numel()
+    }
+
+    pub fn to_list<T: TypeToDType + Clone + Default>(&self) -> Vec<T> {
+        self.tensor.to_list()
+    }
+
+    pub fn as_array_view<T: TypeToDType>(&self) -> Result<ArrayViewD<'_, T>, String> {
+        self.tensor.as_array_view()
+    }
+
+    pub fn to_tensor(&self) -> Tensor {
+        self.tensor.clone()
+    }
+}
+
+impl Drop for BorrowedTensor<'_> {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::Release);
+    }
+}
+
+impl Tensor {
+    pub fn from_vec<T: TypeToDType + Clone>(data: Vec<T>, shape: &[i64]) -> Self {
+        if shape.iter().any(|&dim| dim < 0) || shape.iter().product::<i64>() as usize != data.len() {
+            return Self::new();
+        }
+
+        let options = Options::default().dtype(T::DTYPE);
+        let impl_ = Storage::from_vec(data)
+            .and_then(|storage| TensorImpl::new_with_storage(shape, options, Rc::new(storage), 0));
+        match impl_ {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn from_slice<T: TypeToDType + Clone>(data: &[T], shape: &[i64]) -> Self {
+        let options = Options::default().dtype(T::DTYPE);
+        match TensorImpl::new_from_data(data, shape, options) {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn from_storage(storage: Rc<Storage>, offset: i64, shape: &[i64], dtype: DType) -> Self {
+        if offset < 0 || shape.iter().any(|&dim| dim < 0) || storage.is_released() {
+            return Self::new();
+        }
+
+        let numel: i64 = shape.iter().product();
+        let end = (offset + numel) as usize * dtype.size();
+        let ptr = storage.data_ptr::<u8>().wrapping_add(offset as usize * dtype.size());
+        if end > storage.size() || !(ptr as usize).is_multiple_of(dtype.size()) {
+            return Self::new();
+        }
+
+        let options = Options::default().dtype(dtype).device(storage.device());
+        match TensorImpl::new_with_storage(shape, options, storage, offset) {
+            Ok(impl_) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn from_borrowed<'a>(data: &'a [u8], offset: i64, shape: &[i64], dtype: DType) -> BorrowedTensor<'a> {
+        let alive = Arc::new(AtomicBool::new(true));
+        let tensor = match Storage::borrowed(data, Some(Arc::clone(&alive))) {
+            Ok(storage) => Self::from_storage(Rc::new(storage), offset, shape, dtype),
+            Err(_) => Self::new(),
+        };
+        BorrowedTensor {
+            tensor,
+            alive,
+            _data: PhantomData,
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        offset: u64,
+        shape: &[i64],
+        dtype: DType,
+        mode: MapMode,
+    ) -> Self {
+        if shape.iter().any(|&dim| dim < 0) {
+            return Self::new();
+        }
+
+        let size = shape.iter().product::<i64>() as usize * dtype.size();
+        if size == 0 {
+            return Self::empty_with_options(shape, Options::default().dtype(dtype));
+        }
+        match Storage::from_file(path, offset, Some(size), mode) {
+            Ok(storage) => Self::from_storage(Rc::new(storage), 0, shape, dtype),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn to_ndarray<T: TypeToDType + Clone>(&self) -> Result<ArrayD<T>, String> {
+        let impl_ = self.impl_.as_ref().ok_or("Cannot convert undefined tensor")?;
+        check_dtype_match::<T>(impl_.dtype())?;
+        if !impl_.device().is_cpu() {
+            return Err("Only CPU tensors can be converted to ndarray".to_string());
+        }
+
+        let shape: Vec<usize> = impl_.shape().iter().map(|&dim| dim as usize).collect();
+        ArrayD::from_shape_vec(IxDyn(&shape), impl_.gather_elements::<T>())
+            .map_err(|e| format!("Failed to build ndarray: {}", e))
+    }
+
+    pub fn as_array_view<T: TypeToDType>(&self) -> Result<ArrayViewD<'_, T>, String> {
+        let impl_ = self.impl_.as_ref().ok_or("Cannot view undefined tensor")?;
+        check_dtype_match::<T>(impl_.dtype())?;
+        if !impl_.device().is_cpu() {
+            return Err("Only CPU tensors can be viewed as ndarray".to_string());
+        }
+        if impl_.strides().iter().any(|&stride| stride < 0) {
+            return Err("Negative strides cannot be viewed as ndarray".to_string());
+        }
+
+        let ptr = impl_.data_ptr::<T>() as *const T;
+        if ptr.is_null() || !ptr.is_aligned() {
+            return Err("Tensor data is not addressable as the requested type".to_string());
+        }
+
+        let shape: Vec<usize> = impl_.shape().iter().map(|&dim| dim as usize).collect();
+        let strides: Vec<usize> = impl_.strides().iter().map(|&stride| stride as usize).collect();
+        unsafe { Ok(ArrayViewD::from_shape_ptr(IxDyn(&shape).strides(IxDyn(&strides)), ptr)) }
+    }
+}
+
+impl<T: TypeToDType + Clone> From<ArrayD<T>> for Tensor {
+    fn from(array: ArrayD<T>) -> Self {
+        let shape: Vec<i64> = array.shape().iter().map(|&dim| dim as i64).collect();
+        if !array.is_standard_layout() {
+            let data: Vec<T> = array.iter().cloned().collect();
+            return Self::from_vec(data, &shape);
+        }
+
+        let start = array.as_ptr();
+        let len = array.len();
+        let data = array.into_raw_vec();
+        if data.as_ptr() == start && data.len() == len {
+            return Self::from_vec(data, &shape);
+        }
+
+        let offset = (start as usize - data.as_ptr() as usize) / std::mem::size_of::<T>().max(1);
+        Self::from_slice(&data[offset..offset + len], &shape)
+    }
+}
@@ -0,0 +1,601 @@
+use crate::tensor::broadcasting::broadcast_shapes;
+use crate::tensor::{
+    backend, bf16_to_f32, dispatch_device, f16_to_f32, f32_to_bf16, f32_to_f16, meta_binary_shape, meta_shape_rule,
+    BinaryOp, DType, Options, Tensor, TensorImpl, UnaryOp,
+};
+use std::rc::Rc;
+
+#[derive(Debug, Clone)]
+pub struct TensorIterator {
+    shape: Vec<usize>,
+    strides: Vec<Vec<isize>>,
+    numel: usize,
+}
+
+impl TensorIterator {
+    pub fn new(shape: &[i64], operand_strides: &[Vec<i64>]) -> Result<Self, String> {
+        for strides in operand_strides {
+            if strides.len() != shape.len() {
+                return Err(format!(
+                    "Operand strides {:?} don't match iteration shape {:?}",
+                    strides, shape
+                ));
+            }
+        }
+
+        let numel = shape.iter().product::<i64>().max(0) as usize;
+        let mut iter = Self {
+            shape: shape.iter().map(|&d| d as usize).collect(),
+            strides: operand_strides
+                .iter()
+                .map(|s| s.iter().map(|&v| v as isize).collect())
+                .collect(),
+            numel,
+        };
+        iter.coalesce();
+        Ok(iter)
+    }
+
+    pub fn broadcast_strides(
+        from_shape: &[i64],
+        from_strides: &[i64],
+        to_shape: &[i64],
+    ) -> Result<Vec<i64>, String> {
+        if from_shape.len() > to_shape.len() {
+            return Err(format!(
+                "Cannot broadcast shape {:?} to {:?}",
+                from_shape, to_shape
+            ));
+        }
+
+        let offset = to_shape.len() - from_shape.len();
+        let mut strides = vec![0i64; to_shape.len()];
+        for (i, (&dim, &stride)) in from_shape.iter().zip(from_strides.iter()).enumerate() {
+            let target = to_shape[offset + i];
+            if dim == target {
+                strides[offset + i] = if dim == 1 { 0 } else { stride };
+            } else if dim == 1 {
+                strides[offset + i] = 0;
+            } else {
+                return Err(format!(
+                    "Cannot broadcast shape {:?} to {:?}",
+                    from_shape, to_shape
+                ));
+            }
+        }
+        Ok(strides)
+    }
+
+    pub fn contiguous_strides(shape: &[i64]) -> Vec<i64> {
+        let mut strides = vec![1i64; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1].max(1);
+        }
+        strides
+    }
+
+    pub fn numel(&self) -> usize {
+        self.numel
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn num_operands(&self) -> usize {
+        self.strides.len()
+    }
+
+    pub fn for_each_run<F: FnMut(&[isize], usize, &[isize])>(&self, mut f: F) {
+        if self.numel == 0 {
+            return;
+        }
+
+        let num_operands = self.strides.len();
+        let mut offsets = vec![0isize; num_operands];
+
+        if self.shape.is_empty() {
+            let inner = vec![0isize; num_operands];
+            f(&offsets, 1, &inner);
+            return;
+        }
+
+        let last = self.shape.len() - 1;
+        let inner_len = self.shape[last];
+        let inner: Vec<isize> = self.strides.iter().map(|s| s[last]).collect();
+        let mut counter = vec![0usize; last];
+
+        loop {
+            f(&offsets, inner_len, &inner);
+
+            let mut dim = last;
+            loop {
+                if dim == 0 {
+                    return;
+                }
+                dim -= 1;
+                counter[dim] += 1;
+                for (offset, strides) in offsets.iter_mut().zip(self.strides.iter()) {
+                    *offset += strides[dim];
+                }
+                if counter[dim] < self.shape[dim] {
+                    break;
+                }
+                for (offset, strides) in offsets.iter_mut().zip(self.strides.iter()) {
+                    *offset -= strides[dim] * self.shape[dim] as isize;
+                }
+                counter[dim] = 0;
+            }
+        }
+    }
+
+    fn coalesce(&mut self) {
+        if self.numel == 0 {
+            return;
+        }
+
+        let keep: Vec<usize> = (0..self.shape.len()).filter(|&d| self.shape[d] != 1).collect();
+        let mut shape: Vec<usize> = keep.iter().map(|&d| self.shape[d]).collect();
+        let mut strides: Vec<Vec<isize>> = self
+            .strides
+            .iter()
+            .map(|s| keep.iter().map(|&d| s[d]).collect())
+            .collect();
+
+        let mut dim = shape.len();
+        while dim > 1 {
+            dim -= 1;
+            let can_merge = strides
+                .iter()
+                .all(|s| s[dim - 1] == s[dim] * shape[dim] as isize);
+            if can_merge {
+                shape[dim - 1] *= shape[dim];
+                shape.remove(dim);
+                for s in strides.iter_mut() {
+                    s[dim - 1] = s[dim];
+                    s.remove(dim);
+                }
+            }
+        }
+
+        self.shape = shape;
+        self.strides = strides;
+    }
+}
+
+impl TensorImpl {
+    pub fn is_contiguous(&self) -> bool {
+        if self.numel() == 0 {
+            return true;
+        }
+        let expected = TensorIterator::contiguous_strides(self.shape());
+        self.shape()
+            .iter()
+            .zip(self.strides().iter().zip(expected.iter()))
+            .all(|(&dim, (&stride, &want))| dim == 1 || stride == want)
+    }
+
+    pub fn gather_elements<T: Clone>(&self) -> Vec<T> {
+        let numel = self.numel().max(0) as usize;
+        let ptr = self.data_ptr::<T>() as *const T;
+        if ptr.is_null() {
+            return Vec::new();
+        }
+        if self.is_contiguous() {
+            return unsafe { std::slice::from_raw_parts(ptr, numel).to_vec() };
+        }
+
+        let mut result = Vec::with_capacity(numel);
+        let iter = match TensorIterator::new(self.shape(), &[self.strides().to_vec()]) {
+            Ok(iter) => iter,
+            Err(_) => return result,
+        };
+        iter.for_each_run(|offsets, len, inner| unsafe {
+            let base = ptr.offset(offsets[0]);
+            for i in 0..len as isize {
+                result.push((*base.offset(i * inner[0])).clone());
+            }
+        });
+        result
+    }
+
+    pub fn scatter_elements<T: Copy>(&self, data: &[T]) {
+        let ptr = self.data_ptr::<T>();
+        if ptr.is_null() || self.is_read_only() {
+            return;
+        }
+        if self.is_contiguous() {
+            let len = data.len().min(self.numel().max(0) as usize);
+            unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, len) };
+            return;
+        }
+
+        let iter = match TensorIterator::new(self.shape(), &[self.strides().to_vec()]) {
+            Ok(iter) => iter,
+            Err(_) => return,
+        };
+        let mut values = data.iter();
+        iter.for_each_run(|offsets, len, inner| unsafe {
+            let base = ptr.offset(offsets[0]);
+            for i in 0..len as isize {
+                if let Some(&v) = values.next() {
+                    *base.offset(i * inner[0]) = v;
+                }
+            }
+        });
+    }
+}
+
+impl Tensor {
+    pub fn unary_op<F: Fn(f32) -> f32>(&self, op: F) -> Self {
+        if let Some(output) = meta_shape_rule(&[self], &self.shape(), DType::Float32) {
+            return output;
+        }
+        match self.impl_.as_ref() {
+            Some(impl_) if impl_.device().is_cpu() => self.map_unary_cpu(&op),
+            _ => Self::new(),
+        }
+    }
+
+    pub fn binary_op<F: Fn(f32, f32) -> f32>(&self, other: &Self, op: F) -> Self {
+        match dispatch_device(&[self, other]) {
+            Some(device) if device.is_meta() => meta_binary_shape(self, other),
+            Some(device) if device.is_cpu() => self.map_binary_cpu(other, &op),
+            _ => Self::new(),
+        }
+    }
+
+    pub(crate) fn apply_unary(&self, op: UnaryOp) -> Self {
+        match self.impl_.as_ref().map(|impl_| backend(impl_.device())) {
+            Some(Ok(backend)) => (backend.kernels().unary)(self, op),
+            _ => Self::new(),
+        }
+    }
+
+    pub(crate) fn apply_binary(&self, other: &Self, op: BinaryOp) -> Self {
+        match dispatch_device(&[self, other]).map(backend) {
+            Some(Ok(backend)) => (backend.kernels().binary)(self, other, op),
+            _ => Self::new(),
+        }
+    }
+
+    pub(crate) fn unary_cpu(&self, op: UnaryOp) -> Self {
+        self.map_unary_cpu(&|x| op.apply(x))
+    }
+
+    pub(crate) fn binary_cpu(&self, other: &Self, op: BinaryOp) -> Self {
+        self.map_binary_cpu(other, &|a, b| op.apply(a, b))
+    }
+
+    fn map_unary_cpu(&self, op: &dyn Fn(f32) -> f32) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+
+        let input = match Float32Operand::from_impl(impl_) {
+            Some(input) => input,
+            None => return Self::new(),
+        };
+        let shape = impl_.shape().to_vec();
+        let numel = impl_.numel().max(0) as usize;
+
+        let output = match Self::float32_output(&shape) {
+            Some(output) => output,
+            None => return Self::new(),
+        };
+        let out = output.data_ptr::<f32>();
+
+        if input.contiguous {
+            let src = unsafe { std::slice::from_raw_parts(input.ptr, numel) };
+            let dst = unsafe { std::slice::from_raw_parts_mut(out, numel) };
+            for (d, &x) in dst.iter_mut().zip(src) {
+                *d = op(x);
+            }
+        } else {
+            let out_strides = TensorIterator::contiguous_strides(&shape);
+            let iter = match TensorIterator::new(&shape, &[out_strides, input.strides.clone()]) {
+                Ok(iter) => iter,
+                Err(_) => return Self::new(),
+            };
+            iter.for_each_run(|offsets, len, inner| unsafe {
+                let dst = out.offset(offsets[0]);
+                let src = input.ptr.offset(offsets[1]);
+                for i in 0..len as isize {
+                    *dst.offset(i * inner[0]) = op(*src.offset(i * inner[1]));
+                }
+            });
+        }
+
+        Self::new_from_impl(Rc::new(output))
+    }
+
+    fn map_binary_cpu(&self, other: &Self, op: &dyn Fn(f32, f32) -> f32) -> Self {
+        let (lhs_impl, rhs_impl) = match (self.impl_.as_ref(), other.impl_.as_ref()) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return Self::new(),
+        };
+
+        let result_shape = match broadcast_shapes(lhs_impl.shape(), rhs_impl.shape()) {
+            Ok(shape) => shape,
+            Err(_) => return Self::new(),
+        };
+
+        let lhs = match Float32Operand::from_impl(lhs_impl) {
+            Some(lhs) => lhs,
+            None => return Self::new(),
+        };
+        let rhs = match Float32Operand::from_impl(rhs_impl) {
+            Some(rhs) => rhs,
+            None => return Self::new(),
+        };
+
+        let numel = result_shape.iter().product::<i64>().max(0) as usize;
+        let output = match Self::float32_output(&result_shape) {
+            Some(output) => output,
+            None => return Self::new(),
+        };
+        let out = output.data_ptr::<f32>();
+
+        let same_shape = lhs_impl.shape() == result_shape.as_slice()
+            && rhs_impl.shape() == result_shape.as_slice();
+        if same_shape && lhs.contiguous && rhs.contiguous {
+            let a = unsafe { std::slice::from_raw_parts(lhs.ptr, numel) };
+            let b = unsafe { std::slice::from_raw_parts(rhs.ptr, numel) };
+            let dst = unsafe { std::slice::from_raw_parts_mut(out, numel) };
+            for ((d, &x), &y) in dst.iter_mut().zip(a).zip(b) {
+                *d = op(x, y);
+            }
+            return Self::new_from_impl(Rc::new(output));
+        }
+
+        let lhs_strides =
+            match TensorIterator::broadcast_strides(lhs_impl.shape(), &lhs.strides, &result_shape) {
+                Ok(strides) => strides,
+                Err(_) => return Self::new(),
+            };
+        let rhs_strides =
+            match TensorIterator::broadcast_strides(rhs_impl.shape(), &rhs.strides, &result_shape) {
+                Ok(strides) => strides,
+                Err(_) => return Self::new(),
+            };
+        let out_strides = TensorIterator::contiguous_strides(&result_shape);
+
+        let iter = match TensorIterator::new(&result_shape, &[out_strides, lhs_strides, rhs_strides]) {
+            Ok(iter) => iter,
+            Err(_) => return Self::new(),
+        };
+
+        iter.for_each_run(|offsets, len, inner| unsafe {
+            let dst = out.offset(offsets[0]);
+            let a = lhs.ptr.offset(offsets[1]);
+            let b = rhs.ptr.offset(offsets[2]);
+            match (inner[0], inner[1], inner[2]) {
+                (1, 1, 1) => {
+                    let dst = std::slice::from_raw_parts_mut(dst, len);
+                    let a = std::slice::from_raw_parts(a, len);
+                    let b = std::slice::from_raw_parts(b, len);
+                    for ((d, &x), &y) in dst.iter_mut().zip(a).zip(b) {
+                        *d = op(x, y);
+                    }
+                }
+                (1, 1, 0) => {
+                    let dst = std::slice::from_raw_parts_mut(dst, len);
+                    let a = std::slice::from_raw_parts(a, len);
+                    let y = *b;
+                    for (d, &x) in dst.iter_mut().zip(a) {
+                        *d = op(x, y);
+                    }
+                }
+                (1, 0, 1) => {
+                    let dst = std::slice::from_raw_parts_mut(dst, len);
+                    let x = *a;
+                    let b = std::slice::from_raw_parts(b, len);
+                    for (d, &y) in dst.iter_mut().zip(b) {
+                        *d = op(x, y);
+                    }
+                }
+                (sd, sa, sb) => {
+                    for i in 0..len as isize {
+                        *dst.offset(i * sd) = op(*a.offset(i * sa), *b.offset(i * sb));
+                    }
+                }
+            }
+        });
+
+        Self::new_from_impl(Rc::new(output))
+    }
+
+    pub(crate) fn integer_binary_op(&self, other: &Self, op: fn(i64, i64) -> i64) -> Option<Self> {
+        let (lhs_impl, rhs_impl) = (self.impl_.as_ref()?, other.impl_.as_ref()?);
+        let dtype = integer_result_dtype(lhs_impl, rhs_impl)?;
+        let (lhs, rhs) = (int64_values(lhs_impl)?, int64_values(rhs_impl)?);
+
+        let shape = broadcast_shapes(lhs_impl.shape(), rhs_impl.shape()).ok()?;
+        let strides = |impl_: &TensorImpl| {
+            let contiguous = TensorIterator::contiguous_strides(impl_.shape());
+            TensorIterator::broadcast_strides(impl_.shape(), &contiguous, &shape)
+        };
+        let iter = TensorIterator::new(&shape, &[strides(lhs_impl).ok()?, strides(rhs_impl).ok()?]).ok()?;
+
+        let mut values = Vec::with_capacity(iter.numel());
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                let a = lhs[(offsets[0] + i * inner[0]) as usize];
+                let b = rhs[(offsets[1] + i * inner[1]) as usize];
+                values.push(op(a, b));
+            }
+        });
+
+        Some(match dtype {
+            DType::Int32 => Self::from_vec(values.iter().map(|&v| v as i32).collect(), &shape),
+            _ => Self::from_vec(values, &shape),
+        })
+    }
+
+    fn float32_output(shape: &[i64]) -> Option<TensorImpl> {
+        let options = Options::default().dtype(DType::Float32);
+        let output = TensorImpl::new(shape, options).ok()?;
+        if output.data_ptr::<f32>().is_null() {
+            return None;
+        }
+        Some(output)
+    }
+}
+
+impl UnaryOp {
+    pub(crate) fn apply(self, x: f32) -> f32 {
+        match self {
+            Self::Neg => -x,
+            Self::Sqrt => x.sqrt(),
+            Self::Log => x.ln(),
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tanh => x.tanh(),
+            Self::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Self::Relu => x.max(0.0),
+            Self::Gelu => 0.5 * x * (1.0 + (x * 0.797_884_6 * (1.0 + 0.044715 * x * x)).tanh()),
+            Self::Silu => x / (1.0 + (-x).exp()),
+            Self::LeakyRelu { negative_slope } => {
+                if x > 0.0 {
+                    x
+                } else {
+                    negative_slope * x
+                }
+            }
+        }
+    }
+}
+
+impl BinaryOp {
+    pub(crate) fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+            Self::Div => {
+                if b != 0.0 {
+                    a / b
+                } else {
+                    0.0
+                }
+            }
+            Self::Pow => a.powf(b),
+            Self::Remainder => a - (a / b).floor() * b,
+            Self::FloorDivide => (a / b).floor(),
+            Self::Maximum => a.max(b),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Elements {
+    ptr: *mut u8,
+    dtype: DType,
+}
+
+impl Elements {
+    pub(crate) fn from_impl(impl_: &TensorImpl) -> Option<Self> {
+        let ptr = impl_.data_ptr::<u8>();
+        if ptr.is_null() {
+            return None;
+        }
+        Some(Self { ptr, dtype: impl_.dtype() })
+    }
+
+    pub(crate) unsafe fn read(&self, index: isize) -> f64 {
+        match self.dtype {
+            DType::Float32 => *(self.ptr as *const f32).offset(index) as f64,
+            DType::Float16 => f16_to_f32(*(self.ptr as *const u16).offset(index)) as f64,
+            DType::BFloat16 => bf16_to_f32(*(self.ptr as *const u16).offset(index)) as f64,
+            DType::Int32 => *(self.ptr as *const i32).offset(index) as f64,
+            DType::Int64 => *(self.ptr as *const i64).offset(index) as f64,
+            DType::Bool => (*self.ptr.offset(index) != 0) as u8 as f64,
+        }
+    }
+
+    pub(crate) fn is_integral(&self) -> bool {
+        matches!(self.dtype, DType::Int32 | DType::Int64 | DType::Bool)
+    }
+
+    pub(crate) unsafe fn read_i64(&self, index: isize) -> i64 {
+        match self.dtype {
+            DType::Int32 => *(self.ptr as *const i32).offset(index) as i64,
+            DType::Int64 => *(self.ptr as *const i64).offset(index),
+            _ => self.read(index) as i64,
+        }
+    }
+
+    pub(crate) unsafe fn write_i64(&self, index: isize, value: i64) {
+        match self.dtype {
+            DType::Int32 => *(self.ptr as *mut i32).offset(index) = value as i32,
+            DType::Int64 => *(self.ptr as *mut i64).offset(index) = value,
+            DType::Bool => *self.ptr.offset(index) = (value != 0) as u8,
+            _ => self.write(index, value as f64),
+        }
+    }
+
+    pub(crate) unsafe fn write(&self, index: isize, value: f64) {
+        match self.dtype {
+            DType::Float32 => *(self.ptr as *mut f32).offset(index) = value as f32,
+            DType::Float16 => *(self.ptr as *mut u16).offset(index) = f32_to_f16(value as f32),
+            DType::BFloat16 => *(self.ptr as *mut u16).offset(index) = f32_to_bf16(value as f32),
+            DType::Int32 => *(self.ptr as *mut i32).offset(index) = value as i32,
+            DType::Int64 => *(self.ptr as *mut i64).offset(index) = value as i64,
+            DType::Bool => *self.ptr.offset(index) = (value != 0.0) as u8,
+        }
+    }
+}
+
+struct Float32Operand {
+    ptr: *const f32,
+    strides: Vec<i64>,
+    contiguous: bool,
+    _converted: Option<Vec<f32>>,
+}
+
+impl Float32Operand {
+    fn from_impl(impl_: &TensorImpl) -> Option<Self> {
+        if impl_.dtype() == DType::Float32 {
+            let ptr = impl_.data_ptr::<f32>() as *const f32;
+            if ptr.is_null() {
+                return None;
+            }
+            return Some(Self {
+                ptr,
+                strides: impl_.strides().to_vec(),
+                contiguous: impl_.is_contiguous(),
+                _converted: None,
+            });
+        }
+
+        let converted: Vec<f32> = impl_.to_f64_list().ok()?.iter().map(|&v| v as f32).collect();
+        Some(Self {
+            ptr: converted.as_ptr(),
+            strides: TensorIterator::contiguous_strides(impl_.shape()),
+            contiguous: true,
+            _converted: Some(converted),
+        })
+    }
+}
+
+fn integer_result_dtype(lhs: &TensorImpl, rhs: &TensorImpl) -> Option<DType> {
+    let integral = |dtype: DType| matches!(dtype, DType::Int32 | DType::Int64);
+    if !integral(lhs.dtype()) || !integral(rhs.dtype()) {
+        return None;
+    }
+    Some(match (lhs.dim(), rhs.dim()) {
+        _ if lhs.dtype() == rhs.dtype() => lhs.dtype(),
+        (0, dim) if dim > 0 => rhs.dtype(),
+        (dim, 0) if dim > 0 => lhs.dtype(),
+        _ => DType::Int64,
+    })
+}
+
+pub(crate) fn int64_values(impl_: &TensorImpl) -> Option<Vec<i64>> {
+    match impl_.dtype() {
+        DType::Int64 => impl_.to_list::<i64>().ok(),
+        DType::Int32 => Some(impl_.to_list::<i32>().ok()?.iter().map(|&v| v as i64).collect()),
+        _ => None,
+    }
+}
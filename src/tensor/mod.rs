@@ -2,19 +2,41 @@ pub mod dtype;
 pub mod device;
 pub mod scalar;
 pub mod options;
+pub mod generator;
+pub mod allocator;
+pub mod backend;
 pub mod storage;
 pub mod tensor_impl;
+#[allow(clippy::module_inception)]
 pub mod tensor;
 pub mod broadcasting;
+pub mod iterator;
+pub mod comparison;
+pub mod random;
+pub mod factory;
+pub mod display;
+pub mod interop;
+pub mod view;
+pub mod indexing;
+pub mod sorting;
+pub mod concat;
+pub mod ops;
 
 pub use dtype::*;
 pub use device::*;
 pub use scalar::*;
 pub use options::*;
+pub use generator::*;
+pub use allocator::*;
+pub use backend::*;
 pub use storage::*;
 pub use tensor_impl::*;
 pub use tensor::*;
 pub use broadcasting::*;
+pub use iterator::*;
+pub use display::*;
+pub use indexing::*;
+pub use interop::*;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,272 @@
+use crate::autograd::is_grad_enabled;
+use crate::tensor::broadcasting::broadcast_shapes;
+use crate::tensor::{BinaryOp, DType, Elements, Options, Tensor, TensorImpl, TensorIterator, UnaryOp};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+impl Rem for &Tensor {
+    type Output = Tensor;
+
+    fn rem(self, other: &Tensor) -> Tensor {
+        let (a, b) = (self.detach(), other.detach());
+        self.integer_binary_op(other, floor_remainder)
+            .unwrap_or_else(|| self.apply_binary(other, BinaryOp::Remainder))
+            .with_grad_fn("RemainderBackward0", &[self, other], move |grad| {
+                vec![Clone::clone(grad), -&(grad * &a.apply_binary(&b, BinaryOp::FloorDivide))]
+            })
+    }
+}
+
+impl Neg for &Tensor {
+    type Output = Tensor;
+
+    fn neg(self) -> Tensor {
+        self.apply_unary(UnaryOp::Neg)
+            .with_grad_fn("NegBackward0", &[self], |grad| vec![-grad])
+    }
+}
+
+impl Neg for Tensor {
+    type Output = Tensor;
+
+    fn neg(self) -> Tensor {
+        -&self
+    }
+}
+
+macro_rules! impl_tensor_binary_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait<Tensor> for Tensor {
+            type Output = Tensor;
+
+            fn $method(self, other: Tensor) -> Tensor {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&Tensor> for Tensor {
+            type Output = Tensor;
+
+            fn $method(self, other: &Tensor) -> Tensor {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<Tensor> for &Tensor {
+            type Output = Tensor;
+
+            fn $method(self, other: Tensor) -> Tensor {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+macro_rules! impl_scalar_binary_op {
+    ($trait:ident, $method:ident, $($scalar:ty),+) => {
+        $(
+            impl $trait<$scalar> for &Tensor {
+                type Output = Tensor;
+
+                fn $method(self, other: $scalar) -> Tensor {
+                    self.$method(&other.operand_for(self))
+                }
+            }
+
+            impl $trait<$scalar> for Tensor {
+                type Output = Tensor;
+
+                fn $method(self, other: $scalar) -> Tensor {
+                    (&self).$method(&other.operand_for(&self))
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_assign_op {
+    ($trait:ident, $method:ident, $inplace:ident, $($scalar:ty),+) => {
+        impl std::ops::$trait<&Tensor> for Tensor {
+            fn $method(&mut self, other: &Tensor) {
+                if let Err(error) = self.$inplace(other) {
+                    panic!("{}", error);
+                }
+            }
+        }
+
+        impl std::ops::$trait<Tensor> for Tensor {
+            fn $method(&mut self, other: Tensor) {
+                if let Err(error) = self.$inplace(&other) {
+                    panic!("{}", error);
+                }
+            }
+        }
+
+        $(
+            impl std::ops::$trait<$scalar> for Tensor {
+                fn $method(&mut self, other: $scalar) {
+                    if let Err(error) = self.$inplace(&other.operand_for(self)) {
+                        panic!("{}", error);
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_tensor_binary_op!(Add, add);
+impl_tensor_binary_op!(Sub, sub);
+impl_tensor_binary_op!(Mul, mul);
+impl_tensor_binary_op!(Div, div);
+impl_tensor_binary_op!(Rem, rem);
+
+impl_scalar_binary_op!(Add, add, f32, f64, i64);
+impl_scalar_binary_op!(Sub, sub, f32, f64, i64);
+impl_scalar_binary_op!(Mul, mul, f32, f64, i64);
+impl_scalar_binary_op!(Div, div, f32, f64, i64);
+impl_scalar_binary_op!(Rem, rem, f32, f64, i64);
+
+impl_assign_op!(AddAssign, add_assign, add_, f32, f64, i64);
+impl_assign_op!(SubAssign, sub_assign, sub_, f32, f64, i64);
+impl_assign_op!(MulAssign, mul_assign, mul_, f32, f64, i64);
+impl_assign_op!(DivAssign, div_assign, div_, f32, f64, i64);
+impl_assign_op!(RemAssign, rem_assign, rem_, f32, f64, i64);
+
+impl Tensor {
+    pub fn add_(&mut self, other: &Tensor) -> Result<&mut Self, String> {
+        self.binary_inplace(other, |a, b| a + b, Some(i64::wrapping_add))
+    }
+
+    pub fn sub_(&mut self, other: &Tensor) -> Result<&mut Self, String> {
+        self.binary_inplace(other, |a, b| a - b, Some(i64::wrapping_sub))
+    }
+
+    pub fn mul_(&mut self, other: &Tensor) -> Result<&mut Self, String> {
+        self.binary_inplace(other, |a, b| a * b, Some(i64::wrapping_mul))
+    }
+
+    pub fn div_(&mut self, other: &Tensor) -> Result<&mut Self, String> {
+        self.binary_inplace(other, |a, b| if b != 0.0 { a / b } else { 0.0 }, None)
+    }
+
+    pub fn rem_(&mut self, other: &Tensor) -> Result<&mut Self, String> {
+        self.binary_inplace(other, |a, b| a - (a / b).floor() * b, Some(floor_remainder))
+    }
+
+    pub fn neg_(&mut self) -> Result<&mut Self, String> {
+        let target = self.inplace_target()?;
+        let strides: Vec<i64> = target.strides().to_vec();
+        let iter = TensorIterator::new(target.shape(), &[strides])?;
+        let dst = Elements::from_impl(target).ok_or("Cannot update a tensor without data in place")?;
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                let index = offsets[0] + i * inner[0];
+                unsafe {
+                    if dst.is_integral() {
+                        dst.write_i64(index, dst.read_i64(index).wrapping_neg());
+                    } else {
+                        dst.write(index, -dst.read(index));
+                    }
+                }
+            }
+        });
+        Ok(self)
+    }
+
+    fn binary_inplace(
+        &mut self,
+        other: &Tensor,
+        float: fn(f64, f64) -> f64,
+        integer: Option<fn(i64, i64) -> i64>,
+    ) -> Result<&mut Self, String> {
+        let target = self.inplace_target()?;
+        let source = other
+            .impl_
+            .as_ref()
+            .ok_or("Cannot apply an in-place operation with an undefined operand")?;
+        let shape = broadcast_shapes(target.shape(), source.shape())?;
+        if shape != target.shape() {
+            return Err(format!(
+                "Operand shape {:?} can't be broadcast to the in-place target shape {:?}",
+                source.shape(),
+                target.shape()
+            ));
+        }
+
+        let source_strides = TensorIterator::broadcast_strides(source.shape(), source.strides(), &shape)?;
+        let iter = TensorIterator::new(&shape, &[target.strides().to_vec(), source_strides])?;
+        let dst = Elements::from_impl(target).ok_or("Cannot update a tensor without data in place")?;
+        let src = Elements::from_impl(source).ok_or("In-place operand has no data")?;
+        let integer = integer.filter(|_| dst.is_integral() && src.is_integral());
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                let (d, s) = (offsets[0] + i * inner[0], offsets[1] + i * inner[1]);
+                unsafe {
+                    match integer {
+                        Some(op) => dst.write_i64(d, op(dst.read_i64(d), src.read_i64(s))),
+                        None => dst.write(d, float(dst.read(d), src.read(s))),
+                    }
+                }
+            }
+        });
+        Ok(self)
+    }
+
+    fn inplace_target(&self) -> Result<&TensorImpl, String> {
+        let target = self
+            .impl_
+            .as_ref()
+            .ok_or("Cannot update an undefined tensor in place")?;
+        if target.is_read_only() {
+            return Err("Cannot update a read-only tensor in place".to_string());
+        }
+        if is_grad_enabled() && self.requires_grad() {
+            return Err(if self.is_leaf() {
+                "A leaf tensor that requires grad can't be used in an in-place operation".to_string()
+            } else {
+                "A tensor that requires grad can't be modified in place while grad mode is enabled".to_string()
+            });
+        }
+        Ok(target)
+    }
+}
+
+trait ScalarOperand {
+    fn operand_for(self, tensor: &Tensor) -> Tensor;
+}
+
+impl ScalarOperand for i64 {
+    fn operand_for(self, tensor: &Tensor) -> Tensor {
+        match tensor.dtype() {
+            DType::Int32 | DType::Int64 => Tensor::scalar(self),
+            _ => (self as f64).operand_for(tensor),
+        }
+    }
+}
+
+impl ScalarOperand for f64 {
+    fn operand_for(self, tensor: &Tensor) -> Tensor {
+        let dtype = match tensor.dtype() {
+            DType::Float16 | DType::BFloat16 => tensor.dtype(),
+            _ => DType::Float32,
+        };
+        Tensor::full_with_options(&[], self, Options::default().dtype(dtype))
+    }
+}
+
+impl ScalarOperand for f32 {
+    fn operand_for(self, tensor: &Tensor) -> Tensor {
+        (self as f64).operand_for(tensor)
+    }
+}
+
+fn floor_remainder(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        return 0;
+    }
+    let remainder = a.wrapping_rem(b);
+    if remainder != 0 && (remainder < 0) != (b < 0) {
+        remainder + b
+    } else {
+        remainder
+    }
+}
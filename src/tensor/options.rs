@@ -61,6 +61,7 @@ impl Options {
     }
 }
 
+#[allow(clippy::module_inception)]
 pub mod options {
     use super::*;
 
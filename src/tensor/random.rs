@@ -0,0 +1,487 @@
+use crate::tensor::{with_generator, DType, Generator, Options, Tensor, TensorImpl};
+use rand::Rng;
+use std::rc::Rc;
+
+impl Tensor {
+    pub fn uniform(
+        low: f64,
+        high: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if high < low {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            low + (high - low) * rng.gen::<f64>()
+        })
+    }
+
+    pub fn randint(low: i64, high: i64, shape: &[i64], generator: Option<&mut Generator>) -> Self {
+        Self::randint_with_options(low, high, shape, Options::default().indices(), generator)
+    }
+
+    pub fn randint_with_options(
+        low: i64,
+        high: i64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if high <= low || shape.iter().any(|&dim| dim < 0) {
+            return Self::new();
+        }
+        let numel: usize = shape.iter().product::<i64>() as usize;
+        let data: Vec<i64> = with_generator(generator, |rng| (0..numel).map(|_| rng.gen_range(low..high)).collect());
+        if options.dtype != DType::Int64 {
+            let data: Vec<f64> = data.iter().map(|&v| v as f64).collect();
+            return Self::from_f64_data(&data, shape, options);
+        }
+        match TensorImpl::new_from_data(&data, shape, options) {
+            Ok(impl_) => Self::new_from_impl(Rc::new(impl_)),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn normal(
+        mean: f64,
+        std: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if std < 0.0 {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            mean + std * standard_normal(rng)
+        })
+    }
+
+    pub fn trunc_normal(
+        mean: f64,
+        std: f64,
+        a: f64,
+        b: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if std < 0.0 || b < a {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            truncated_normal(rng, mean, std, a, b)
+        })
+    }
+
+    pub fn exponential(
+        lambda: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if lambda <= 0.0 {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            standard_exponential(rng) / lambda
+        })
+    }
+
+    pub fn gamma(
+        concentration: f64,
+        rate: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if concentration <= 0.0 || rate <= 0.0 {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            standard_gamma(rng, concentration) / rate
+        })
+    }
+
+    pub fn beta(
+        alpha: f64,
+        beta: f64,
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        if alpha <= 0.0 || beta <= 0.0 {
+            return Self::new();
+        }
+        Self::sample(shape, options, generator, |rng| {
+            let x = standard_gamma(rng, alpha);
+            let y = standard_gamma(rng, beta);
+            if x + y > 0.0 {
+                x / (x + y)
+            } else if alpha >= beta {
+                1.0
+            } else {
+                0.0
+            }
+        })
+    }
+
+    pub fn randperm(n: usize, generator: Option<&mut Generator>) -> Self {
+        Self::randperm_with_options(n, Options::default().indices(), generator)
+    }
+
+    pub fn randperm_with_options(n: usize, options: Options, generator: Option<&mut Generator>) -> Self {
+        let mut perm: Vec<i64> = (0..n as i64).collect();
+        with_generator(generator, |rng| {
+            for i in (1..n).rev() {
+                let j = rng.gen_range(0..=i);
+                perm.swap(i, j);
+            }
+        });
+        if options.dtype != DType::Int64 {
+            let perm: Vec<f64> = perm.iter().map(|&v| v as f64).collect();
+            return Self::from_f64_data(&perm, &[n as i64], options);
+        }
+        match TensorImpl::new_from_data(&perm, &[n as i64], options) {
+            Ok(impl_) => Self::new_from_impl(Rc::new(impl_)),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn poisson(&self, generator: Option<&mut Generator>) -> Self {
+        let rates = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        if rates.iter().any(|&rate| !rate.is_finite() || rate < 0.0) {
+            return Self::new();
+        }
+
+        let data: Vec<f64> = with_generator(generator, |rng| {
+            rates.iter().map(|&rate| sample_poisson(rng, rate)).collect()
+        });
+        let options = Options::default().dtype(self.dtype()).device(self.device());
+        Self::from_f64_data(&data, &self.shape(), options)
+    }
+
+    pub fn multinomial(
+        &self,
+        num_samples: usize,
+        replacement: bool,
+        generator: Option<&mut Generator>,
+    ) -> Self {
+        let shape = self.shape();
+        let (rows, categories) = match shape.as_slice() {
+            [k] => (1, *k as usize),
+            [n, k] => (*n as usize, *k as usize),
+            _ => return Self::new(),
+        };
+        let weights = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        if categories == 0 || weights.iter().any(|&w| !w.is_finite() || w < 0.0) {
+            return Self::new();
+        }
+
+        let mut indices = Vec::with_capacity(rows * num_samples);
+        let valid = with_generator(generator, |rng| {
+            for row in weights.chunks(categories) {
+                let mut row = row.to_vec();
+                let nonzero = row.iter().filter(|&&w| w > 0.0).count();
+                if nonzero == 0 || (!replacement && num_samples > nonzero) {
+                    return false;
+                }
+                for _ in 0..num_samples {
+                    let index = sample_index(rng, &row);
+                    if !replacement {
+                        row[index] = 0.0;
+                    }
+                    indices.push(index as f64);
+                }
+            }
+            true
+        });
+        if !valid {
+            return Self::new();
+        }
+
+        let result_shape = if shape.len() == 1 {
+            vec![num_samples as i64]
+        } else {
+            vec![rows as i64, num_samples as i64]
+        };
+        Self::from_f64_data(&indices, &result_shape, Options::default().dtype(DType::Int64))
+    }
+
+    pub fn categorical(&self, generator: Option<&mut Generator>) -> Self {
+        let shape = self.shape();
+        if shape.is_empty() {
+            return Self::new();
+        }
+
+        let categories = shape[shape.len() - 1];
+        let batch_shape = &shape[..shape.len() - 1];
+        let rows: i64 = batch_shape.iter().product();
+        let samples = self
+            .reshape(&[rows, categories])
+            .multinomial(1, true, generator);
+        if !samples.defined() {
+            return Self::new();
+        }
+        samples.reshape(batch_shape)
+    }
+
+    pub fn uniform_(&mut self, low: f64, high: f64, generator: Option<&mut Generator>) -> Result<&mut Self, String> {
+        if high < low {
+            return Err(format!("Invalid uniform range: low={}, high={}", low, high));
+        }
+        self.sample_inplace(generator, |rng| low + (high - low) * rng.gen::<f64>())
+    }
+
+    pub fn normal_(&mut self, mean: f64, std: f64, generator: Option<&mut Generator>) -> Result<&mut Self, String> {
+        if std < 0.0 {
+            return Err(format!("Invalid normal standard deviation: {}", std));
+        }
+        self.sample_inplace(generator, |rng| mean + std * standard_normal(rng))
+    }
+
+    pub fn trunc_normal_(
+        &mut self,
+        mean: f64,
+        std: f64,
+        a: f64,
+        b: f64,
+        generator: Option<&mut Generator>,
+    ) -> Result<&mut Self, String> {
+        if std < 0.0 || b < a {
+            return Err(format!("Invalid truncated normal parameters: std={}, a={}, b={}", std, a, b));
+        }
+        self.sample_inplace(generator, |rng| truncated_normal(rng, mean, std, a, b))
+    }
+
+    pub fn exponential_(&mut self, lambda: f64, generator: Option<&mut Generator>) -> Result<&mut Self, String> {
+        if lambda <= 0.0 {
+            return Err(format!("Invalid exponential rate: {}", lambda));
+        }
+        self.sample_inplace(generator, |rng| standard_exponential(rng) / lambda)
+    }
+
+    fn sample<F: FnMut(&mut Generator) -> f64>(
+        shape: &[i64],
+        options: Options,
+        generator: Option<&mut Generator>,
+        mut f: F,
+    ) -> Self {
+        if shape.iter().any(|&dim| dim < 0) {
+            return Self::new();
+        }
+        let numel: usize = shape.iter().product::<i64>() as usize;
+        let data: Vec<f64> = with_generator(generator, |rng| (0..numel).map(|_| f(rng)).collect());
+        Self::from_f64_data(&data, shape, options)
+    }
+
+    fn sample_inplace<F: FnMut(&mut Generator) -> f64>(
+        &mut self,
+        generator: Option<&mut Generator>,
+        mut f: F,
+    ) -> Result<&mut Self, String> {
+        let impl_ = self
+            .impl_
+            .as_ref()
+            .ok_or("Cannot sample into an undefined tensor")?;
+        let numel = impl_.numel() as usize;
+        let data: Vec<f64> = with_generator(generator, |rng| (0..numel).map(|_| f(rng)).collect());
+        impl_.write_f64(&data)?;
+        Ok(self)
+    }
+}
+
+fn standard_normal(rng: &mut Generator) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn standard_exponential(rng: &mut Generator) -> f64 {
+    -(1.0 - rng.gen::<f64>()).ln()
+}
+
+fn truncated_normal(rng: &mut Generator, mean: f64, std: f64, a: f64, b: f64) -> f64 {
+    if std == 0.0 {
+        return mean.clamp(a, b);
+    }
+    let (alpha, beta) = ((a - mean) / std, (b - mean) / std);
+    let (low, high, sign) = if alpha > 0.0 { (-beta, -alpha, -1.0) } else { (alpha, beta, 1.0) };
+    let (cdf_low, cdf_high) = (normal_cdf(low), normal_cdf(high));
+    let p = cdf_low + (cdf_high - cdf_low) * rng.gen::<f64>();
+    (mean + std * sign * normal_quantile(p)).clamp(a, b)
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549671010422196,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const TAIL: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < TAIL {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - TAIL {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+fn standard_gamma(rng: &mut Generator, alpha: f64) -> f64 {
+    if alpha < 1.0 {
+        let u: f64 = rng.gen();
+        return standard_gamma(rng, alpha + 1.0) * u.powf(1.0 / alpha);
+    }
+
+    let d = alpha - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let (x, v) = loop {
+            let x = standard_normal(rng);
+            let v = 1.0 + c * x;
+            if v > 0.0 {
+                break (x, v * v * v);
+            }
+        };
+        let u: f64 = 1.0 - rng.gen::<f64>();
+        if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+fn sample_poisson(rng: &mut Generator, rate: f64) -> f64 {
+    if rate == 0.0 {
+        return 0.0;
+    }
+
+    if rate < 30.0 {
+        let limit = (-rate).exp();
+        let mut count = 0.0;
+        let mut product: f64 = rng.gen();
+        while product > limit {
+            count += 1.0;
+            product *= rng.gen::<f64>();
+        }
+        return count;
+    }
+
+    let log_rate = rate.ln();
+    let b = 0.931 + 2.53 * rate.sqrt();
+    let a = -0.059 + 0.02483 * b;
+    let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+    let v_r = 0.9277 - 3.6224 / (b - 2.0);
+    loop {
+        let u = rng.gen::<f64>() - 0.5;
+        let v: f64 = rng.gen();
+        let us = 0.5 - u.abs();
+        let k = ((2.0 * a / us + b) * u + rate + 0.43).floor();
+        if us >= 0.07 && v <= v_r {
+            return k;
+        }
+        if k < 0.0 || (us < 0.013 && v > us) {
+            continue;
+        }
+        let lhs = (v * inv_alpha / (a / (us * us) + b)).ln();
+        let rhs = -rate + k * log_rate - ln_factorial(k);
+        if lhs <= rhs {
+            return k;
+        }
+    }
+}
+
+fn ln_factorial(k: f64) -> f64 {
+    if k < 2.0 {
+        return 0.0;
+    }
+    let n = k + 1.0;
+    (n - 0.5) * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI).ln() + 1.0 / (12.0 * n)
+        - 1.0 / (360.0 * n * n * n)
+}
+
+fn sample_index(rng: &mut Generator, weights: &[f64]) -> usize {
+    let total: f64 = weights.iter().sum();
+    let target = rng.gen::<f64>() * total;
+    let mut cumulative = 0.0;
+    let mut last_positive = 0;
+    for (i, &w) in weights.iter().enumerate() {
+        if w <= 0.0 {
+            continue;
+        }
+        cumulative += w;
+        last_positive = i;
+        if target < cumulative {
+            return i;
+        }
+    }
+    last_positive
+}
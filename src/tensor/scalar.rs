@@ -82,3 +82,26 @@ impl From<bool> for Scalar {
         Scalar::Bool(v)
     }
 }
+
+pub trait FillValue: Copy {
+    fn to_f64(self) -> f64;
+    fn to_i64(self) -> i64;
+}
+
+macro_rules! impl_fill_value {
+    ($($ty:ty),+) => {
+        $(
+            impl FillValue for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn to_i64(self) -> i64 {
+                    self as i64
+                }
+            }
+        )+
+    };
+}
+
+impl_fill_value!(f32, f64, i32, i64);
@@ -0,0 +1,483 @@
+use crate::tensor::{backend, meta_shape_rule, DType, LaneSelect, Options, Tensor, TensorImpl};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+impl Tensor {
+    pub fn sort(&self, dim: i64, descending: bool) -> (Self, Self) {
+        let indices = self.argsort(dim, descending);
+        if !indices.defined() {
+            return (Self::new(), Self::new());
+        }
+        (self.gather(dim, &indices), indices)
+    }
+
+    pub fn argsort(&self, dim: i64, descending: bool) -> Self {
+        self.lane_indices(dim, LaneSelect::Sort { descending })
+    }
+
+    pub fn topk(&self, k: usize, dim: i64, largest: bool, sorted: bool) -> (Self, Self) {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return (Self::new(), Self::new()),
+        };
+        if k as i64 > shape.get(d).copied().unwrap_or(1) {
+            return (Self::new(), Self::new());
+        }
+
+        let indices = self.lane_indices(dim, LaneSelect::TopK { k, largest, sorted });
+        if !indices.defined() {
+            return (Self::new(), Self::new());
+        }
+        (self.gather(dim, &indices), indices)
+    }
+
+    pub fn kthvalue(&self, k: usize, dim: i64, keepdim: bool) -> (Self, Self) {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return (Self::new(), Self::new()),
+        };
+        let size = shape.get(d).copied().unwrap_or(1) as usize;
+        if k == 0 || k > size {
+            return (Self::new(), Self::new());
+        }
+
+        let indices = self.lane_indices(dim, LaneSelect::Kth { k });
+        self.reduced_pair(dim, indices, keepdim)
+    }
+
+    pub fn median(&self) -> Self {
+        if !self.defined() || self.numel() == 0 {
+            return Self::new();
+        }
+        let k = (self.numel() as usize).div_ceil(2);
+        self.reshape(&[-1]).kthvalue(k, 0, false).0
+    }
+
+    pub fn median_dim(&self, dim: i64, keepdim: bool) -> (Self, Self) {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return (Self::new(), Self::new()),
+        };
+        let size = shape.get(d).copied().unwrap_or(1) as usize;
+        self.kthvalue(size.div_ceil(2), dim, keepdim)
+    }
+
+    pub fn mode(&self, dim: i64, keepdim: bool) -> (Self, Self) {
+        let indices = self.lane_indices(dim, LaneSelect::Mode);
+        self.reduced_pair(dim, indices, keepdim)
+    }
+
+    pub fn unique(
+        &self,
+        sorted: bool,
+        return_inverse: bool,
+        return_counts: bool,
+    ) -> (Self, Option<Self>, Option<Self>) {
+        let keys = match self.impl_.as_ref().and_then(|impl_| SortKeys::read(impl_).ok()) {
+            Some(keys) => keys,
+            None => return (Self::new(), None, None),
+        };
+
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_by(|&a, &b| keys.cmp(a, b));
+        let mut firsts: Vec<usize> = Vec::new();
+        let mut groups = vec![0usize; keys.len()];
+        for &i in &order {
+            match firsts.last() {
+                Some(&first) if keys.cmp(first, i) == Ordering::Equal => {}
+                _ => firsts.push(i),
+            }
+            groups[i] = firsts.len() - 1;
+        }
+
+        let mut rank: Vec<usize> = (0..firsts.len()).collect();
+        if !sorted {
+            let mut by_appearance = rank.clone();
+            by_appearance.sort_by_key(|&group| firsts[group]);
+            for (position, &group) in by_appearance.iter().enumerate() {
+                rank[group] = position;
+            }
+            firsts = by_appearance.iter().map(|&group| firsts[group]).collect();
+        }
+
+        let firsts: Vec<i64> = firsts.iter().map(|&i| i as i64).collect();
+        let unique = self
+            .detach()
+            .reshape(&[-1])
+            .index_select(0, &Self::from_vec(firsts.clone(), &[firsts.len() as i64]));
+        let inverse = return_inverse.then(|| {
+            let inverse: Vec<i64> = groups.iter().map(|&group| rank[group] as i64).collect();
+            Self::from_vec(inverse, &self.shape())
+        });
+        let counts = return_counts.then(|| {
+            let mut counts = vec![0i64; firsts.len()];
+            for &group in &groups {
+                counts[rank[group]] += 1;
+            }
+            Self::from_vec(counts, &[firsts.len() as i64])
+        });
+        (unique, inverse, counts)
+    }
+
+    pub fn searchsorted(&self, values: &Self, right: bool) -> Self {
+        let sequence = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        let queries = match values.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+
+        let seq_shape = self.shape();
+        let value_shape = values.shape();
+        if seq_shape.is_empty() {
+            return Self::new();
+        }
+        let seq_len = seq_shape[seq_shape.len() - 1] as usize;
+        let batched = seq_shape.len() > 1;
+        if batched
+            && (value_shape.len() != seq_shape.len()
+                || value_shape[..value_shape.len() - 1] != seq_shape[..seq_shape.len() - 1])
+        {
+            return Self::new();
+        }
+
+        let query_len = if batched { value_shape[value_shape.len() - 1] as usize } else { 1 };
+        let result: Vec<f64> = queries
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let lane = if batched && query_len > 0 { i / query_len } else { 0 };
+                let row = &sequence[lane * seq_len..(lane + 1) * seq_len];
+                row.partition_point(|&s| match total_cmp(s, v) {
+                    Ordering::Less => true,
+                    Ordering::Equal => right,
+                    Ordering::Greater => false,
+                }) as f64
+            })
+            .collect();
+
+        Self::from_f64_data(&result, &value_shape, Options::default().dtype(DType::Int64))
+    }
+
+    pub fn bucketize(&self, boundaries: &Self, right: bool) -> Self {
+        if boundaries.dim() != 1 {
+            return Self::new();
+        }
+        boundaries.searchsorted(self, right)
+    }
+
+    pub fn cumsum(&self, dim: i64) -> Self {
+        let result = self.scan(dim, |acc, x| acc + x);
+        result.with_grad_fn("CumsumBackward0", &[self], move |grad| {
+            vec![grad.reverse_scan(dim, |acc, x| acc + x)]
+        })
+    }
+
+    pub fn cumprod(&self, dim: i64) -> Self {
+        let result = self.scan(dim, |acc, x| acc * x);
+        let input = self.detach();
+        result.with_grad_fn("CumprodBackward0", &[self], move |grad| {
+            vec![cumprod_backward(&input, grad, dim)]
+        })
+    }
+
+    pub fn cummax(&self, dim: i64) -> (Self, Self) {
+        let indices = self.lane_indices(dim, LaneSelect::Cummax);
+        if !indices.defined() {
+            return (Self::new(), Self::new());
+        }
+        (self.gather(dim, &indices), indices)
+    }
+
+    pub fn histc(&self, bins: usize, min: f64, max: f64) -> Self {
+        let data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+        if bins == 0 || min > max {
+            return Self::new();
+        }
+
+        let (mut low, mut high) = (min, max);
+        if low == high {
+            low = data.iter().copied().fold(f64::INFINITY, f64::min);
+            high = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if data.is_empty() {
+                low = 0.0;
+                high = 0.0;
+            }
+        }
+        if low == high {
+            low -= 1.0;
+            high += 1.0;
+        }
+
+        let mut histogram = vec![0.0; bins];
+        for &v in &data {
+            if v < low || v > high || v.is_nan() {
+                continue;
+            }
+            let bin = (((v - low) / (high - low)) * bins as f64) as usize;
+            histogram[bin.min(bins - 1)] += 1.0;
+        }
+
+        let dtype = if matches!(self.dtype(), DType::Float32 | DType::Float16 | DType::BFloat16) {
+            self.dtype()
+        } else {
+            DType::Float32
+        };
+        Self::from_f64_data(&histogram, &[bins as i64], Options::default().dtype(dtype))
+    }
+
+    fn dim_info(&self, dim: i64) -> Option<(Vec<i64>, usize)> {
+        if !self.defined() {
+            return None;
+        }
+        let shape = self.shape();
+        let ndim = shape.len().max(1) as i64;
+        let d = if dim < 0 { dim + ndim } else { dim };
+        if d < 0 || d >= ndim {
+            return None;
+        }
+        Some((shape, d as usize))
+    }
+
+    fn lane_indices(&self, dim: i64, select: LaneSelect) -> Self {
+        match self.impl_.as_ref().map(|impl_| backend(impl_.device())) {
+            Some(Ok(backend)) => (backend.kernels().sort)(self, dim, select),
+            _ => Self::new(),
+        }
+    }
+
+    pub(crate) fn sort_cpu(&self, dim: i64, select: LaneSelect) -> Self {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return Self::new(),
+        };
+        let keys = match self.impl_.as_ref().map(|impl_| SortKeys::read(impl_)) {
+            Some(Ok(keys)) => keys,
+            _ => return Self::new(),
+        };
+
+        let (outer, size, inner) = lane_layout(&shape, d);
+        let out_size = select.out_size(size);
+        let mut result = vec![0i64; outer * out_size * inner];
+        for o in 0..outer {
+            for n in 0..inner {
+                let lane: Vec<usize> = (0..size).map(|i| (o * size + i) * inner + n).collect();
+                for (i, &index) in select.select(&lane, &keys).iter().take(out_size).enumerate() {
+                    result[(o * out_size + i) * inner + n] = index as i64;
+                }
+            }
+        }
+
+        let mut out_shape = shape.clone();
+        if !out_shape.is_empty() {
+            out_shape[d] = out_size as i64;
+        }
+        Self::from_vec(result, &out_shape)
+    }
+
+    fn reduced_pair(&self, dim: i64, indices: Self, keepdim: bool) -> (Self, Self) {
+        if !indices.defined() {
+            return (Self::new(), Self::new());
+        }
+        let values = self.gather(dim, &indices);
+        if keepdim || self.dim() == 0 {
+            (values, indices)
+        } else {
+            (values.squeeze(Some(dim)), indices.squeeze(Some(dim)))
+        }
+    }
+
+    fn scan<F: Fn(f64, f64) -> f64>(&self, dim: i64, op: F) -> Self {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return Self::new(),
+        };
+        let dtype = match self.dtype() {
+            DType::Bool => DType::Int64,
+            dtype => dtype,
+        };
+        if let Some(output) = meta_shape_rule(&[self], &shape, dtype) {
+            return output;
+        }
+        let mut data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+
+        let (outer, size, inner) = lane_layout(&shape, d);
+        for o in 0..outer {
+            for n in 0..inner {
+                for i in 1..size {
+                    let (prev, cur) = ((o * size + i - 1) * inner + n, (o * size + i) * inner + n);
+                    data[cur] = op(data[prev], data[cur]);
+                }
+            }
+        }
+
+        Self::from_f64_data(&data, &shape, Options::default().dtype(dtype).device(self.device()))
+    }
+
+    fn reverse_scan<F: Fn(f64, f64) -> f64>(&self, dim: i64, op: F) -> Self {
+        let (shape, d) = match self.dim_info(dim) {
+            Some(info) => info,
+            None => return Self::new(),
+        };
+        let mut data = match self.impl_.as_ref().map(|impl_| impl_.to_f64_list()) {
+            Some(Ok(data)) => data,
+            _ => return Self::new(),
+        };
+
+        let (outer, size, inner) = lane_layout(&shape, d);
+        for o in 0..outer {
+            for n in 0..inner {
+                for i in (0..size.saturating_sub(1)).rev() {
+                    let (next, cur) = ((o * size + i + 1) * inner + n, (o * size + i) * inner + n);
+                    data[cur] = op(data[next], data[cur]);
+                }
+            }
+        }
+        Self::from_f64_data(&data, &shape, Options::default().dtype(self.dtype()))
+    }
+}
+
+impl LaneSelect {
+    fn select(self, lane: &[usize], keys: &SortKeys) -> Vec<usize> {
+        let ordered = |descending: bool| {
+            let mut order: Vec<usize> = (0..lane.len()).collect();
+            order.sort_by(|&a, &b| {
+                let ordering = keys.cmp(lane[a], lane[b]);
+                if descending { ordering.reverse() } else { ordering }
+            });
+            order
+        };
+        match self {
+            Self::Sort { descending } => ordered(descending),
+            Self::TopK { k, largest, sorted } => {
+                let mut order = ordered(largest);
+                order.truncate(k);
+                if !sorted {
+                    order.sort_unstable();
+                }
+                order
+            }
+            Self::Kth { k } => vec![ordered(false)[k - 1]],
+            Self::Mode => {
+                let mut counts: HashMap<u64, (usize, usize)> = HashMap::new();
+                for (i, &offset) in lane.iter().enumerate() {
+                    let entry = counts.entry(keys.bits(offset)).or_insert((0, i));
+                    entry.0 += 1;
+                    entry.1 = i;
+                }
+                let best = counts
+                    .values()
+                    .max_by(|a, b| a.0.cmp(&b.0).then_with(|| keys.cmp(lane[b.1], lane[a.1])))
+                    .map(|&(_, i)| i)
+                    .unwrap_or(0);
+                vec![best]
+            }
+            Self::Cummax => {
+                let mut best = 0;
+                (0..lane.len())
+                    .map(|i| {
+                        if keys.cmp(lane[i], lane[best]) != Ordering::Less {
+                            best = i;
+                        }
+                        best
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+pub enum SortKeys {
+    Float(Vec<f64>),
+    Int(Vec<i64>),
+}
+
+impl SortKeys {
+    pub fn read(impl_: &TensorImpl) -> Result<Self, String> {
+        match impl_.dtype() {
+            DType::Int64 => impl_.to_list::<i64>().map(Self::Int),
+            _ => impl_.to_f64_list().map(Self::Float),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Float(values) => values.len(),
+            Self::Int(values) => values.len(),
+        }
+    }
+
+    pub fn cmp(&self, a: usize, b: usize) -> Ordering {
+        match self {
+            Self::Float(values) => total_cmp(values[a], values[b]),
+            Self::Int(values) => values[a].cmp(&values[b]),
+        }
+    }
+
+    pub fn bits(&self, i: usize) -> u64 {
+        match self {
+            Self::Float(values) => values[i].to_bits(),
+            Self::Int(values) => values[i] as u64,
+        }
+    }
+}
+
+fn total_cmp(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+fn lane_layout(shape: &[i64], dim: usize) -> (usize, usize, usize) {
+    if shape.is_empty() {
+        return (1, 1, 1);
+    }
+    let outer: i64 = shape[..dim].iter().product();
+    let inner: i64 = shape[dim + 1..].iter().product();
+    (outer as usize, shape[dim] as usize, inner as usize)
+}
+
+fn cumprod_backward(input: &Tensor, grad: &Tensor, dim: i64) -> Tensor {
+    let (shape, d) = match input.dim_info(dim) {
+        Some(info) => info,
+        None => return Tensor::new(),
+    };
+    let (x, g) = match (
+        input.impl_.as_ref().map(|impl_| impl_.to_f64_list()),
+        grad.impl_.as_ref().map(|impl_| impl_.to_f64_list()),
+    ) {
+        (Some(Ok(x)), Some(Ok(g))) => (x, g),
+        _ => return Tensor::new(),
+    };
+
+    let (outer, size, inner) = lane_layout(&shape, d);
+    let mut result = vec![0.0; x.len()];
+    for o in 0..outer {
+        for n in 0..inner {
+            let at = |i: usize| (o * size + i) * inner + n;
+            let mut suffix = 0.0;
+            for i in (0..size).rev() {
+                let next = if i + 1 < size { x[at(i + 1)] } else { 0.0 };
+                suffix = g[at(i)] + next * suffix;
+                result[at(i)] = suffix;
+            }
+            let mut prefix = 1.0;
+            for i in 0..size {
+                result[at(i)] *= prefix;
+                prefix *= x[at(i)];
+            }
+        }
+    }
+    Tensor::from_f64_data(&result, &shape, Options::default().dtype(grad.dtype()))
+}
@@ -1,45 +1,224 @@
-use crate::tensor::Device;
-use std::alloc::{alloc, dealloc, Layout};
+use crate::tensor::{allocator, backend, Device};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use std::alloc::{dealloc, Layout};
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapMode {
+    ReadOnly,
+    CopyOnWrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Owned,
+    Mapped(MapMode),
+    Borrowed,
+    Foreign,
+}
+
+type Deleter = Box<dyn FnOnce(*mut u8, usize) + Send + Sync>;
+
+enum Allocation {
+    Empty,
+    Meta,
+    Cached(usize),
+    Owned(Layout),
+    Mapped(Mmap),
+    MappedMut(MmapMut),
+    Borrowed(Option<Arc<AtomicBool>>),
+    Foreign(Option<Deleter>),
+}
+
+impl fmt::Debug for Allocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Allocation::Empty => f.write_str("Empty"),
+            Allocation::Meta => f.write_str("Meta"),
+            Allocation::Cached(block_size) => f.debug_tuple("Cached").field(block_size).finish(),
+            Allocation::Owned(layout) => f.debug_tuple("Owned").field(layout).finish(),
+            Allocation::Mapped(_) => f.write_str("Mapped(ReadOnly)"),
+            Allocation::MappedMut(_) => f.write_str("Mapped(CopyOnWrite)"),
+            Allocation::Borrowed(_) => f.write_str("Borrowed"),
+            Allocation::Foreign(_) => f.write_str("Foreign"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Storage {
     data: NonNull<u8>,
     size: usize,
     device: Device,
-    layout: Layout,
+    allocation: Allocation,
 }
 
 impl Storage {
     pub fn new(size: usize, device: Device) -> Result<Self, String> {
+        backend(device)?.allocate(size, device)
+    }
+
+    pub fn cpu(size: usize) -> Result<Self, String> {
         if size == 0 {
-            return Err("Storage size cannot be zero".to_string());
+            return Ok(Self::empty(Device::cpu()));
         }
+        let (data, block_size) = allocator::allocate(size)?;
+
+        Ok(Self {
+            data,
+            size,
+            device: Device::cpu(),
+            allocation: Allocation::Cached(block_size),
+        })
+    }
 
-        let layout = Layout::from_size_align(size, 8)
+    pub fn meta(size: usize) -> Self {
+        Self {
+            data: NonNull::<u64>::dangling().cast(),
+            size,
+            device: Device::meta(),
+            allocation: Allocation::Meta,
+        }
+    }
+
+    pub fn from_vec<T>(data: Vec<T>) -> Result<Self, String> {
+        let size = std::mem::size_of_val(data.as_slice());
+        if size == 0 {
+            return Ok(Self::empty(Device::cpu()));
+        }
+
+        let mut data = std::mem::ManuallyDrop::new(data);
+        let layout = Layout::array::<T>(data.capacity())
             .map_err(|e| format!("Invalid layout: {}", e))?;
+        let ptr = NonNull::new(data.as_mut_ptr() as *mut u8)
+            .ok_or("Cannot take ownership of null buffer")?;
 
-        let data = if device.is_cpu() {
-            unsafe {
-                let ptr = alloc(layout);
-                if ptr.is_null() {
-                    return Err("Failed to allocate memory".to_string());
-                }
-                NonNull::new_unchecked(ptr)
-            }
-        } else {
-            return Err("CUDA storage not yet implemented".to_string());
+        Ok(Self {
+            data: ptr,
+            size,
+            device: Device::cpu(),
+            allocation: Allocation::Owned(layout),
+        })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        offset: u64,
+        len: Option<usize>,
+        mode: MapMode,
+    ) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+        let file_len = file
+            .metadata()
+            .map_err(|e| format!("Failed to read file metadata: {}", e))?
+            .len();
+        if offset > file_len {
+            return Err(format!("Offset {} is past the end of a {} byte file", offset, file_len));
+        }
+
+        let size = len.unwrap_or((file_len - offset) as usize);
+        if size == 0 {
+            return Err("Storage size cannot be zero".to_string());
+        }
+        if offset + size as u64 > file_len {
+            return Err(format!(
+                "Mapping {} bytes at offset {} exceeds a {} byte file",
+                size, offset, file_len
+            ));
+        }
+
+        let mut options = MmapOptions::new();
+        options.offset(offset).len(size);
+        let allocation = match mode {
+            MapMode::ReadOnly => unsafe { options.map(&file) }.map(Allocation::Mapped),
+            MapMode::CopyOnWrite => unsafe { options.map_copy(&file) }.map(Allocation::MappedMut),
+        }
+        .map_err(|e| format!("Failed to map file: {}", e))?;
+
+        let ptr = match &allocation {
+            Allocation::Mapped(map) => map.as_ptr() as *mut u8,
+            Allocation::MappedMut(map) => map.as_ptr() as *mut u8,
+            _ => unreachable!(),
         };
 
         Ok(Self {
-            data,
+            data: NonNull::new(ptr).ok_or("Mapped region is null")?,
+            size,
+            device: Device::cpu(),
+            allocation,
+        })
+    }
+
+    pub fn from_static(data: &'static [u8]) -> Result<Self, String> {
+        Self::borrowed(data, None)
+    }
+
+    pub(crate) fn borrowed(data: &[u8], alive: Option<Arc<AtomicBool>>) -> Result<Self, String> {
+        if data.is_empty() {
+            return Ok(Self::empty(Device::cpu()));
+        }
+
+        Ok(Self {
+            data: NonNull::new(data.as_ptr() as *mut u8).ok_or("Cannot borrow null buffer")?,
+            size: data.len(),
+            device: Device::cpu(),
+            allocation: Allocation::Borrowed(alive),
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads and writes of `size` bytes until `deleter` runs.
+    pub unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        size: usize,
+        device: Device,
+        deleter: impl FnOnce(*mut u8, usize) + Send + Sync + 'static,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            data: NonNull::new(ptr).ok_or("Cannot take ownership of null buffer")?,
             size,
             device,
-            layout,
+            allocation: Allocation::Foreign(Some(Box::new(deleter))),
         })
     }
 
+    fn empty(device: Device) -> Self {
+        Self {
+            data: NonNull::<u64>::dangling().cast(),
+            size: 0,
+            device,
+            allocation: Allocation::Empty,
+        }
+    }
+
+    pub fn kind(&self) -> StorageKind {
+        match self.allocation {
+            Allocation::Empty | Allocation::Meta | Allocation::Cached(_) | Allocation::Owned(_) => StorageKind::Owned,
+            Allocation::Mapped(_) => StorageKind::Mapped(MapMode::ReadOnly),
+            Allocation::MappedMut(_) => StorageKind::Mapped(MapMode::CopyOnWrite),
+            Allocation::Borrowed(_) => StorageKind::Borrowed,
+            Allocation::Foreign(_) => StorageKind::Foreign,
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.allocation, Allocation::Mapped(_) | Allocation::Borrowed(_))
+    }
+
+    pub fn is_released(&self) -> bool {
+        matches!(&self.allocation, Allocation::Borrowed(Some(alive)) if !alive.load(Ordering::Acquire))
+    }
+
     pub fn data_ptr<T>(&self) -> *mut T {
+        if self.is_released() {
+            return std::ptr::null_mut();
+        }
         self.data.as_ptr() as *mut T
     }
 
@@ -52,6 +231,13 @@ impl Storage {
     }
 
     pub fn copy_from_slice<T>(&mut self, src: &[T]) -> Result<(), String> {
+        if self.is_read_only() {
+            return Err("Cannot write to read-only storage".to_string());
+        }
+        if self.device.is_meta() {
+            return Ok(());
+        }
+
         let src_size = std::mem::size_of_val(src);
         if src_size > self.size {
             return Err("Source data too large for storage".to_string());
@@ -67,7 +253,7 @@ impl Storage {
             }
             Ok(())
         } else {
-            Err("CUDA copy not yet implemented".to_string())
+            Err(format!("Copying into {} storage is not supported", self.device))
         }
     }
 
@@ -76,6 +262,9 @@ impl Storage {
         if dst_size > self.size {
             return Err("Destination slice too large".to_string());
         }
+        if self.is_released() {
+            return Err("Borrowed storage has been released".to_string());
+        }
 
         if self.device.is_cpu() {
             unsafe {
@@ -87,12 +276,22 @@ impl Storage {
             }
             Ok(())
         } else {
-            Err("CUDA copy not yet implemented".to_string())
+            Err(format!("Copying out of {} storage is not supported", self.device))
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> Result<Self, String> {
+        if self.is_released() {
+            return Err("Borrowed storage has been released".to_string());
+        }
         let new_storage = Self::new(self.size, self.device)?;
+        if !self.device.is_cpu() {
+            return match self.allocation {
+                Allocation::Meta => Ok(new_storage),
+                _ => Err(format!("Cloning {} storage is not supported", self.device)),
+            };
+        }
         unsafe {
             std::ptr::copy_nonoverlapping(
                 self.data.as_ptr(),
@@ -106,10 +305,21 @@ impl Storage {
 
 impl Drop for Storage {
     fn drop(&mut self) {
-        if self.device.is_cpu() {
-            unsafe {
-                dealloc(self.data.as_ptr(), self.layout);
+        match &mut self.allocation {
+            Allocation::Cached(block_size) => allocator::release(self.data, self.size, *block_size),
+            Allocation::Owned(layout) => {
+                if self.device.is_cpu() {
+                    unsafe {
+                        dealloc(self.data.as_ptr(), *layout);
+                    }
+                }
+            }
+            Allocation::Foreign(deleter) => {
+                if let Some(deleter) = deleter.take() {
+                    deleter(self.data.as_ptr(), self.size);
+                }
             }
+            Allocation::Empty | Allocation::Meta | Allocation::Mapped(_) | Allocation::MappedMut(_) | Allocation::Borrowed(_) => {}
         }
     }
 }
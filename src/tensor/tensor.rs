@@ -1,8 +1,10 @@
 use crate::tensor::{
-    Array1d, Array2d, Array3d, DType, Device, Options, Scalar, TensorImpl, TypeToDType,
-    flatten_2d, flatten_3d,
+    Array1d, Array2d, Array3d, BinaryOp, DType, Device, Generator, Options, Reduction, Scalar, TensorImpl,
+    TypeToDType, UnaryOp, backend, dispatch_device, flatten_2d, flatten_3d, int64_values, with_generator,
 };
+use crate::autograd::{is_grad_enabled, run_backward, AutogradMeta, Node};
 use rand::Rng;
+use std::cell::RefCell;
 use std::rc::Rc;
 
 #[derive(Debug)]
@@ -46,34 +48,31 @@ impl Tensor {
     }
 
     pub fn ones(shape: &[i64]) -> Self {
-        let options = Options::default();
-        let numel: usize = shape.iter().product::<i64>() as usize;
-        let data = vec![1.0f32; numel];
-        match TensorImpl::new_from_data(&data, shape, options) {
-            Ok(impl_) => Self {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Self::new(),
-        }
+        Self::ones_with_options(shape, Options::default())
+    }
+
+    pub fn ones_with_options(shape: &[i64], options: Options) -> Self {
+        Self::full_with_options(shape, 1.0, options)
     }
 
     pub fn zeros(shape: &[i64]) -> Self {
-        let options = Options::default();
-        let numel: usize = shape.iter().product::<i64>() as usize;
-        let data = vec![0.0f32; numel];
-        match TensorImpl::new_from_data(&data, shape, options) {
-            Ok(impl_) => Self {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Self::new(),
-        }
+        Self::zeros_with_options(shape, Options::default())
+    }
+
+    pub fn zeros_with_options(shape: &[i64], options: Options) -> Self {
+        Self::full_with_options(shape, 0.0, options)
     }
 
     pub fn rand(shape: &[i64]) -> Self {
+        Self::rand_with_generator(shape, None)
+    }
+
+    pub fn rand_with_generator(shape: &[i64], generator: Option<&mut Generator>) -> Self {
         let options = Options::default();
         let numel: usize = shape.iter().product::<i64>() as usize;
-        let mut rng = rand::thread_rng();
-        let data: Vec<f32> = (0..numel).map(|_| rng.gen::<f32>()).collect();
+        let data: Vec<f32> = with_generator(generator, |rng| {
+            (0..numel).map(|_| rng.gen::<f32>()).collect()
+        });
         match TensorImpl::new_from_data(&data, shape, options) {
             Ok(impl_) => Self {
                 impl_: Some(Rc::new(impl_)),
@@ -83,16 +82,21 @@ impl Tensor {
     }
 
     pub fn randn(shape: &[i64]) -> Self {
+        Self::randn_with_generator(shape, None)
+    }
+
+    pub fn randn_with_generator(shape: &[i64], generator: Option<&mut Generator>) -> Self {
         let options = Options::default();
         let numel: usize = shape.iter().product::<i64>() as usize;
-        let mut rng = rand::thread_rng();
-        let data: Vec<f32> = (0..numel)
-            .map(|_| {
-                let u1: f32 = rng.gen();
-                let u2: f32 = rng.gen();
-                (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
-            })
-            .collect();
+        let data: Vec<f32> = with_generator(generator, |rng| {
+            (0..numel)
+                .map(|_| {
+                    let u1: f32 = 1.0 - rng.gen::<f32>();
+                    let u2: f32 = rng.gen();
+                    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+                })
+                .collect()
+        });
         match TensorImpl::new_from_data(&data, shape, options) {
             Ok(impl_) => Self {
                 impl_: Some(Rc::new(impl_)),
@@ -102,12 +106,17 @@ impl Tensor {
     }
 
     pub fn bernoulli(shape: &[i64], p: f32) -> Self {
+        Self::bernoulli_with_generator(shape, p, None)
+    }
+
+    pub fn bernoulli_with_generator(shape: &[i64], p: f32, generator: Option<&mut Generator>) -> Self {
         let options = Options::default();
         let numel: usize = shape.iter().product::<i64>() as usize;
-        let mut rng = rand::thread_rng();
-        let data: Vec<f32> = (0..numel)
-            .map(|_| if rng.gen::<f32>() < p { 1.0 } else { 0.0 })
-            .collect();
+        let data: Vec<f32> = with_generator(generator, |rng| {
+            (0..numel)
+                .map(|_| if rng.gen::<f32>() < p { 1.0 } else { 0.0 })
+                .collect()
+        });
         match TensorImpl::new_from_data(&data, shape, options) {
             Ok(impl_) => Self {
                 impl_: Some(Rc::new(impl_)),
@@ -117,34 +126,15 @@ impl Tensor {
     }
 
     pub fn linspace(start: f32, end: f32, steps: usize) -> Self {
-        let options = Options::default();
-        let step_size = if steps > 1 { (end - start) / (steps - 1) as f32 } else { 0.0 };
-        let data: Vec<f32> = (0..steps)
-            .map(|i| start + i as f32 * step_size)
-            .collect();
-        let shape = [steps as i64];
-        match TensorImpl::new_from_data(&data, &shape, options) {
-            Ok(impl_) => Self {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Self::new(),
-        }
+        Self::linspace_with_options(start as f64, end as f64, steps, Options::default())
     }
 
     pub fn ones_like(other: &Self) -> Self {
-        if let Some(ref impl_) = other.impl_ {
-            Self::ones(impl_.shape())
-        } else {
-            Self::new()
-        }
+        Self::full_like(other, 1.0)
     }
 
     pub fn zeros_like(other: &Self) -> Self {
-        if let Some(ref impl_) = other.impl_ {
-            Self::zeros(impl_.shape())
-        } else {
-            Self::new()
-        }
+        Self::full_like(other, 0.0)
     }
 
     pub fn from_array_1d<T: TypeToDType + Clone>(data: Array1d<T>) -> Self {
@@ -286,73 +276,139 @@ impl Tensor {
         }
     }
 
-    pub fn flatten(&self) -> Self {
-        if let Some(ref impl_) = self.impl_ {
-            let total_elements = impl_.numel();
-            let new_shape = [total_elements];
-            let mut new_tensor = self.clone();
-            let _ = new_tensor.reshape_(&new_shape);
-            new_tensor
-        } else {
-            Self::new()
-        }
-    }
-
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> Self {
-        if let Some(ref impl_) = self.impl_ {
-            if let Some(storage) = impl_.storage() {
-                if let Ok(cloned_storage) = storage.as_ref().clone() {
-                    if let Ok(new_impl) = TensorImpl::new_with_storage(
-                        impl_.shape(),
-                        impl_.options().clone(),
-                        Rc::new(cloned_storage),
-                        impl_.storage_offset(),
-                    ) {
-                        return Self {
-                            impl_: Some(Rc::new(new_impl)),
-                        };
-                    }
-                }
-            }
+        match self.impl_.as_ref().map(|impl_| impl_.contiguous_copy()) {
+            Some(Ok(impl_)) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            _ => Self::new(),
         }
-        Self::new()
     }
 
     pub fn pow(&self, exponent: &Self) -> Self {
-        if !self.defined() || !exponent.defined() {
-            return Self::new();
-        }
+        let (base, exp) = (self.detach(), exponent.detach());
+        self.apply_binary(exponent, BinaryOp::Pow)
+            .with_grad_fn("PowBackward1", &[self, exponent], move |grad| {
+                let zero = Tensor::scalar(0.0f32);
+                let grad_base = (&exp * &base.apply_binary(&(&exp - 1.0f32), BinaryOp::Pow))
+                    .masked_fill(&exp.eq(&zero), 0.0);
+                let grad_exp = (&base.apply_binary(&exp, BinaryOp::Pow) * &base.apply_unary(UnaryOp::Log))
+                    .masked_fill(&base.gt(&zero).logical_not(), 0.0);
+                vec![grad * &grad_base, grad * &grad_exp]
+            })
+    }
 
-        let self_data = self.to_list::<f32>();
-        let exp_data = exponent.to_list::<f32>();
-        
-        let result_data: Vec<f32> = if exp_data.len() == 1 {
-            let exp_val = exp_data[0];
-            self_data.iter().map(|&x| x.powf(exp_val)).collect()
-        } else {
-            self_data.iter().zip(exp_data.iter())
-                .map(|(&x, &e)| x.powf(e))
-                .collect()
-        };
+    pub fn sum(&self) -> Self {
+        let input_shape = self.shape();
+        self.reduce(Reduction::Sum).with_grad_fn("SumBackward0", &[self], move |grad| {
+            vec![grad.expand(&input_shape)]
+        })
+    }
 
-        let shape = self.shape();
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Self {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Self::new(),
+    pub fn prod(&self) -> Self {
+        let input = self.detach();
+        self.reduce(Reduction::Prod).with_grad_fn("ProdBackward0", &[self], move |grad| {
+            let data = input.to_list::<f32>();
+            let zeros = data.iter().filter(|&&v| v == 0.0).count();
+            let nonzero_prod: f64 = data.iter().filter(|&&v| v != 0.0).map(|&v| v as f64).product();
+            let others: Vec<f64> = data
+                .iter()
+                .map(|&v| match (zeros, v == 0.0) {
+                    (0, _) => nonzero_prod / v as f64,
+                    (1, true) => nonzero_prod,
+                    _ => 0.0,
+                })
+                .collect();
+            let others = Self::from_f64_data(&others, &input.shape(), Options::default());
+            vec![grad * &others]
+        })
+    }
+
+    pub fn mean(&self) -> Self {
+        let input_shape = self.shape();
+        let count = self.numel();
+        self.reduce(Reduction::Mean).with_grad_fn("MeanBackward0", &[self], move |grad| {
+            vec![grad.expand(&input_shape) / count as f64]
+        })
+    }
+
+    pub fn max(&self) -> Self {
+        self.extremum(Reduction::Max, "MaxBackward1")
+    }
+
+    pub fn min(&self) -> Self {
+        self.extremum(Reduction::Min, "MinBackward1")
+    }
+
+    fn extremum(&self, reduction: Reduction, name: &'static str) -> Self {
+        let result = self.reduce(reduction);
+        let (input, best) = (self.detach(), result.detach());
+        result.with_grad_fn(name, &[self], move |grad| {
+            let mask = input.eq(&best).logical_or(&input.isnan().logical_and(&best.isnan()));
+            vec![&(grad * &mask) / &mask.sum()]
+        })
+    }
+
+    fn reduce(&self, reduction: Reduction) -> Self {
+        match self.impl_.as_ref().map(|impl_| backend(impl_.device())) {
+            Some(Ok(backend)) => (backend.kernels().reduce)(self, reduction),
+            _ => Self::new(),
         }
     }
 
-    pub fn sum(&self) -> Self {
-        if !self.defined() {
-            return Self::new();
+    pub(crate) fn reduce_cpu(&self, reduction: Reduction) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+
+        let dtype = reduction_dtype(impl_.dtype(), reduction);
+        if dtype != DType::Float32 {
+            let values = match impl_.dtype() {
+                DType::Bool => impl_.to_f64_list().map(|data| data.iter().map(|&v| v as i64).collect()).ok(),
+                _ => int64_values(impl_),
+            };
+            let values = match values {
+                Some(values) => values,
+                None => return Self::new(),
+            };
+            let value = match reduction {
+                Reduction::Sum => values.iter().fold(0i64, |acc, &v| acc.wrapping_add(v)),
+                Reduction::Prod => values.iter().fold(1i64, |acc, &v| acc.wrapping_mul(v)),
+                Reduction::Max => match values.iter().max() {
+                    Some(&value) => value,
+                    None => return Self::new(),
+                },
+                _ => match values.iter().min() {
+                    Some(&value) => value,
+                    None => return Self::new(),
+                },
+            };
+            return match dtype {
+                DType::Int64 => Self::scalar(value),
+                _ => Self::from_f64_data(&[value as f64], &[], Options::default().dtype(dtype)),
+            };
         }
 
-        let data = self.to_list::<f32>();
-        let sum_val = data.iter().sum::<f32>();
-        Self::scalar(sum_val)
+        let data = match impl_.to_f64_list() {
+            Ok(data) => data,
+            Err(_) => return Self::new(),
+        };
+        let value = match reduction {
+            Reduction::Sum => data.iter().fold(0.0, |acc, &v| acc + v),
+            Reduction::Prod => data.iter().fold(1.0, |acc, &v| acc * v),
+            Reduction::Mean if data.is_empty() => f64::NAN,
+            Reduction::Mean => data.iter().fold(0.0, |acc, &v| acc + v) / data.len() as f64,
+            Reduction::Max | Reduction::Min if data.is_empty() => return Self::new(),
+            Reduction::Max | Reduction::Min => {
+                let better = |v: f64, best: f64| if reduction == Reduction::Max { v > best } else { v < best };
+                data.iter().copied().fold(data[0], |best, v| {
+                    if v.is_nan() || (!best.is_nan() && better(v, best)) { v } else { best }
+                })
+            }
+        };
+        Self::scalar(value as f32)
     }
 
     pub fn backward(&self) {
@@ -361,12 +417,51 @@ impl Tensor {
     }
 
     pub fn backward_with_grad(&self, grad: &Self) {
-        if let Some(ref impl_) = self.impl_ {
-            if let Some(ref autograd_meta) = impl_.autograd_meta {
-                let mut meta = autograd_meta.borrow_mut();
-                meta.add_grad(grad.clone());
-            }
+        run_backward(self, grad);
+    }
+
+    pub fn grad_fn(&self) -> Option<Rc<Node>> {
+        self.impl_
+            .as_ref()
+            .and_then(|impl_| impl_.autograd_meta.as_ref())
+            .and_then(|meta| meta.borrow().grad_fn())
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.grad_fn().is_none()
+    }
+
+    pub fn detach(&self) -> Self {
+        match self.impl_.as_ref() {
+            Some(impl_) => match impl_.as_strided(impl_.shape(), impl_.strides(), impl_.storage_offset()) {
+                Ok(impl_) => Self {
+                    impl_: Some(Rc::new(impl_)),
+                },
+                Err(_) => Self::new(),
+            },
+            None => Self::new(),
+        }
+    }
+
+    pub(crate) fn with_grad_fn(
+        mut self,
+        name: &'static str,
+        inputs: &[&Tensor],
+        backward: impl Fn(&Tensor) -> Vec<Tensor> + 'static,
+    ) -> Self {
+        if !is_grad_enabled() || !inputs.iter().any(|input| input.requires_grad()) {
+            return self;
         }
+
+        let node = Node::new(name, inputs.iter().map(|&input| Clone::clone(input)).collect(), backward);
+        if let Some(impl_) = self.impl_.as_mut().and_then(Rc::get_mut) {
+            let mut meta = AutogradMeta::new();
+            meta.set_requires_grad(true);
+            meta.set_grad_fn(Some(Rc::new(node)));
+            impl_.autograd_meta = Some(Rc::new(RefCell::new(meta)));
+            impl_.options_mut().requires_grad = true;
+        }
+        self
     }
 
     pub fn set_requires_grad(&mut self, requires_grad: bool) {
@@ -399,6 +494,18 @@ impl Tensor {
     }
 
     pub fn matmul(&self, other: &Self) -> Self {
+        let result = match dispatch_device(&[self, other]).map(backend) {
+            Some(Ok(backend)) => (backend.kernels().matmul)(self, other),
+            _ => return Self::new(),
+        };
+
+        let (a, b) = (self.detach(), other.detach());
+        result.with_grad_fn("MmBackward0", &[self, other], move |grad| {
+            vec![grad.matmul(&b.transpose(0, 1)), a.transpose(0, 1).matmul(grad)]
+        })
+    }
+
+    pub(crate) fn matmul_cpu(&self, other: &Self) -> Self {
         if !self.defined() || !other.defined() {
             return Self::new();
         }
@@ -440,47 +547,6 @@ impl Tensor {
         }
     }
 
-    pub fn transpose(&self, dim0: i64, dim1: i64) -> Self {
-        if !self.defined() {
-            return Self::new();
-        }
-        
-        let shape = self.shape();
-        if shape.len() != 2 || dim0 != 0 || dim1 != 1 {
-            return self.clone(); // Only 2D transpose supported for now
-        }
-        
-        let rows = shape[0] as usize;
-        let cols = shape[1] as usize;
-        let data = self.to_list::<f32>();
-        let mut transposed = vec![0.0f32; rows * cols];
-        
-        for i in 0..rows {
-            for j in 0..cols {
-                transposed[j * rows + i] = data[i * cols + j];
-            }
-        }
-        
-        let new_shape = [cols as i64, rows as i64];
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&transposed, &new_shape, options) {
-            Ok(impl_) => Self {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Self::new(),
-        }
-    }
-
-    pub fn reshape(&self, shape: &[i64]) -> Self {
-        if !self.defined() {
-            return Self::new();
-        }
-        
-        let mut new_tensor = self.clone();
-        let _ = new_tensor.reshape_(shape);
-        new_tensor
-    }
-
     pub fn size(&self) -> i64 {
         self.numel()
     }
@@ -528,48 +594,11 @@ impl std::ops::Add for &Tensor {
     type Output = Tensor;
 
     fn add(self, other: &Tensor) -> Tensor {
-        if !self.defined() || !other.defined() {
-            return Tensor::new();
-        }
-
-        let self_shape = self.shape();
-        let other_shape = other.shape();
-        
-        let result_shape = match crate::tensor::broadcasting::broadcast_shapes(&self_shape, &other_shape) {
-            Ok(shape) => shape,
-            Err(_) => return Tensor::new(),
-        };
-        
-        let self_data = if self_shape == result_shape {
-            self.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&self.to_list::<f32>(), &self_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let other_data = if other_shape == result_shape {
-            other.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&other.to_list::<f32>(), &other_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let result_data: Vec<f32> = self_data.iter().zip(other_data.iter())
-            .map(|(&a, &b)| a + b)
-            .collect();
-
-        let shape = result_shape;
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        self.integer_binary_op(other, i64::wrapping_add)
+            .unwrap_or_else(|| self.apply_binary(other, BinaryOp::Add))
+            .with_grad_fn("AddBackward0", &[self, other], |grad| {
+                vec![Clone::clone(grad), Clone::clone(grad)]
+            })
     }
 }
 
@@ -577,48 +606,11 @@ impl std::ops::Sub for &Tensor {
     type Output = Tensor;
 
     fn sub(self, other: &Tensor) -> Tensor {
-        if !self.defined() || !other.defined() {
-            return Tensor::new();
-        }
-
-        let self_shape = self.shape();
-        let other_shape = other.shape();
-        
-        let result_shape = match crate::tensor::broadcasting::broadcast_shapes(&self_shape, &other_shape) {
-            Ok(shape) => shape,
-            Err(_) => return Tensor::new(),
-        };
-        
-        let self_data = if self_shape == result_shape {
-            self.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&self.to_list::<f32>(), &self_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let other_data = if other_shape == result_shape {
-            other.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&other.to_list::<f32>(), &other_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let result_data: Vec<f32> = self_data.iter().zip(other_data.iter())
-            .map(|(&a, &b)| a - b)
-            .collect();
-
-        let shape = result_shape;
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        self.integer_binary_op(other, i64::wrapping_sub)
+            .unwrap_or_else(|| self.apply_binary(other, BinaryOp::Sub))
+            .with_grad_fn("SubBackward0", &[self, other], |grad| {
+                vec![Clone::clone(grad), -grad]
+            })
     }
 }
 
@@ -626,48 +618,12 @@ impl std::ops::Mul for &Tensor {
     type Output = Tensor;
 
     fn mul(self, other: &Tensor) -> Tensor {
-        if !self.defined() || !other.defined() {
-            return Tensor::new();
-        }
-
-        let self_shape = self.shape();
-        let other_shape = other.shape();
-        
-        let result_shape = match crate::tensor::broadcasting::broadcast_shapes(&self_shape, &other_shape) {
-            Ok(shape) => shape,
-            Err(_) => return Tensor::new(),
-        };
-        
-        let self_data = if self_shape == result_shape {
-            self.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&self.to_list::<f32>(), &self_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let other_data = if other_shape == result_shape {
-            other.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&other.to_list::<f32>(), &other_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let result_data: Vec<f32> = self_data.iter().zip(other_data.iter())
-            .map(|(&a, &b)| a * b)
-            .collect();
-
-        let shape = result_shape;
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
+        let (a, b) = (self.detach(), other.detach());
+        self.integer_binary_op(other, i64::wrapping_mul)
+            .unwrap_or_else(|| self.apply_binary(other, BinaryOp::Mul))
+            .with_grad_fn("MulBackward0", &[self, other], move |grad| {
+                vec![grad * &b, grad * &a]
+            })
     }
 }
 
@@ -675,109 +631,30 @@ impl std::ops::Div for &Tensor {
     type Output = Tensor;
 
     fn div(self, other: &Tensor) -> Tensor {
-        if !self.defined() || !other.defined() {
-            return Tensor::new();
-        }
-
-        let self_shape = self.shape();
-        let other_shape = other.shape();
-        
-        let result_shape = match crate::tensor::broadcasting::broadcast_shapes(&self_shape, &other_shape) {
-            Ok(shape) => shape,
-            Err(_) => return Tensor::new(),
-        };
-        
-        let self_data = if self_shape == result_shape {
-            self.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&self.to_list::<f32>(), &self_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let other_data = if other_shape == result_shape {
-            other.to_list::<f32>()
-        } else {
-            match crate::tensor::broadcasting::broadcast_tensor_data(&other.to_list::<f32>(), &other_shape, &result_shape) {
-                Ok(data) => data,
-                Err(_) => return Tensor::new(),
-            }
-        };
-        
-        let result_data: Vec<f32> = self_data.iter().zip(other_data.iter())
-            .map(|(&a, &b)| if b != 0.0 { a / b } else { 0.0 })
-            .collect();
-
-        let shape = result_shape;
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &shape, options) {
-            Ok(impl_) => Tensor {
-                impl_: Some(Rc::new(impl_)),
-            },
-            Err(_) => Tensor::new(),
-        }
-    }
-}
-
-impl std::ops::Add<&Tensor> for Tensor {
-    type Output = Tensor;
-
-    fn add(self, other: &Tensor) -> Tensor {
-        &self + other
-    }
-}
-
-impl std::ops::Mul<&Tensor> for Tensor {
-    type Output = Tensor;
-
-    fn mul(self, other: &Tensor) -> Tensor {
-        &self * other
-    }
-}
-
-impl std::ops::Div<&Tensor> for Tensor {
-    type Output = Tensor;
-
-    fn div(self, other: &Tensor) -> Tensor {
-        &self / other
+        let (a, b) = (self.detach(), other.detach());
+        self.apply_binary(other, BinaryOp::Div)
+            .with_grad_fn("DivBackward0", &[self, other], move |grad| {
+                let grad_a = grad / &b;
+                let grad_b = -&(&grad_a * &a) / &b;
+                vec![grad_a, grad_b]
+            })
     }
 }
 
 impl Tensor {
     pub fn sqrt(&self) -> Self {
-        if !self.defined() {
-            return Self::new();
-        }
-        
-        let data: Vec<f32> = self.to_list::<f32>().iter()
-            .map(|&x| x.sqrt())
-            .collect();
-        
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&data, &self.shape(), options) {
-            Ok(impl_) => Self { impl_: Some(Rc::new(impl_)) },
-            Err(_) => Self::new(),
-        }
+        self.apply_unary(UnaryOp::Sqrt)
     }
     
     pub fn max_elementwise(&self, other: &Self) -> Self {
-        if !self.defined() || !other.defined() {
-            return Self::new();
-        }
-        
-        let self_data = self.to_list::<f32>();
-        let other_data = other.to_list::<f32>();
-        
-        let result_data: Vec<f32> = self_data.iter()
-            .zip(other_data.iter())
-            .map(|(&a, &b)| a.max(b))
-            .collect();
-        
-        let options = Options::default().dtype(DType::Float32);
-        match TensorImpl::new_from_data(&result_data, &self.shape(), options) {
-            Ok(impl_) => Self { impl_: Some(Rc::new(impl_)) },
-            Err(_) => Self::new(),
-        }
+        self.apply_binary(other, BinaryOp::Maximum)
+    }
+}
+
+pub(crate) fn reduction_dtype(dtype: DType, reduction: Reduction) -> DType {
+    match (dtype, reduction) {
+        (DType::Int32 | DType::Int64 | DType::Bool, Reduction::Sum | Reduction::Prod) => DType::Int64,
+        (DType::Int32 | DType::Int64 | DType::Bool, Reduction::Max | Reduction::Min) => dtype,
+        _ => DType::Float32,
     }
 }
@@ -1,4 +1,7 @@
-use crate::tensor::{check_dtype_match, Device, DType, Options, Storage, TypeToDType};
+use crate::tensor::{
+    bf16_to_f32, check_dtype_match, f16_to_f32, f32_to_bf16, f32_to_f16, Device, DType, Options,
+    Storage, TypeToDType,
+};
 use crate::autograd::AutogradMeta;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -6,7 +9,7 @@ use std::cell::RefCell;
 pub type IntArrayView = [i64];
 pub type SizeVector = Vec<i64>;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct TensorImpl {
     shape: SizeVector,
     strides: SizeVector,
@@ -19,8 +22,14 @@ pub struct TensorImpl {
 
 impl TensorImpl {
     pub fn new(shape: &IntArrayView, options: Options) -> Result<Self, String> {
+        if shape.iter().any(|&dim| dim < 0) {
+            return Err(format!("Invalid shape {:?}: dimensions must be non-negative", shape));
+        }
+
         let autograd_meta = if options.requires_grad_value() {
-            Some(Rc::new(RefCell::new(AutogradMeta::new())))
+            let mut meta = AutogradMeta::new();
+            meta.set_requires_grad(true);
+            Some(Rc::new(RefCell::new(meta)))
         } else {
             None
         };
@@ -49,7 +58,9 @@ impl TensorImpl {
         offset: i64,
     ) -> Result<Self, String> {
         let autograd_meta = if options.requires_grad_value() {
-            Some(Rc::new(RefCell::new(AutogradMeta::new())))
+            let mut meta = AutogradMeta::new();
+            meta.set_requires_grad(true);
+            Some(Rc::new(RefCell::new(meta)))
         } else {
             None
         };
@@ -96,6 +107,51 @@ impl TensorImpl {
         Ok(impl_)
     }
 
+    pub fn new_from_f64(
+        data: &[f64],
+        shape: &IntArrayView,
+        options: Options,
+    ) -> Result<Self, String> {
+        let mut impl_ = Self::new(shape, options)?;
+
+        if data.len() != impl_.numel as usize {
+            return Err(format!(
+                "Data length {} doesn't match tensor numel {}",
+                data.len(),
+                impl_.numel
+            ));
+        }
+
+        match impl_.dtype() {
+            DType::Float32 => {
+                let values: Vec<f32> = data.iter().map(|&v| v as f32).collect();
+                impl_.fill_storage(&values)?;
+            }
+            DType::Float16 => {
+                let values: Vec<u16> = data.iter().map(|&v| f32_to_f16(v as f32)).collect();
+                impl_.fill_storage(&values)?;
+            }
+            DType::BFloat16 => {
+                let values: Vec<u16> = data.iter().map(|&v| f32_to_bf16(v as f32)).collect();
+                impl_.fill_storage(&values)?;
+            }
+            DType::Int32 => {
+                let values: Vec<i32> = data.iter().map(|&v| v as i32).collect();
+                impl_.fill_storage(&values)?;
+            }
+            DType::Int64 => {
+                let values: Vec<i64> = data.iter().map(|&v| v as i64).collect();
+                impl_.fill_storage(&values)?;
+            }
+            DType::Bool => {
+                let values: Vec<u8> = data.iter().map(|&v| (v != 0.0) as u8).collect();
+                impl_.fill_storage(&values)?;
+            }
+        }
+
+        Ok(impl_)
+    }
+
     pub fn dtype(&self) -> DType {
         self.options.dtype
     }
@@ -129,8 +185,16 @@ impl TensorImpl {
     }
 
     pub fn data_ptr<T>(&self) -> *mut T {
+        if !self.device().is_cpu() {
+            return std::ptr::null_mut();
+        }
         if let Some(ref storage) = self.storage {
-            storage.data_ptr::<T>()
+            let base = storage.data_ptr::<u8>();
+            if base.is_null() {
+                return std::ptr::null_mut();
+            }
+            let offset = self.storage_offset as usize * self.options.dtype.size();
+            base.wrapping_add(offset) as *mut T
         } else {
             std::ptr::null_mut()
         }
@@ -171,7 +235,14 @@ impl TensorImpl {
         self.storage_offset = offset;
     }
 
+    pub fn is_read_only(&self) -> bool {
+        self.storage.as_ref().is_some_and(|storage| storage.is_read_only())
+    }
+
     pub fn reshape_(&mut self, shape: &IntArrayView) -> Result<(), String> {
+        if !self.is_contiguous() {
+            return Err("Cannot reshape non-contiguous tensor in place".to_string());
+        }
         let new_numel = shape.iter().product::<i64>();
         if new_numel != self.numel {
             return Err(format!(
@@ -192,6 +263,9 @@ impl TensorImpl {
         if start < 0 || end >= self.dim() || start > end {
             return Err("Invalid flatten dimensions".to_string());
         }
+        if !self.is_contiguous() {
+            return Err("Cannot flatten non-contiguous tensor in place".to_string());
+        }
 
         let mut new_shape = Vec::new();
         
@@ -265,14 +339,96 @@ impl TensorImpl {
                 return Err("Null data pointer".to_string());
             }
             
-            let mut result = vec![T::default(); self.numel as usize];
-            unsafe {
-                std::ptr::copy_nonoverlapping(ptr, result.as_mut_ptr(), self.numel as usize);
-            }
-            Ok(result)
+            Ok(self.gather_elements::<T>())
         } else {
-            Err("CUDA tensor to_list not yet implemented".to_string())
+            Err(format!("Cannot read {} tensor data on the host", self.device()))
+        }
+    }
+
+    pub fn to_f64_list(&self) -> Result<Vec<f64>, String> {
+        if !self.device().is_cpu() {
+            return Err(format!("Cannot read {} tensor data on the host", self.device()));
         }
+
+        if self.data_ptr::<u8>().is_null() {
+            return Err("Null data pointer".to_string());
+        }
+
+        let values = match self.dtype() {
+            DType::Float32 => self.gather_elements::<f32>()
+                .iter()
+                .map(|&v| v as f64)
+                .collect(),
+            DType::Float16 => self.gather_elements::<u16>()
+                .iter()
+                .map(|&v| f16_to_f32(v) as f64)
+                .collect(),
+            DType::BFloat16 => self.gather_elements::<u16>()
+                .iter()
+                .map(|&v| bf16_to_f32(v) as f64)
+                .collect(),
+            DType::Int32 => self.gather_elements::<i32>()
+                .iter()
+                .map(|&v| v as f64)
+                .collect(),
+            DType::Int64 => self.gather_elements::<i64>()
+                .iter()
+                .map(|&v| v as f64)
+                .collect(),
+            DType::Bool => self.gather_elements::<u8>()
+                .iter()
+                .map(|&v| if v != 0 { 1.0 } else { 0.0 })
+                .collect(),
+        };
+        Ok(values)
+    }
+
+    pub fn write_f64(&self, data: &[f64]) -> Result<(), String> {
+        if data.len() != self.numel as usize {
+            return Err(format!(
+                "Data length {} doesn't match tensor numel {}",
+                data.len(),
+                self.numel
+            ));
+        }
+        if !self.device().is_cpu() {
+            return Err(format!("Cannot write {} tensor data from the host", self.device()));
+        }
+
+        if self.data_ptr::<u8>().is_null() {
+            return Err("Null data pointer".to_string());
+        }
+        if self.is_read_only() {
+            return Err("Cannot write to a tensor backed by read-only storage".to_string());
+        }
+
+        match self.dtype() {
+            DType::Float32 => {
+                let values: Vec<f32> = data.iter().map(|&v| v as f32).collect();
+                self.scatter_elements::<f32>(&values);
+            }
+            DType::Float16 => {
+                let values: Vec<u16> = data.iter().map(|&v| f32_to_f16(v as f32)).collect();
+                self.scatter_elements::<u16>(&values);
+            }
+            DType::BFloat16 => {
+                let values: Vec<u16> = data.iter().map(|&v| f32_to_bf16(v as f32)).collect();
+                self.scatter_elements::<u16>(&values);
+            }
+            DType::Int32 => {
+                let values: Vec<i32> = data.iter().map(|&v| v as i32).collect();
+                self.scatter_elements::<i32>(&values);
+            }
+            DType::Int64 => {
+                let values: Vec<i64> = data.iter().map(|&v| v as i64).collect();
+                self.scatter_elements::<i64>(&values);
+            }
+            DType::Bool => {
+                let values: Vec<u8> = data.iter().map(|&v| (v != 0.0) as u8).collect();
+                self.scatter_elements::<u8>(&values);
+            }
+        }
+        Ok(())
     }
 
     pub fn item<T: TypeToDType + Clone + Default>(&self) -> Result<T, String> {
@@ -290,10 +446,70 @@ impl TensorImpl {
             
             unsafe { Ok(ptr.read()) }
         } else {
-            Err("CUDA tensor item not yet implemented".to_string())
+            Err(format!("Cannot read {} tensor data on the host", self.device()))
         }
     }
 
+    pub fn as_strided(&self, shape: &IntArrayView, strides: &IntArrayView, offset: i64) -> Result<Self, String> {
+        if shape.len() != strides.len() {
+            return Err(format!(
+                "Shape {:?} and strides {:?} have different ranks",
+                shape, strides
+            ));
+        }
+        if shape.iter().any(|&dim| dim < 0) || offset < 0 {
+            return Err("View shape and offset must be non-negative".to_string());
+        }
+
+        let storage = self.storage.clone().ok_or("Cannot view tensor without storage")?;
+        let capacity = (storage.size() / self.options.dtype.size()) as i64;
+        let numel: i64 = shape.iter().product();
+        if numel > 0 {
+            let (mut low, mut high) = (offset, offset);
+            for (&dim, &stride) in shape.iter().zip(strides.iter()) {
+                let extent = (dim - 1) * stride;
+                if extent < 0 { low += extent } else { high += extent }
+            }
+            if low < 0 || high >= capacity {
+                return Err(format!(
+                    "View with shape {:?}, strides {:?} and offset {} is out of storage bounds",
+                    shape, strides, offset
+                ));
+            }
+        }
+
+        Ok(Self {
+            shape: shape.to_vec(),
+            strides: strides.to_vec(),
+            numel,
+            storage_offset: offset,
+            options: self.options.no_grad(),
+            storage: Some(storage),
+            autograd_meta: None,
+        })
+    }
+
+    pub fn contiguous_copy(&self) -> Result<Self, String> {
+        let mut impl_ = Self::new(&self.shape, self.options.clone())?;
+        match self.dtype() {
+            DType::Float32 => impl_.fill_storage(&self.gather_elements::<f32>())?,
+            DType::Float16 | DType::BFloat16 => impl_.fill_storage(&self.gather_elements::<u16>())?,
+            DType::Int32 => impl_.fill_storage(&self.gather_elements::<i32>())?,
+            DType::Int64 => impl_.fill_storage(&self.gather_elements::<i64>())?,
+            DType::Bool => impl_.fill_storage(&self.gather_elements::<u8>())?,
+        }
+        Ok(impl_)
+    }
+
+    fn fill_storage<T>(&mut self, data: &[T]) -> Result<(), String> {
+        if let Some(ref mut storage) = self.storage {
+            let storage_mut = Rc::get_mut(storage)
+                .ok_or("Cannot get mutable reference to storage")?;
+            storage_mut.copy_from_slice(data)?;
+        }
+        Ok(())
+    }
+
     fn ensure_storage(&mut self) -> Result<(), String> {
         if self.storage.is_none() {
             let size = (self.numel as usize) * self.options.dtype.size();
@@ -304,6 +520,7 @@ impl TensorImpl {
     }
 
     pub fn set_requires_grad(&mut self, requires_grad: bool) {
+        self.options.requires_grad = requires_grad;
         if requires_grad && self.autograd_meta.is_none() {
             self.autograd_meta = Some(Rc::new(RefCell::new(AutogradMeta::new())));
         }
@@ -318,7 +535,7 @@ impl TensorImpl {
 
         if !shape.is_empty() {
             for i in (0..shape.len() - 1).rev() {
-                strides[i] = strides[i + 1] * shape[i + 1];
+                strides[i] = strides[i + 1] * shape[i + 1].max(1);
             }
         }
     }
@@ -327,17 +544,3 @@ impl TensorImpl {
         *numel = shape.iter().product();
     }
 }
-
-impl Default for TensorImpl {
-    fn default() -> Self {
-        Self {
-            shape: Vec::new(),
-            strides: Vec::new(),
-            numel: 0,
-            storage_offset: 0,
-            options: Options::default(),
-            storage: None,
-            autograd_meta: None,
-        }
-    }
-}
@@ -1,3 +1,5 @@
+#![allow(unused_imports, unused_variables, dead_code, clippy::module_inception, clippy::excessive_precision)]
+
 use super::*;
 
 #[cfg(test)]
@@ -114,7 +116,7 @@ mod tests {
         let scalar = Tensor::scalar(5.0f32);
         let tensor = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let result = &scalar + &tensor;
-        
+
         assert!(result.defined());
         assert_eq!(result.shape(), vec![3]);
         assert_eq!(result.to_list::<f32>(), vec![6.0, 7.0, 8.0]);
@@ -125,7 +127,7 @@ mod tests {
         let a = Tensor::from_array_2d(vec![vec![1.0f32, 2.0, 3.0]]);
         let b = Tensor::from_array_2d(vec![vec![4.0f32], vec![5.0]]);
         let result = &a + &b;
-        
+
         assert!(result.defined());
         assert_eq!(result.shape(), vec![2, 3]);
         assert_eq!(result.to_list::<f32>(), vec![5.0, 6.0, 7.0, 6.0, 7.0, 8.0]);
@@ -136,7 +138,7 @@ mod tests {
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0]);
         let b = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let result = &a + &b;
-        
+
         assert!(!result.defined());
     }
 
@@ -144,19 +146,19 @@ mod tests {
     fn test_broadcasting_all_operations() {
         let a = Tensor::from_array_2d(vec![vec![2.0f32, 4.0]]);
         let b = Tensor::from_array_2d(vec![vec![1.0f32], vec![2.0]]);
-        
+
         let add_result = &a + &b;
         assert_eq!(add_result.shape(), vec![2, 2]);
         assert_eq!(add_result.to_list::<f32>(), vec![3.0, 5.0, 4.0, 6.0]);
-        
+
         let mul_result = &a * &b;
         assert_eq!(mul_result.shape(), vec![2, 2]);
         assert_eq!(mul_result.to_list::<f32>(), vec![2.0, 4.0, 4.0, 8.0]);
-        
+
         let sub_result = &a - &b;
         assert_eq!(sub_result.shape(), vec![2, 2]);
         assert_eq!(sub_result.to_list::<f32>(), vec![1.0, 3.0, 0.0, 2.0]);
-        
+
         let div_result = &a / &b;
         assert_eq!(div_result.shape(), vec![2, 2]);
         assert_eq!(div_result.to_list::<f32>(), vec![2.0, 4.0, 1.0, 2.0]);
@@ -167,7 +169,7 @@ mod tests {
         let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
         let b = Tensor::from_array_2d(vec![vec![1.0f32, 1.0, 1.0], vec![2.0, 2.0, 2.0]]);
         let result = &a + &b;
-        
+
         assert!(result.defined());
         assert_eq!(result.shape(), vec![2, 3]);
         assert_eq!(result.to_list::<f32>(), vec![2.0, 3.0, 4.0, 3.0, 4.0, 5.0]);
@@ -176,13 +178,1505 @@ mod tests {
     #[test]
     fn test_broadcasting_utilities() {
         use crate::tensor::broadcasting::{is_broadcastable, broadcast_shapes};
-        
+
         assert!(is_broadcastable(&[3], &[1, 3]));
         assert!(is_broadcastable(&[1, 3], &[2, 1]));
         assert!(!is_broadcastable(&[2], &[3]));
-        
+
         assert_eq!(broadcast_shapes(&[3], &[1, 3]).unwrap(), vec![1, 3]);
         assert_eq!(broadcast_shapes(&[1, 3], &[2, 1]).unwrap(), vec![2, 3]);
         assert!(broadcast_shapes(&[2], &[3]).is_err());
     }
+
+    #[test]
+    fn test_comparison_ops() {
+        let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+        let b = Tensor::from_array_1d(vec![3.0f32, 2.0, 1.0]);
+
+        let eq = a.eq(&b);
+        assert_eq!(eq.dtype(), DType::Bool);
+        assert_eq!(eq.to_list::<u8>(), vec![0, 1, 0]);
+        assert_eq!(a.ne(&b).to_list::<u8>(), vec![1, 0, 1]);
+        assert_eq!(a.lt(&b).to_list::<u8>(), vec![1, 0, 0]);
+        assert_eq!(a.le(&b).to_list::<u8>(), vec![1, 1, 0]);
+        assert_eq!(a.gt(&b).to_list::<u8>(), vec![0, 0, 1]);
+        assert_eq!(a.ge(&b).to_list::<u8>(), vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_comparison_broadcasting() {
+        let a = Tensor::from_array_2d(vec![vec![1.0f32], vec![2.0]]);
+        let b = Tensor::from_array_1d(vec![1i64, 2, 3]);
+
+        let result = a.lt(&b);
+        assert_eq!(result.shape(), vec![2, 3]);
+        assert_eq!(result.to_list::<u8>(), vec![0, 1, 1, 0, 0, 1]);
+
+        let scalar = a.ge(&Tensor::scalar(2.0f32));
+        assert_eq!(scalar.to_list::<u8>(), vec![0, 1]);
+
+        assert!(!Tensor::from_array_1d(vec![1.0f32, 2.0]).eq(&Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0])).defined());
+    }
+
+    #[test]
+    fn test_logical_ops() {
+        let a = Tensor::from_array_1d(vec![1u8, 1, 0, 0]);
+        let b = Tensor::from_array_1d(vec![1u8, 0, 1, 0]);
+
+        assert_eq!(a.logical_and(&b).to_list::<u8>(), vec![1, 0, 0, 0]);
+        assert_eq!(a.logical_or(&b).to_list::<u8>(), vec![1, 1, 1, 0]);
+        assert_eq!(a.logical_xor(&b).to_list::<u8>(), vec![0, 1, 1, 0]);
+        assert_eq!(a.logical_not().to_list::<u8>(), vec![0, 0, 1, 1]);
+
+        let x = Tensor::from_array_1d(vec![0.5f32, 0.0, -2.0]);
+        assert_eq!(x.logical_not().to_list::<u8>(), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_float_classification() {
+        let x = Tensor::from_array_1d(vec![1.0f32, f32::NAN, f32::INFINITY, f32::NEG_INFINITY]);
+        assert_eq!(x.isnan().to_list::<u8>(), vec![0, 1, 0, 0]);
+        assert_eq!(x.isinf().to_list::<u8>(), vec![0, 0, 1, 1]);
+        assert_eq!(x.isfinite().to_list::<u8>(), vec![1, 0, 0, 0]);
+
+        let ints = Tensor::from_array_1d(vec![1i32, 2]);
+        assert_eq!(ints.isfinite().to_list::<u8>(), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_allclose() {
+        let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+        let b = Tensor::from_array_1d(vec![1.0f32, 2.000001, 3.0]);
+        let c = Tensor::from_array_1d(vec![1.0f32, 2.1, 3.0]);
+
+        assert!(a.allclose(&b, 1e-5, 1e-8));
+        assert!(!a.allclose(&c, 1e-5, 1e-8));
+        assert_eq!(a.isclose(&c, 1e-5, 1e-8).to_list::<u8>(), vec![1, 0, 1]);
+        assert!(!a.allclose(&Tensor::from_array_1d(vec![1.0f32, 2.0]), 1e-5, 1e-8));
+    }
+
+    #[test]
+    fn test_masked_fill() {
+        let x = Tensor::from_array_2d(vec![vec![1.0f32, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        let mask = x.gt(&Tensor::scalar(3.0f32));
+        let filled = x.masked_fill(&mask, 0.0);
+        assert_eq!(filled.to_list::<f32>(), vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.0]);
+
+        let column_mask = Tensor::from_array_1d(vec![1u8, 0, 1]);
+        let filled = x.masked_fill(&column_mask, -1.0);
+        assert_eq!(filled.to_list::<f32>(), vec![-1.0, 2.0, -1.0, -1.0, 5.0, -1.0]);
+
+        let ints = Tensor::from_array_1d(vec![1i64, 2, 3]);
+        let filled = ints.masked_fill(&column_mask, 9.0);
+        assert_eq!(filled.dtype(), DType::Int64);
+        assert_eq!(filled.to_list::<i64>(), vec![9, 2, 9]);
+    }
+
+    #[test]
+    fn test_comparison_and_masked_fill_on_views() {
+        let x = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]).transpose(0, 1);
+        let mask = x.ge(&Tensor::from_array_1d(vec![1.0f32, 4.0]));
+        assert_eq!(mask.shape(), vec![3, 2]);
+        assert_eq!(mask.to_list::<u8>(), vec![0, 0, 1, 1, 1, 1]);
+
+        let filled = x.masked_fill(&mask.transpose(0, 1).transpose(0, 1), -1.0);
+        assert_eq!(filled.to_list::<f32>(), vec![0.0, 3.0, -1.0, -1.0, -1.0, -1.0]);
+        assert_eq!(x.to_list::<f32>(), vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+        assert!(!x.masked_fill(&Tensor::ones(&[2, 3]), 0.0).defined());
+    }
+
+    #[test]
+    fn test_integer_comparison_and_masked_fill_backward() {
+        let big = 1i64 << 53;
+        let a = Tensor::from_array_1d(vec![big, big + 1, -big - 1]);
+        let b = Tensor::from_array_1d(vec![big + 1, big + 1, -big]);
+        assert_eq!(a.eq(&b).to_list::<u8>(), vec![0, 1, 0]);
+        assert_eq!(a.lt(&b).to_list::<u8>(), vec![1, 0, 1]);
+        assert_eq!(a.ne(&Tensor::scalar(big)).to_list::<u8>(), vec![0, 1, 1]);
+        assert_eq!(a.isclose(&b, 0.0, 0.0).to_list::<u8>(), vec![0, 1, 0]);
+        let nan = Tensor::from_array_1d(vec![f32::NAN]);
+        assert_eq!(nan.ne(&nan).to_list::<u8>(), vec![1]);
+
+        let mut x = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+        x.set_requires_grad(true);
+        let mask = Tensor::from_array_1d(vec![0u8, 1, 0]);
+        let filled = x.masked_fill(&mask, 5.0);
+        assert_eq!(filled.grad_fn().unwrap().name(), "MaskedFillBackward0");
+        (&filled * &Tensor::from_array_1d(vec![2.0f32, 3.0, 4.0])).sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![2.0, 0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_nonzero_and_count_nonzero() {
+        let x = Tensor::from_array_2d(vec![vec![0.0f32, 1.0], vec![2.0, 0.0]]);
+
+        let nz = x.nonzero();
+        assert_eq!(nz.dtype(), DType::Int64);
+        assert_eq!(nz.shape(), vec![2, 2]);
+        assert_eq!(nz.to_list::<i64>(), vec![0, 1, 1, 0]);
+
+        let count = x.count_nonzero();
+        assert_eq!(count.dtype(), DType::Int64);
+        assert_eq!(count.item::<i64>(), 2);
+    }
+
+    #[test]
+    fn test_owned_and_borrowed_operators() {
+        let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+        let b = Tensor::from_array_1d(vec![4.0f32, 5.0, 6.0]);
+
+        assert_eq!((&a + &b).to_list::<f32>(), vec![5.0, 7.0, 9.0]);
+        assert_eq!((a.clone() + b.clone()).to_list::<f32>(), vec![5.0, 7.0, 9.0]);
+        assert_eq!((&a - b.clone()).to_list::<f32>(), vec![-3.0, -3.0, -3.0]);
+        assert_eq!((a.clone() * &b).to_list::<f32>(), vec![4.0, 10.0, 18.0]);
+        assert_eq!((b.clone() / a.clone()).to_list::<f32>(), vec![4.0, 2.5, 2.0]);
+        assert_eq!((&b % &a).to_list::<f32>(), vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_scalar_operators() {
+        let a = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+
+        assert_eq!((&a + 1.0f32).to_list::<f32>(), vec![2.0, 3.0, 4.0]);
+        assert_eq!((&a - 1.0f64).to_list::<f32>(), vec![0.0, 1.0, 2.0]);
+        assert_eq!((a.clone() * 2i64).to_list::<f32>(), vec![2.0, 4.0, 6.0]);
+        assert_eq!((&a / 2.0f32).to_list::<f32>(), vec![0.5, 1.0, 1.5]);
+        assert_eq!((&a % 2i64).to_list::<f32>(), vec![1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_neg_and_remainder_sign() {
+        let a = Tensor::from_array_1d(vec![-3.0f32, 0.5, 3.0]);
+
+        assert_eq!((-&a).to_list::<f32>(), vec![3.0, -0.5, -3.0]);
+        assert_eq!((-a.clone()).to_list::<f32>(), vec![3.0, -0.5, -3.0]);
+        assert_eq!((&a % 2.0f32).to_list::<f32>(), vec![1.0, 0.5, 1.0]);
+        assert_eq!((&a % -2.0f32).to_list::<f32>(), vec![-1.0, -1.5, -1.0]);
+    }
+
+    #[test]
+    fn test_compound_assignment_is_in_place() {
+        let mut a = Tensor::from_array_2d(vec![vec![1.0f32, 2.0], vec![3.0, 4.0]]);
+        let alias = Clone::clone(&a);
+
+        a += &Tensor::from_array_1d(vec![1.0f32, 1.0]);
+        a *= 2.0f32;
+        a -= 1i64;
+        a /= 2.0f64;
+        assert_eq!(a.to_list::<f32>(), vec![1.5, 2.5, 3.5, 4.5]);
+        assert_eq!(alias.to_list::<f32>(), vec![1.5, 2.5, 3.5, 4.5]);
+
+        a %= Tensor::scalar(2.0f32);
+        assert_eq!(alias.to_list::<f32>(), vec![1.5, 0.5, 1.5, 0.5]);
+
+        a.neg_().unwrap();
+        assert_eq!(alias.to_list::<f32>(), vec![-1.5, -0.5, -1.5, -0.5]);
+    }
+
+    #[test]
+    fn test_in_place_rejects_shape_change() {
+        let mut a = Tensor::from_array_1d(vec![1.0f32, 2.0]);
+        assert!(a.add_(&Tensor::ones(&[2, 2])).is_err());
+        assert!(a.add_(&Tensor::new()).is_err());
+        assert_eq!(a.to_list::<f32>(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be broadcast")]
+    fn test_compound_assignment_panics_on_shape_change() {
+        let mut a = Tensor::from_array_1d(vec![1.0f32, 2.0]);
+        a += &Tensor::from_array_2d(vec![vec![1.0f32], vec![2.0]]);
+    }
+
+    #[test]
+    fn test_in_place_writes_through_strided_views() {
+        let base = Tensor::from_array_2d(vec![vec![1.0f32, 2.0], vec![3.0, 4.0]]);
+        let mut column = base.transpose(0, 1).narrow(0, 0, 1);
+        column.mul_(&Tensor::scalar(10.0f32)).unwrap();
+        assert_eq!(base.to_list::<f32>(), vec![10.0, 2.0, 30.0, 4.0]);
+
+        let mut big = Tensor::from_vec(vec![(1i64 << 53) + 1, -7], &[2]);
+        big.add_(&Tensor::scalar(2i64)).unwrap();
+        assert_eq!(big.to_list::<i64>(), vec![(1i64 << 53) + 3, -5]);
+        big.rem_(&Tensor::scalar(3i64)).unwrap();
+        assert_eq!(big.to_list::<i64>(), vec![((1i64 << 53) + 3) % 3, 1]);
+    }
+
+    #[test]
+    fn test_in_place_rejects_tensors_requiring_grad() {
+        let mut leaf = Tensor::ones(&[2]);
+        leaf.set_requires_grad(true);
+        assert!(leaf.add_(&Tensor::ones(&[2])).is_err());
+        assert!(leaf.neg_().is_err());
+        let mut derived = &leaf * 2.0f32;
+        assert!(derived.mul_(&Tensor::ones(&[2])).is_err());
+        assert_eq!(leaf.to_list::<f32>(), vec![1.0, 1.0]);
+
+        {
+            let _guard = crate::autograd::NoGradGuard::new();
+            leaf.sub_(&Tensor::ones(&[2])).unwrap();
+        }
+        assert_eq!(leaf.to_list::<f32>(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_empty_batch_broadcasting() {
+        assert_eq!(broadcast_shapes(&[0, 3], &[3]).unwrap(), vec![0, 3]);
+        assert_eq!(broadcast_shapes(&[1, 3], &[0, 1]).unwrap(), vec![0, 3]);
+
+        let empty = Tensor::zeros(&[0, 3]);
+        let sum = &empty + &Tensor::ones(&[3]);
+        assert!(sum.defined());
+        assert_eq!(sum.shape(), vec![0, 3]);
+        let scaled = &empty * 2.0f64;
+        assert!(scaled.defined());
+        assert_eq!(scaled.shape(), vec![0, 3]);
+        assert_eq!(empty.lt(&Tensor::ones(&[1, 3])).shape(), vec![0, 3]);
+        assert_eq!(empty.sum().item::<f32>().to_bits(), 0.0f32.to_bits());
+
+        let projected = &Tensor::zeros(&[0, 4]).matmul(&Tensor::ones(&[4, 3])) + &Tensor::ones(&[3]);
+        assert!(projected.defined());
+        assert_eq!(projected.shape(), vec![0, 3]);
+    }
+
+    #[test]
+    fn test_integer_reductions() {
+        let x = Tensor::from_vec(vec![3i64, 7, 2], &[3]);
+        assert_eq!(x.sum().dtype(), DType::Int64);
+        assert_eq!(x.sum().item::<i64>(), 12);
+        assert_eq!(x.prod().item::<i64>(), 42);
+        assert_eq!(x.max().item::<i64>(), 7);
+        assert_eq!(x.min().item::<i64>(), 2);
+        assert_eq!(x.mean().item::<f32>(), 4.0);
+
+        let big = Tensor::from_vec(vec![(1i64 << 60) + 1, 1], &[2]);
+        assert_eq!(big.sum().item::<i64>(), (1i64 << 60) + 2);
+        assert_eq!(Tensor::from_vec(vec![2i32, 5], &[2]).max().dtype(), DType::Int32);
+        assert_eq!(Tensor::from_vec(Vec::<i64>::new(), &[0]).sum().item::<i64>(), 0);
+        assert!(!Tensor::from_vec(Vec::<i64>::new(), &[0]).max().defined());
+        assert_eq!(Tensor::from_slice(&[1.5f32, 2.5], &[2]).to(Device::meta()).sum().dtype(), DType::Float32);
+        assert_eq!(x.to(Device::meta()).sum().dtype(), DType::Int64);
+    }
+
+    #[test]
+    fn test_scalar_ops_keep_integer_dtype() {
+        let a = Tensor::from_vec(vec![0i64, 1, -7], &[3]);
+        let sum = &a + 16777217i64;
+        assert_eq!(sum.dtype(), DType::Int64);
+        assert_eq!(sum.to_list::<i64>(), vec![16777217, 16777218, 16777210]);
+        assert_eq!((&a * 3i64).to_list::<i64>(), vec![0, 3, -21]);
+        assert_eq!((&a % 3i64).to_list::<i64>(), vec![0, 1, 2]);
+        assert_eq!((&a + 0.5f32).dtype(), DType::Float32);
+
+        let small = Tensor::from_vec(vec![1i32, 2], &[2]);
+        assert_eq!((&small - 1i64).dtype(), DType::Int32);
+        assert_eq!((&small + &a.narrow(0, 0, 2)).dtype(), DType::Int64);
+
+        let mut b = Tensor::from_vec(vec![1i64 << 60], &[1]);
+        b += 1i64;
+        assert_eq!(b.to_list::<i64>(), vec![(1i64 << 60) + 1]);
+    }
+
+    #[test]
+    fn test_tensor_iterator_coalesces_dimensions() {
+        let shape = [2, 3, 4];
+        let strides = TensorIterator::contiguous_strides(&shape);
+        let iter = TensorIterator::new(&shape, &[strides.clone(), strides]).unwrap();
+        assert_eq!(iter.ndim(), 1);
+        assert_eq!(iter.numel(), 24);
+
+        let broadcast = TensorIterator::broadcast_strides(&[3, 1], &[1, 1], &[2, 3, 4]).unwrap();
+        assert_eq!(broadcast, vec![0, 1, 0]);
+        let iter = TensorIterator::new(&shape, &[TensorIterator::contiguous_strides(&shape), broadcast]).unwrap();
+        assert_eq!(iter.ndim(), 3);
+
+        assert!(TensorIterator::broadcast_strides(&[3], &[1], &[4]).is_err());
+    }
+
+    #[test]
+    fn test_tensor_iterator_runs_with_zero_strides() {
+        let shape = [2, 3];
+        let iter = TensorIterator::new(&shape, &[vec![1, 0], vec![0, 1]]).unwrap();
+
+        let mut visited = Vec::new();
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                visited.push((offsets[0] + i * inner[0], offsets[1] + i * inner[1]));
+            }
+        });
+        assert_eq!(visited, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_tensor_iterator_transposed_walk() {
+        let data: Vec<i64> = (0..6).collect();
+        let iter = TensorIterator::new(&[3, 2], &[vec![1, 3]]).unwrap();
+
+        let mut gathered = Vec::new();
+        iter.for_each_run(|offsets, len, inner| {
+            for i in 0..len as isize {
+                gathered.push(data[(offsets[0] + i * inner[0]) as usize]);
+            }
+        });
+        assert_eq!(gathered, vec![0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn test_broadcasting_three_dimensional() {
+        let a = Tensor::from_array_3d(vec![vec![vec![1.0f32, 2.0]], vec![vec![3.0, 4.0]]]);
+        let b = Tensor::from_array_2d(vec![vec![10.0f32], vec![20.0], vec![30.0]]);
+        let result = &a + &b;
+
+        assert_eq!(result.shape(), vec![2, 3, 2]);
+        assert_eq!(
+            result.to_list::<f32>(),
+            vec![11.0, 12.0, 21.0, 22.0, 31.0, 32.0, 13.0, 14.0, 23.0, 24.0, 33.0, 34.0]
+        );
+    }
+
+    #[test]
+    fn test_binary_op_promotes_integer_inputs() {
+        let a = Tensor::from_array_1d(vec![1i64, 2, 3]);
+        let b = Tensor::from_array_1d(vec![0.5f32, 0.5, 0.5]);
+        let result = &a * &b;
+
+        assert_eq!(result.dtype(), DType::Float32);
+        assert_eq!(result.to_list::<f32>(), vec![0.5, 1.0, 1.5]);
+        assert_eq!(a.unary_op(|x| x * 2.0).to_list::<f32>(), vec![2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_generator_reproducible_sequences() {
+        let mut g1 = Generator::with_seed(42);
+        let mut g2 = Generator::with_seed(42);
+
+        let a = Tensor::rand_with_generator(&[4], Some(&mut g1));
+        let b = Tensor::rand_with_generator(&[4], Some(&mut g2));
+        assert_eq!(a.to_list::<f32>(), b.to_list::<f32>());
+
+        let a = Tensor::randn_with_generator(&[8], Some(&mut g1));
+        let b = Tensor::randn_with_generator(&[8], Some(&mut g2));
+        assert_eq!(a.to_list::<f32>(), b.to_list::<f32>());
+        assert!(a.to_list::<f32>().iter().all(|v| v.is_finite()));
+
+        let mut g3 = Generator::with_seed(43);
+        let c = Tensor::rand_with_generator(&[4], Some(&mut g3));
+        assert_ne!(c.to_list::<f32>(), Tensor::rand_with_generator(&[4], Some(&mut Generator::with_seed(42))).to_list::<f32>());
+    }
+
+    #[test]
+    fn test_generator_state_round_trip() {
+        let mut generator = Generator::with_seed(7);
+        let _ = Tensor::rand_with_generator(&[3], Some(&mut generator));
+
+        let state = generator.get_state();
+        let first = Tensor::bernoulli_with_generator(&[16], 0.5, Some(&mut generator));
+        generator.set_state(&state).unwrap();
+        let second = Tensor::bernoulli_with_generator(&[16], 0.5, Some(&mut generator));
+
+        assert_eq!(first.to_list::<f32>(), second.to_list::<f32>());
+        assert_eq!(generator.initial_seed(), 7);
+        assert!(generator.set_state(&[0u8; 3]).is_err());
+        assert!(generator.set_state(&[0u8; 40]).is_err());
+    }
+
+    #[test]
+    fn test_global_manual_seed() {
+        manual_seed(1234);
+        let a = Tensor::rand(&[5]);
+        manual_seed(1234);
+        let b = Tensor::rand(&[5]);
+        assert_eq!(a.to_list::<f32>(), b.to_list::<f32>());
+        assert_eq!(initial_seed(), 1234);
+
+        let state = get_rng_state();
+        let c = Tensor::randn(&[5]);
+        set_rng_state(&state).unwrap();
+        let d = Tensor::randn(&[5]);
+        assert_eq!(c.to_list::<f32>(), d.to_list::<f32>());
+    }
+
+    #[test]
+    fn test_randint_and_uniform_ranges() {
+        let mut generator = Generator::with_seed(0);
+        let ints = Tensor::randint(-3, 4, &[200], Some(&mut generator));
+        assert_eq!(ints.dtype(), DType::Int64);
+        assert!(ints.to_list::<i64>().iter().all(|&v| (-3..4).contains(&v)));
+
+        let uniform = Tensor::uniform(2.0, 5.0, &[200], Options::default(), Some(&mut generator));
+        assert!(uniform.to_list::<f32>().iter().all(|&v| (2.0..5.0).contains(&v)));
+
+        let floats = Tensor::randint_with_options(0, 3, &[4], Options::default(), Some(&mut generator));
+        assert_eq!(floats.dtype(), DType::Float32);
+        let large = Tensor::randint((1i64 << 60) + 1, (1i64 << 60) + 2, &[2], None);
+        assert_eq!(large.to_list::<i64>(), vec![(1i64 << 60) + 1; 2]);
+
+        assert!(!Tensor::randint(2, 2, &[3], None).defined());
+    }
+
+    #[test]
+    fn test_continuous_distribution_moments() {
+        let mut generator = Generator::with_seed(5);
+        let n = 20000;
+        let mean = |t: &Tensor| t.to_list::<f32>().iter().map(|&v| v as f64).sum::<f64>() / n as f64;
+
+        let normal = Tensor::normal(3.0, 2.0, &[n], Options::default(), Some(&mut generator));
+        assert!((mean(&normal) - 3.0).abs() < 0.1);
+
+        let exponential = Tensor::exponential(4.0, &[n], Options::default(), Some(&mut generator));
+        assert!((mean(&exponential) - 0.25).abs() < 0.02);
+        assert!(exponential.to_list::<f32>().iter().all(|&v| v >= 0.0));
+
+        let gamma = Tensor::gamma(0.5, 2.0, &[n], Options::default(), Some(&mut generator));
+        assert!((mean(&gamma) - 0.25).abs() < 0.02);
+
+        let beta = Tensor::beta(2.0, 6.0, &[n], Options::default(), Some(&mut generator));
+        assert!((mean(&beta) - 0.25).abs() < 0.02);
+        assert!(beta.to_list::<f32>().iter().all(|&v| (0.0..=1.0).contains(&v)));
+
+        let trunc = Tensor::trunc_normal(0.0, 1.0, -0.5, 0.5, &[n], Options::default(), Some(&mut generator));
+        assert!(trunc.to_list::<f32>().iter().all(|&v| (-0.5..=0.5).contains(&v)));
+
+        let upper = Tensor::trunc_normal(0.0, 1.0, 4.0, 5.0, &[n], Options::default(), Some(&mut generator));
+        assert!(upper.to_list::<f32>().iter().all(|&v| (4.0..=5.0).contains(&v)));
+        assert!((mean(&upper) - 4.217).abs() < 0.02);
+        let lower = Tensor::trunc_normal(1.0, 2.0, -9.0, -7.0, &[n], Options::default(), Some(&mut generator));
+        assert!((mean(&lower) + 7.434).abs() < 0.04);
+    }
+
+    #[test]
+    fn test_poisson_sampling() {
+        let mut generator = Generator::with_seed(9);
+        let rates = Tensor::from_array_1d(vec![0.0, 2.0, 50.0]).reshape(&[3, 1]);
+        let rates = Tensor::ones(&[3, 4000]) * &rates;
+        let samples = rates.poisson(Some(&mut generator));
+        assert_eq!(samples.shape(), vec![3, 4000]);
+
+        let data = samples.to_list::<f32>();
+        assert!(data.iter().all(|&v| v >= 0.0 && v.fract() == 0.0));
+        let row_mean = |r: usize| data[r * 4000..(r + 1) * 4000].iter().sum::<f32>() / 4000.0;
+        assert_eq!(row_mean(0), 0.0);
+        assert!((row_mean(1) - 2.0).abs() < 0.15);
+        assert!((row_mean(2) - 50.0).abs() < 0.6);
+
+        assert!(!Tensor::from_array_1d(vec![-1.0]).poisson(None).defined());
+    }
+
+    #[test]
+    fn test_randperm_is_permutation() {
+        let perm = Tensor::randperm(10, Some(&mut Generator::with_seed(1)));
+        assert_eq!(perm.dtype(), DType::Int64);
+        let floats = Tensor::randperm_with_options(4, Options::default(), None);
+        assert_eq!(floats.dtype(), DType::Float32);
+        let mut values = perm.to_list::<i64>();
+        values.sort();
+        assert_eq!(values, (0..10).collect::<Vec<i64>>());
+    }
+
+    #[test]
+    fn test_multinomial_and_categorical() {
+        let mut generator = Generator::with_seed(2);
+        let probs = Tensor::from_array_2d(vec![vec![0.0, 1.0, 0.0], vec![0.2, 0.0, 0.8]]);
+
+        let samples = probs.multinomial(50, true, Some(&mut generator));
+        assert_eq!(samples.shape(), vec![2, 50]);
+        assert_eq!(samples.dtype(), DType::Int64);
+        let data = samples.to_list::<i64>();
+        assert!(data[..50].iter().all(|&v| v == 1));
+        assert!(data[50..].iter().all(|&v| v == 0 || v == 2));
+
+        let without = probs.multinomial(2, false, Some(&mut generator));
+        assert!(!without.defined());
+        let weights = Tensor::from_array_1d(vec![1.0, 1.0, 1.0, 1.0]);
+        let mut drawn = weights.multinomial(4, false, Some(&mut generator)).to_list::<i64>();
+        drawn.sort();
+        assert_eq!(drawn, vec![0, 1, 2, 3]);
+
+        let categorical = probs.categorical(Some(&mut generator));
+        assert_eq!(categorical.shape(), vec![2]);
+        assert_eq!(categorical.to_list::<i64>()[0], 1);
+    }
+
+    #[test]
+    fn test_inplace_sampling_preserves_storage() {
+        let mut t = Tensor::zeros(&[64]);
+        let alias = Clone::clone(&t);
+        t.uniform_(-1.0, 1.0, Some(&mut Generator::with_seed(3))).unwrap();
+        assert_eq!(t.to_list::<f32>(), alias.to_list::<f32>());
+        assert!(alias.to_list::<f32>().iter().all(|&v| (-1.0..1.0).contains(&v)));
+
+        t.normal_(0.0, 1.0, None).unwrap().exponential_(1.0, None).unwrap();
+        assert!(alias.to_list::<f32>().iter().all(|&v| v >= 0.0));
+
+        let before = t.to_list::<f32>();
+        assert!(t.normal_(0.0, -1.0, None).is_err());
+        assert!(t.uniform_(1.0, -1.0, None).is_err());
+        assert!(t.defined());
+        assert_eq!(t.to_list::<f32>(), before);
+    }
+
+    #[test]
+    fn test_distributions_reproducible_with_seed() {
+        let a = Tensor::gamma(2.0, 1.0, &[16], Options::default(), Some(&mut Generator::with_seed(4)));
+        let b = Tensor::gamma(2.0, 1.0, &[16], Options::default(), Some(&mut Generator::with_seed(4)));
+        assert_eq!(a.to_list::<f32>(), b.to_list::<f32>());
+    }
+
+    #[test]
+    fn test_factories_honour_options() {
+        let options = Options::default().dtype(DType::Int64).requires_grad(true);
+        let ones = Tensor::ones_with_options(&[2, 3], options.clone());
+        assert_eq!(ones.dtype(), DType::Int64);
+        assert!(ones.requires_grad());
+        assert_eq!(ones.to_list::<i64>(), vec![1; 6]);
+
+        let zeros = Tensor::zeros_with_options(&[2], Options::default().dtype(DType::Float16));
+        assert_eq!(zeros.dtype(), DType::Float16);
+
+        let full = Tensor::full_with_options(&[3], 7.0, Options::default().dtype(DType::Int32));
+        assert_eq!(full.to_list::<i32>(), vec![7, 7, 7]);
+        assert_eq!(Tensor::full(&[2, 2], 0.5).to_list::<f32>(), vec![0.5; 4]);
+
+        let mut t = Tensor::zeros(&[2]);
+        t.set_requires_grad(true);
+        assert!(t.requires_grad());
+    }
+
+    #[test]
+    fn test_like_factories_keep_dtype() {
+        let source = Tensor::full_with_options(&[2, 2], 3.0, Options::default().dtype(DType::Int64).requires_grad(true));
+
+        let ones = Tensor::ones_like(&source);
+        assert_eq!(ones.dtype(), DType::Int64);
+        assert_eq!(ones.shape(), vec![2, 2]);
+        assert!(!ones.requires_grad());
+        assert_eq!(Tensor::zeros_like(&source).to_list::<i64>(), vec![0; 4]);
+        assert_eq!(Tensor::full_like(&source, 9.0).to_list::<i64>(), vec![9; 4]);
+
+        let empty = Tensor::empty_like(&source);
+        assert_eq!(empty.dtype(), DType::Int64);
+        assert_eq!(empty.shape(), vec![2, 2]);
+        assert!(!Tensor::ones_like(&Tensor::new()).defined());
+    }
+
+    #[test]
+    fn test_arange_linspace_logspace() {
+        assert_eq!(Tensor::arange(0.0, 5.0, 1.0).to_list::<f32>(), vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(Tensor::arange(1.0, 2.0, 0.25).to_list::<f32>(), vec![1.0, 1.25, 1.5, 1.75]);
+        assert_eq!(Tensor::arange(3.0, 0.0, -1.0).to_list::<f32>(), vec![3.0, 2.0, 1.0]);
+        let ints = Tensor::arange_with_options(0.0, 4.0, 2.0, Options::default().dtype(DType::Int64));
+        assert_eq!(ints.to_list::<i64>(), vec![0, 2]);
+        assert!(!Tensor::arange(0.0, 1.0, 0.0).defined());
+
+        assert_eq!(Tensor::logspace(0.0, 3.0, 4, 10.0).to_list::<f32>(), vec![1.0, 10.0, 100.0, 1000.0]);
+        assert_eq!(Tensor::linspace(0.0, 1.0, 3).to_list::<f32>(), vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_eye_tril_triu() {
+        assert_eq!(Tensor::eye(2).to_list::<f32>(), vec![1.0, 0.0, 0.0, 1.0]);
+        let rect = Tensor::eye_with_options(2, 3, Options::default().dtype(DType::Int64));
+        assert_eq!(rect.shape(), vec![2, 3]);
+        assert_eq!(rect.to_list::<i64>(), vec![1, 0, 0, 0, 1, 0]);
+
+        let x = Tensor::ones(&[2, 3, 3]);
+        assert_eq!(
+            x.tril(0).to_list::<f32>()[..9].to_vec(),
+            vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0]
+        );
+        assert_eq!(
+            x.triu(1).to_list::<f32>()[9..].to_vec(),
+            vec![0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]
+        );
+        assert!(!Tensor::ones(&[3]).tril(0).defined());
+
+        let big = Tensor::full_with_options(&[2, 2], (1i64 << 53) + 1, Options::default().dtype(DType::Int64));
+        assert_eq!(big.to_list::<i64>(), vec![(1i64 << 53) + 1; 4]);
+        assert_eq!(big.triu(0).to_list::<i64>(), vec![(1i64 << 53) + 1, (1i64 << 53) + 1, 0, (1i64 << 53) + 1]);
+
+        let w = Tensor::full_with_options(&[2, 2], 2.0, Options::default().requires_grad(true));
+        let lower = w.tril(0);
+        assert_eq!(lower.grad_fn().unwrap().name(), "TrilBackward0");
+        lower.sum().backward();
+        assert_eq!(w.grad().to_list::<f32>(), vec![1.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_meshgrid_indexing() {
+        let x = Tensor::from_array_1d(vec![1.0, 2.0, 3.0]);
+        let y = Tensor::from_array_1d(vec![4.0, 5.0]);
+
+        let ij = Tensor::meshgrid(&[Clone::clone(&x), Clone::clone(&y)], "ij");
+        assert_eq!(ij[0].shape(), vec![3, 2]);
+        assert_eq!(ij[0].to_list::<f32>(), vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0]);
+        assert_eq!(ij[1].to_list::<f32>(), vec![4.0, 5.0, 4.0, 5.0, 4.0, 5.0]);
+
+        let xy = Tensor::meshgrid(&[x, y], "xy");
+        assert_eq!(xy[0].shape(), vec![2, 3]);
+        assert_eq!(xy[0].to_list::<f32>(), vec![1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+        assert_eq!(xy[1].to_list::<f32>(), vec![4.0, 4.0, 4.0, 5.0, 5.0, 5.0]);
+
+        assert!(Tensor::meshgrid(&[Tensor::ones(&[2])], "yx").is_empty());
+    }
+
+    #[test]
+    fn test_display_nested_brackets() {
+        let t = Tensor::from_array_2d(vec![vec![1.0f32, 2.5], vec![-3.0, 4.0]]);
+        assert_eq!(format!("{}", t), "tensor([[ 1.0000,  2.5000],\n        [-3.0000,  4.0000]])");
+
+        let t = Tensor::arange(0.0, 8.0, 1.0).reshape(&[2, 2, 2]);
+        assert_eq!(
+            format!("{}", t),
+            "tensor([[[0., 1.],\n         [2., 3.]],\n\n        [[4., 5.],\n         [6., 7.]]])"
+        );
+
+        assert_eq!(format!("{}", Tensor::scalar(3.5f32)), "tensor(3.5000)");
+        assert_eq!(format!("{}", Tensor::new()), "tensor(undefined)");
+    }
+
+    #[test]
+    fn test_display_dtype_and_grad_suffixes() {
+        let ints = Tensor::arange_with_options(0.0, 3.0, 1.0, Options::default().dtype(DType::Int32));
+        assert_eq!(format!("{}", ints), "tensor([0, 1, 2], dtype=Int32)");
+
+        let longs = Tensor::arange_with_options(0.0, 3.0, 1.0, Options::default().dtype(DType::Int64));
+        assert_eq!(format!("{}", longs), "tensor([0, 1, 2])");
+
+        let mask = Tensor::from_array_1d(vec![1.0, -1.0]).gt(&Tensor::scalar(0.0f32));
+        assert_eq!(format!("{}", mask), "tensor([ True, False])");
+
+        let param = Tensor::ones_with_options(&[2], Options::default().requires_grad(true));
+        assert_eq!(format!("{}", param), "tensor([1., 1.], requires_grad=True)");
+        assert_eq!(format!("{}", &param * &param), "tensor([1., 1.], grad_fn=<MulBackward0>)");
+    }
+
+    #[test]
+    fn test_display_scientific_and_special_values() {
+        let t = Tensor::from_array_1d(vec![1e-6f32, 1.0, 1e5]);
+        assert_eq!(format!("{}", t), "tensor([1.0000e-06, 1.0000e+00, 1.0000e+05])");
+
+        let t = Tensor::from_array_1d(vec![f32::NAN, f32::INFINITY, 0.5]);
+        assert_eq!(format!("{}", t), "tensor([   nan,    inf, 0.5000])");
+    }
+
+    #[test]
+    fn test_display_summarizes_with_printoptions() {
+        let previous = get_printoptions();
+        set_printoptions(PrintOptions {
+            threshold: 5,
+            edgeitems: 2,
+            precision: 2,
+            ..PrintOptions::default()
+        });
+
+        let t = Tensor::arange(0.0, 10.0, 1.0);
+        assert_eq!(format!("{}", t), "tensor([0., 1., ..., 8., 9.])");
+
+        let t = Tensor::linspace(0.0, 1.0, 6).reshape(&[6, 1]);
+        assert_eq!(
+            format!("{}", t),
+            "tensor([[0.00],\n        [0.20],\n        ...,\n        [0.80],\n        [1.00]])"
+        );
+
+        set_printoptions(previous);
+        assert_eq!(get_printoptions(), PrintOptions::default());
+    }
+
+    #[test]
+    fn test_display_wraps_long_rows() {
+        let previous = get_printoptions();
+        set_printoptions(PrintOptions {
+            linewidth: 20,
+            ..PrintOptions::default()
+        });
+
+        let t = Tensor::arange(0.0, 6.0, 1.0);
+        assert_eq!(format!("{}", t), "tensor([0., 1., 2.,\n        3., 4., 5.])");
+
+        set_printoptions(previous);
+    }
+
+    #[test]
+    fn test_from_vec_and_slice_any_dtype() {
+        let t = Tensor::from_vec(vec![1i64, 2, 3, 4, 5, 6], &[2, 3]);
+        assert_eq!(t.dtype(), DType::Int64);
+        assert_eq!(t.shape(), vec![2, 3]);
+        assert_eq!(t.to_list::<i64>(), vec![1, 2, 3, 4, 5, 6]);
+
+        let t = Tensor::from_slice(&[1i32, 2, 3, 4], &[2, 1, 2]);
+        assert_eq!(t.dtype(), DType::Int32);
+        assert_eq!(t.shape(), vec![2, 1, 2]);
+
+        assert!(!Tensor::from_vec(vec![1.0f32, 2.0], &[3]).defined());
+        assert!(!Tensor::from_slice(&[1.0f32, 2.0], &[3]).defined());
+    }
+
+    #[test]
+    fn test_from_ndarray_any_rank() {
+        use ndarray::{ArrayD, IxDyn};
+
+        let array = ArrayD::from_shape_vec(IxDyn(&[2, 1, 2, 2, 1]), (0..8).map(|v| v as f32).collect()).unwrap();
+        let t = Tensor::from(array);
+        assert_eq!(t.shape(), vec![2, 1, 2, 2, 1]);
+        assert_eq!(t.to_list::<f32>(), (0..8).map(|v| v as f32).collect::<Vec<_>>());
+
+        let transposed = ArrayD::from_shape_vec(IxDyn(&[2, 3]), vec![1i64, 2, 3, 4, 5, 6])
+            .unwrap()
+            .reversed_axes();
+        let t = Tensor::from(transposed);
+        assert_eq!(t.shape(), vec![3, 2]);
+        assert_eq!(t.to_list::<i64>(), vec![1, 4, 2, 5, 3, 6]);
+
+        let mut sliced = ArrayD::from_shape_vec(IxDyn(&[4]), vec![1i32, 2, 3, 4]).unwrap();
+        sliced.slice_collapse(ndarray::s![1..3]);
+        assert_eq!(Tensor::from(sliced).to_list::<i32>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_to_ndarray_round_trip() {
+        let t = Tensor::arange_with_options(0.0, 6.0, 1.0, Options::default().dtype(DType::Int64)).reshape(&[2, 3]);
+        let array = t.to_ndarray::<i64>().unwrap();
+        assert_eq!(array.shape(), &[2, 3]);
+        assert_eq!(array[[1, 2]], 5);
+        assert!(t.to_ndarray::<f32>().is_err());
+
+        let back = Tensor::from(array);
+        assert_eq!(back.to_list::<i64>(), t.to_list::<i64>());
+
+        let transposed = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]).transpose(0, 1);
+        let array = transposed.to_ndarray::<f32>().unwrap();
+        assert_eq!(array.shape(), &[3, 2]);
+        assert_eq!(array[[2, 1]], 5.0);
+    }
+
+    #[test]
+    fn test_as_array_view_shares_memory() {
+        let t = Tensor::from_vec(vec![1.0f32, 2.0, 3.0, 4.0], &[2, 2]);
+        let view = t.as_array_view::<f32>().unwrap();
+        assert_eq!(view.shape(), &[2, 2]);
+        assert_eq!(view[[1, 0]], 3.0);
+        assert_eq!(view.as_ptr(), t.impl_.as_ref().unwrap().data_ptr::<f32>() as *const f32);
+        assert!(t.as_array_view::<i64>().is_err());
+    }
+
+    #[test]
+    fn test_view_shares_storage_and_infers_dims() {
+        let mut base = Tensor::arange(0.0, 12.0, 1.0);
+        let view = base.view(&[3, -1]);
+        assert_eq!(view.shape(), vec![3, 4]);
+        assert_eq!(view.strides(), vec![4, 1]);
+
+        base.add_(&Tensor::scalar(1.0f32)).unwrap();
+        assert_eq!(view.to_list::<f32>()[0], 1.0);
+
+        assert!(!base.view(&[5, -1]).defined());
+        assert!(!base.view(&[-1, -1]).defined());
+        assert_eq!(base.view_as(&Tensor::zeros(&[2, 6])).shape(), vec![2, 6]);
+    }
+
+    #[test]
+    fn test_reshape_copies_non_contiguous() {
+        let x = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]);
+        let t = x.transpose(0, 1);
+        assert_eq!(t.shape(), vec![3, 2]);
+        assert_eq!(t.strides(), vec![1, 3]);
+        assert!(!t.view(&[6]).defined());
+
+        let flat = t.reshape(&[-1]);
+        assert_eq!(flat.to_list::<f32>(), vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+        assert!(!x.reshape(&[4, 2]).defined());
+    }
+
+    #[test]
+    fn test_squeeze_unsqueeze() {
+        let x = Tensor::zeros(&[1, 3, 1, 2]);
+        assert_eq!(x.squeeze(None).shape(), vec![3, 2]);
+        assert_eq!(x.squeeze(Some(2)).shape(), vec![1, 3, 2]);
+        assert_eq!(x.squeeze(Some(1)).shape(), vec![1, 3, 1, 2]);
+        assert!(!x.squeeze(Some(4)).defined());
+
+        let y = Tensor::zeros(&[3, 2]);
+        assert_eq!(y.unsqueeze(0).shape(), vec![1, 3, 2]);
+        assert_eq!(y.unsqueeze(-1).shape(), vec![3, 2, 1]);
+        assert_eq!(y.unsqueeze(1).strides(), vec![2, 2, 1]);
+        assert!(!y.unsqueeze(4).defined());
+    }
+
+    #[test]
+    fn test_flatten_range_and_unflatten() {
+        let x = Tensor::zeros(&[2, 3, 4, 5]);
+        assert_eq!(x.flatten().shape(), vec![120]);
+        assert_eq!(x.flatten_range(1, 2).shape(), vec![2, 12, 5]);
+        assert_eq!(x.flatten_range(-2, -1).shape(), vec![2, 3, 20]);
+        assert!(!x.flatten_range(2, 1).defined());
+        assert_eq!(Tensor::scalar(1.0f32).flatten().shape(), vec![1]);
+
+        assert_eq!(x.unflatten(2, &[2, -1]).shape(), vec![2, 3, 2, 2, 5]);
+        assert!(!x.unflatten(2, &[3, -1]).defined());
+    }
+
+    #[test]
+    fn test_movedim_swapaxes_permute() {
+        let x = Tensor::arange(0.0, 24.0, 1.0).reshape(&[2, 3, 4]);
+        let moved = x.movedim(0, -1);
+        assert_eq!(moved.shape(), vec![3, 4, 2]);
+        assert_eq!(moved.to_list::<f32>()[..4].to_vec(), vec![0.0, 12.0, 1.0, 13.0]);
+
+        let swapped = x.swapaxes(0, 2);
+        assert_eq!(swapped.shape(), vec![4, 3, 2]);
+        assert_eq!(swapped.strides(), vec![1, 4, 12]);
+
+        assert_eq!(x.permute(&[2, 0, 1]).shape(), vec![4, 2, 3]);
+        assert!(!x.permute(&[0, 0, 1]).defined());
+    }
+
+    #[test]
+    fn test_expand_as_uses_zero_strides() {
+        let x = Tensor::from_array_2d(vec![vec![1.0f32], vec![2.0]]);
+        let target = Tensor::zeros(&[2, 2, 3]);
+        let expanded = x.expand_as(&target);
+        assert_eq!(expanded.shape(), vec![2, 2, 3]);
+        assert_eq!(expanded.strides(), vec![0, 1, 0]);
+        assert_eq!(expanded.to_list::<f32>()[..6].to_vec(), vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+        assert_eq!((&expanded + &Tensor::ones(&[3])).to_list::<f32>()[3], 3.0);
+
+        assert_eq!(x.expand(&[-1, 4]).shape(), vec![2, 4]);
+        assert!(!x.expand(&[3, 4]).defined());
+    }
+
+    #[test]
+    fn test_clone_of_view_is_contiguous() {
+        let x = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]).transpose(0, 1);
+        let copy = x.clone();
+        assert_eq!(copy.strides(), vec![2, 1]);
+        assert_eq!(copy.to_list::<f32>(), x.to_list::<f32>());
+        assert!(x.contiguous().impl_.as_ref().unwrap().is_contiguous());
+    }
+
+    fn long(values: &[i64], shape: &[i64]) -> Tensor {
+        Tensor::from_slice(values, shape)
+    }
+
+    #[test]
+    fn test_gather_along_dims() {
+        let x = Tensor::from_array_2d(vec![vec![1.0f32, 2.0], vec![3.0, 4.0]]);
+        let index = long(&[0, 0, 1, 0], &[2, 2]);
+        assert_eq!(x.gather(1, &index).to_list::<f32>(), vec![1.0, 1.0, 4.0, 3.0]);
+        assert_eq!(x.gather(0, &index).to_list::<f32>(), vec![1.0, 2.0, 3.0, 2.0]);
+
+        assert!(!x.gather(1, &long(&[2], &[1, 1])).defined());
+        assert!(!x.gather(1, &Tensor::zeros(&[2, 2])).defined());
+    }
+
+    #[test]
+    fn test_scatter_and_scatter_add() {
+        let x = Tensor::zeros(&[2, 3]);
+        let src = Tensor::from_array_2d(vec![vec![1.0f32, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        let index = long(&[2, 0, 1, 0], &[2, 2]);
+
+        assert_eq!(x.scatter(1, &index, &src).to_list::<f32>(), vec![2.0, 0.0, 1.0, 5.0, 4.0, 0.0]);
+
+        let index = long(&[0, 1, 0, 0, 0, 1], &[2, 3]);
+        let added = Tensor::ones(&[2, 3]).scatter_add(0, &index, &src);
+        assert_eq!(added.to_list::<f32>(), vec![6.0, 6.0, 4.0, 1.0, 3.0, 7.0]);
+    }
+
+    #[test]
+    fn test_scatter_reduce_modes() {
+        let x = Tensor::from_array_1d(vec![1.0f32, 2.0, 3.0]);
+        let src = Tensor::from_array_1d(vec![5.0f32, 1.0, 7.0, 2.0]);
+        let index = long(&[0, 0, 2, 2], &[4]);
+
+        let reduce = |mode: &str, include_self: bool| x.scatter_reduce(0, &index, &src, mode, include_self).to_list::<f32>();
+        assert_eq!(reduce("sum", true), vec![7.0, 2.0, 12.0]);
+        assert_eq!(reduce("sum", false), vec![6.0, 2.0, 9.0]);
+        assert_eq!(reduce("mean", true), vec![7.0 / 3.0, 2.0, 4.0]);
+        assert_eq!(reduce("mean", false), vec![3.0, 2.0, 4.5]);
+        assert_eq!(reduce("amax", true), vec![5.0, 2.0, 7.0]);
+        assert_eq!(reduce("amin", false), vec![1.0, 2.0, 2.0]);
+        assert_eq!(reduce("prod", true), vec![5.0, 2.0, 42.0]);
+        assert!(!x.scatter_reduce(0, &index, &src, "median", true).defined());
+    }
+
+    #[test]
+    fn test_index_select_add_copy() {
+        let x = Tensor::arange(0.0, 6.0, 1.0).reshape(&[3, 2]);
+        let index = long(&[2, 0], &[2]);
+        assert_eq!(x.index_select(0, &index).to_list::<f32>(), vec![4.0, 5.0, 0.0, 1.0]);
+        assert_eq!(x.index_select(1, &long(&[1], &[1])).to_list::<f32>(), vec![1.0, 3.0, 5.0]);
+        assert_eq!(x.index_select(0, &long(&[-1], &[1])).to_list::<f32>(), vec![4.0, 5.0]);
+
+        let source = Tensor::ones(&[2, 2]);
+        assert_eq!(
+            x.index_add(0, &index, &source, 2.0).to_list::<f32>(),
+            vec![2.0, 3.0, 2.0, 3.0, 6.0, 7.0]
+        );
+        assert_eq!(
+            x.index_copy(0, &index, &source).to_list::<f32>(),
+            vec![1.0, 1.0, 2.0, 3.0, 1.0, 1.0]
+        );
+        assert!(!x.index_add(0, &index, &Tensor::ones(&[3, 2]), 1.0).defined());
+    }
+
+    #[test]
+    fn test_take_and_take_along_dim() {
+        let x = Tensor::arange(0.0, 6.0, 1.0).reshape(&[2, 3]);
+        let taken = x.take(&long(&[5, 0, -2], &[3, 1]));
+        assert_eq!(taken.shape(), vec![3, 1]);
+        assert_eq!(taken.to_list::<f32>(), vec![5.0, 0.0, 4.0]);
+        assert!(!x.take(&long(&[6], &[1])).defined());
+
+        let indices = long(&[2, 0], &[2, 1]);
+        let along = x.take_along_dim(&indices, Some(1));
+        assert_eq!(along.shape(), vec![2, 1]);
+        assert_eq!(along.to_list::<f32>(), vec![2.0, 3.0]);
+
+        let broadcast = x.take_along_dim(&long(&[1, 0, 1], &[1, 3]), Some(0));
+        assert_eq!(broadcast.to_list::<f32>(), vec![3.0, 1.0, 5.0]);
+        assert_eq!(x.take_along_dim(&long(&[4], &[1]), None).to_list::<f32>(), vec![4.0]);
+    }
+
+    #[test]
+    fn test_indexing_backward() {
+        let options = Options::default().requires_grad(true);
+        let x = Tensor::arange_with_options(0.0, 6.0, 1.0, options.clone()).reshape(&[2, 3]);
+        let leaf = Tensor::arange_with_options(0.0, 6.0, 1.0, options.clone());
+
+        let y = leaf.reshape(&[2, 3]).gather(1, &long(&[0, 0, 2, 1], &[2, 2]));
+        y.sum().backward();
+        assert_eq!(leaf.grad().to_list::<f32>(), vec![2.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+
+        let leaf = Tensor::ones_with_options(&[3, 2], options.clone());
+        let index = long(&[2, 2], &[2]);
+        leaf.index_select(0, &index).sum().backward();
+        assert_eq!(leaf.grad().to_list::<f32>(), vec![0.0, 0.0, 0.0, 0.0, 2.0, 2.0]);
+
+        let leaf = Tensor::ones_with_options(&[4], options.clone());
+        leaf.take(&long(&[3, 3, 0], &[3])).sum().backward();
+        assert_eq!(leaf.grad().to_list::<f32>(), vec![1.0, 0.0, 0.0, 2.0]);
+        assert!(!x.is_leaf());
+    }
+
+    #[test]
+    fn test_scatter_backward() {
+        let options = Options::default().requires_grad(true);
+        let mut base = Tensor::from_slice(&[1.0f32, 2.0, 3.0], &[3]);
+        base.set_requires_grad(true);
+        let mut src = Tensor::from_array_1d(vec![4.0f32, 5.0]);
+        src.set_requires_grad(true);
+        let index = long(&[0, 2], &[2]);
+        let weights = Tensor::from_array_1d(vec![1.0f32, 10.0, 100.0]);
+
+        (&base.scatter(0, &index, &src) * &weights).sum().backward();
+        assert_eq!(base.grad().to_list::<f32>(), vec![0.0, 10.0, 0.0]);
+        assert_eq!(src.grad().to_list::<f32>(), vec![1.0, 100.0]);
+
+        base.zero_grad();
+        src.zero_grad();
+        base.scatter_reduce(0, &long(&[0, 0], &[2]), &src, "prod", true).sum().backward();
+        assert_eq!(base.grad().to_list::<f32>(), vec![20.0, 1.0, 1.0]);
+        assert_eq!(src.grad().to_list::<f32>(), vec![5.0, 4.0]);
+
+        let mut with_zero = Tensor::from_array_1d(vec![0.0f32, 3.0, 2.0]);
+        with_zero.set_requires_grad(true);
+        Tensor::ones(&[2])
+            .scatter_reduce(0, &long(&[1, 1, 1], &[3]), &with_zero, "prod", false)
+            .sum()
+            .backward();
+        assert_eq!(with_zero.grad().to_list::<f32>(), vec![6.0, 0.0, 0.0]);
+
+        base.zero_grad();
+        src.zero_grad();
+        base.scatter_reduce(0, &long(&[1, 1], &[2]), &src, "amax", true).sum().backward();
+        assert_eq!(base.grad().to_list::<f32>(), vec![1.0, 0.0, 1.0]);
+        assert_eq!(src.grad().to_list::<f32>(), vec![0.0, 1.0]);
+
+        let mut values = Tensor::from_array_1d(vec![1.0f32, 1.0]);
+        values.set_requires_grad(true);
+        let target = Tensor::zeros_with_options(&[3, 2], options).index_add(0, &long(&[1], &[1]), &values.reshape(&[1, 2]), 3.0);
+        target.sum().backward();
+        assert_eq!(values.grad().to_list::<f32>(), vec![3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_sort_topk_and_order_statistics() {
+        let x = Tensor::from_slice(&[3.0f32, 1.0, 2.0, 5.0, 4.0, 6.0], &[2, 3]);
+        let (values, indices) = x.sort(1, false);
+        assert_eq!(values.to_list::<f32>(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(indices.to_list::<i64>(), vec![1, 2, 0, 1, 0, 2]);
+        assert_eq!(indices.dtype(), DType::Int64);
+        assert_eq!(x.argsort(0, true).to_list::<i64>(), vec![1, 1, 1, 0, 0, 0]);
+
+        let (top, top_indices) = x.topk(2, -1, true, true);
+        assert_eq!(top.shape(), vec![2, 2]);
+        assert_eq!(top.to_list::<f32>(), vec![3.0, 2.0, 6.0, 5.0]);
+        assert_eq!(top_indices.to_list::<i64>(), vec![0, 2, 2, 0]);
+        assert!(!x.topk(4, 1, true, true).0.defined());
+
+        let (kth, kth_indices) = x.kthvalue(2, 1, false);
+        assert_eq!(kth.to_list::<f32>(), vec![2.0, 5.0]);
+        assert_eq!(kth_indices.to_list::<i64>(), vec![2, 0]);
+        assert_eq!(x.kthvalue(1, 0, true).0.shape(), vec![1, 3]);
+
+        let even = Tensor::from_slice(&[4.0f32, 1.0, 3.0, 2.0], &[4]);
+        assert_eq!(even.median().item::<f32>(), 2.0);
+        let (median, median_indices) = x.median_dim(1, false);
+        assert_eq!(median.to_list::<f32>(), vec![2.0, 5.0]);
+        assert_eq!(median_indices.to_list::<i64>(), vec![2, 0]);
+
+        let repeated = Tensor::from_slice(&[2.0f32, 1.0, 2.0, 1.0, 3.0, 3.0, 3.0, 0.0], &[2, 4]);
+        let (mode, mode_indices) = repeated.mode(1, false);
+        assert_eq!(mode.to_list::<f32>(), vec![1.0, 3.0]);
+        assert_eq!(mode_indices.to_list::<i64>(), vec![3, 2]);
+
+        let nan = Tensor::from_slice(&[f32::NAN, 1.0, 0.0], &[3]);
+        assert_eq!(nan.argsort(0, false).to_list::<i64>(), vec![2, 1, 0]);
+
+        let unsorted = x.topk(2, -1, true, false);
+        assert_eq!(unsorted.0.to_list::<f32>(), vec![3.0, 2.0, 5.0, 6.0]);
+        assert_eq!(unsorted.1.to_list::<i64>(), vec![0, 2, 0, 2]);
+    }
+
+    #[test]
+    fn test_sort_empty_lanes_and_large_integers() {
+        let (values, indices) = Tensor::zeros(&[0, 3]).sort(1, false);
+        assert_eq!(values.shape(), vec![0, 3]);
+        assert_eq!(indices.shape(), vec![0, 3]);
+        assert_eq!(Tensor::zeros(&[2, 0]).argsort(1, true).shape(), vec![2, 0]);
+        assert_eq!(Tensor::zeros(&[0, 4]).topk(2, 1, true, true).1.shape(), vec![0, 2]);
+
+        let big = 1i64 << 60;
+        let x = Tensor::from_vec(vec![big + 3, big + 1, big + 2], &[3]);
+        let (values, indices) = x.sort(0, false);
+        assert_eq!(values.to_list::<i64>(), vec![big + 1, big + 2, big + 3]);
+        assert_eq!(indices.to_list::<i64>(), vec![1, 2, 0]);
+        assert_eq!(x.unique(true, false, false).0.to_list::<i64>(), vec![big + 1, big + 2, big + 3]);
+    }
+
+    #[test]
+    fn test_sort_backward() {
+        let mut x = Tensor::from_slice(&[3.0f32, 1.0, 2.0], &[3]);
+        x.set_requires_grad(true);
+        let weights = Tensor::from_array_1d(vec![1.0f32, 10.0, 100.0]);
+        (&x.sort(0, false).0 * &weights).sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![100.0, 1.0, 10.0]);
+
+        x.zero_grad();
+        x.topk(1, 0, true, true).0.sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_unique_with_inverse_and_counts() {
+        let x = Tensor::from_slice(&[2.0f32, 1.0, 2.0, 3.0, 1.0, 2.0], &[2, 3]);
+        let (values, inverse, counts) = x.unique(true, true, true);
+        assert_eq!(values.to_list::<f32>(), vec![1.0, 2.0, 3.0]);
+        let inverse = inverse.unwrap();
+        assert_eq!(inverse.shape(), vec![2, 3]);
+        assert_eq!(inverse.to_list::<i64>(), vec![1, 0, 1, 2, 0, 1]);
+        assert_eq!(counts.unwrap().to_list::<i64>(), vec![2, 3, 1]);
+
+        let (_, inverse, counts) = x.unique(true, false, false);
+        assert!(inverse.is_none() && counts.is_none());
+
+        let (values, inverse, counts) = x.unique(false, true, true);
+        assert_eq!(values.to_list::<f32>(), vec![2.0, 1.0, 3.0]);
+        assert_eq!(inverse.unwrap().to_list::<i64>(), vec![0, 1, 0, 2, 1, 0]);
+        assert_eq!(counts.unwrap().to_list::<i64>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_searchsorted_and_bucketize() {
+        let boundaries = Tensor::from_slice(&[1.0f32, 3.0, 5.0, 7.0], &[4]);
+        let values = Tensor::from_slice(&[3.0f32, 6.0, 9.0, 0.0], &[2, 2]);
+        let left = boundaries.searchsorted(&values, false);
+        assert_eq!(left.shape(), vec![2, 2]);
+        assert_eq!(left.to_list::<i64>(), vec![1, 3, 4, 0]);
+        assert_eq!(boundaries.searchsorted(&values, true).to_list::<i64>(), vec![2, 3, 4, 0]);
+        assert_eq!(values.bucketize(&boundaries, false).to_list::<i64>(), vec![1, 3, 4, 0]);
+
+        let batched = Tensor::from_slice(&[1.0f32, 2.0, 3.0, 10.0, 20.0, 30.0], &[2, 3]);
+        let queries = Tensor::from_slice(&[2.5f32, 25.0], &[2, 1]);
+        assert_eq!(batched.searchsorted(&queries, false).to_list::<i64>(), vec![2, 2]);
+        assert!(!batched.searchsorted(&values.reshape(&[4]), false).defined());
+    }
+
+    #[test]
+    fn test_cumulative_ops() {
+        let x = Tensor::from_slice(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+        assert_eq!(x.cumsum(1).to_list::<f32>(), vec![1.0, 3.0, 6.0, 4.0, 9.0, 15.0]);
+        assert_eq!(x.cumsum(0).to_list::<f32>(), vec![1.0, 2.0, 3.0, 5.0, 7.0, 9.0]);
+        assert_eq!(x.cumprod(-1).to_list::<f32>(), vec![1.0, 2.0, 6.0, 4.0, 20.0, 120.0]);
+
+        let y = Tensor::from_slice(&[1.0f32, 3.0, 2.0, 3.0, 5.0], &[5]);
+        let (values, indices) = y.cummax(0);
+        assert_eq!(values.to_list::<f32>(), vec![1.0, 3.0, 3.0, 3.0, 5.0]);
+        assert_eq!(indices.to_list::<i64>(), vec![0, 1, 1, 3, 4]);
+
+        let mut leaf = Tensor::from_slice(&[2.0f32, 0.0, 3.0], &[3]);
+        leaf.set_requires_grad(true);
+        leaf.cumsum(0).sum().backward();
+        assert_eq!(leaf.grad().to_list::<f32>(), vec![3.0, 2.0, 1.0]);
+
+        leaf.zero_grad();
+        leaf.cumprod(0).sum().backward();
+        assert_eq!(leaf.grad().to_list::<f32>(), vec![1.0, 2.0 + 6.0, 0.0]);
+
+        let mut rows = Tensor::from_slice(&[1.0f32, 2.0, 3.0, 2.0, 2.0, 2.0], &[2, 3]);
+        rows.set_requires_grad(true);
+        rows.cumprod(1).sum().backward();
+        assert_eq!(rows.grad().to_list::<f32>(), vec![9.0, 4.0, 2.0, 7.0, 6.0, 4.0]);
+    }
+
+    #[test]
+    fn test_histc() {
+        let x = Tensor::from_slice(&[1.0f32, 2.0, 1.0, 4.0, 2.5, 10.0], &[6]);
+        let hist = x.histc(4, 0.0, 4.0);
+        assert_eq!(hist.to_list::<f32>(), vec![0.0, 2.0, 2.0, 1.0]);
+        assert_eq!(x.histc(3, 0.0, 0.0).to_list::<f32>(), vec![4.0, 1.0, 1.0]);
+        assert!(!x.histc(0, 0.0, 1.0).defined());
+    }
+
+    #[test]
+    fn test_mapped_file_storage() {
+        let path = std::env::temp_dir().join("rusted_torch_mapped_storage.bin");
+        let values = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        std::fs::write(&path, &bytes).unwrap();
+
+        let mut read_only = Tensor::from_file(&path, 8, &[2, 2], DType::Float32, MapMode::ReadOnly);
+        assert_eq!(read_only.to_list::<f32>(), vec![3.0, 4.0, 5.0, 6.0]);
+        let storage = std::rc::Rc::clone(read_only.impl_.as_ref().unwrap().storage().unwrap());
+        assert_eq!(storage.kind(), StorageKind::Mapped(MapMode::ReadOnly));
+        assert!(storage.is_read_only());
+        assert!(read_only.neg_().is_err());
+        assert_eq!(read_only.to_list::<f32>(), vec![3.0, 4.0, 5.0, 6.0]);
+
+        let mut copy_on_write = Tensor::from_file(&path, 0, &[6], DType::Float32, MapMode::CopyOnWrite);
+        copy_on_write.neg_().unwrap();
+        assert_eq!(copy_on_write.to_list::<f32>(), vec![-1.0, -2.0, -3.0, -4.0, -5.0, -6.0]);
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+
+        assert!(!Tensor::from_file(&path, 4, &[6], DType::Float32, MapMode::ReadOnly).defined());
+        assert!(!Tensor::from_file(&path, 2, &[2], DType::Float32, MapMode::ReadOnly).defined());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_borrowed_and_foreign_storage() {
+        #[repr(align(8))]
+        struct Aligned([u8; 8]);
+        static BYTES: Aligned = Aligned([1, 0, 0, 0, 2, 0, 0, 0]);
+        let storage = std::rc::Rc::new(Storage::from_static(&BYTES.0).unwrap());
+        assert_eq!(storage.kind(), StorageKind::Borrowed);
+        let borrowed = Tensor::from_storage(std::rc::Rc::clone(&storage), 1, &[1], DType::Int32);
+        assert_eq!(borrowed.to_list::<i32>(), vec![2]);
+        assert!(!Tensor::from_storage(storage, 1, &[2], DType::Int32).defined());
+
+        let released = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let buffer = Box::into_raw(Box::new([7i64, 8, 9])) as *mut u8;
+        let counter = std::sync::Arc::clone(&released);
+        let storage = unsafe {
+            Storage::from_raw_parts(buffer, 24, Device::cpu(), move |ptr, size| {
+                assert_eq!(size, 24);
+                drop(Box::from_raw(ptr as *mut [i64; 3]));
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+        assert_eq!(storage.kind(), StorageKind::Foreign);
+
+        let mut foreign = Tensor::from_storage(std::rc::Rc::new(storage), 0, &[3], DType::Int64);
+        foreign.neg_().unwrap();
+        assert_eq!(foreign.to_list::<i64>(), vec![-7, -8, -9]);
+        let view = foreign.view(&[3, 1]);
+        drop(foreign);
+        assert_eq!(released.load(std::sync::atomic::Ordering::SeqCst), 0);
+        drop(view);
+        assert_eq!(released.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_borrowed_tensor_releases_storage_on_drop() {
+        let bytes: Vec<u8> = [1i32, 2, 3, 4].iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert!(!Tensor::from_borrowed(&bytes, 2, &[3], DType::Int32).defined());
+        let borrowed = Tensor::from_borrowed(&bytes, 1, &[3], DType::Int32);
+        assert_eq!(borrowed.to_list::<i32>(), vec![2, 3, 4]);
+        assert_eq!(borrowed.shape(), vec![3]);
+        assert_eq!(borrowed.as_array_view::<i32>().unwrap().iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let copied = borrowed.to_tensor();
+        assert_ne!(copied.impl_.as_ref().unwrap().storage().unwrap().kind(), StorageKind::Borrowed);
+        drop(borrowed);
+        drop(bytes);
+        assert_eq!(copied.to_list::<i32>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_caching_allocator_stats() {
+        assert_eq!(cache_limit(), 0);
+        empty_cache();
+        let before = memory_stats();
+
+        let x = Tensor::zeros(&[100]);
+        let stats = memory_stats();
+        assert_eq!(stats.allocated_bytes - before.allocated_bytes, 400);
+        assert_eq!(stats.reserved_bytes - before.reserved_bytes, 512);
+        assert_eq!(stats.num_allocs - before.num_allocs, 1);
+        assert!(stats.peak_allocated_bytes >= stats.allocated_bytes);
+        drop(x);
+        assert_eq!(memory_stats().reserved_bytes, before.reserved_bytes);
+
+        set_cache_limit(1 << 20);
+        drop(Tensor::zeros(&[100]));
+        let stats = memory_stats();
+        assert_eq!(stats.allocated_bytes, before.allocated_bytes);
+        assert_eq!(stats.reserved_bytes - before.reserved_bytes, 512);
+
+        let y = Tensor::zeros(&[120]);
+        let reused = memory_stats();
+        assert_eq!(reused.num_cache_hits - stats.num_cache_hits, 1);
+        assert_eq!(reused.reserved_bytes, stats.reserved_bytes);
+        assert_eq!(reused.allocated_bytes - stats.allocated_bytes, 480);
+        drop(y);
+
+        reset_peak_memory_stats();
+        let stats = memory_stats();
+        assert_eq!(stats.peak_allocated_bytes, stats.allocated_bytes);
+        let large = Tensor::zeros(&[300_000]);
+        assert_eq!(memory_stats().peak_allocated_bytes - stats.allocated_bytes, 1_200_000);
+        drop(large);
+
+        set_cache_limit(0);
+        assert_eq!(memory_stats().reserved_bytes, before.reserved_bytes);
+        let z = Tensor::zeros(&[100]);
+        drop(z);
+        assert_eq!(memory_stats().reserved_bytes, before.reserved_bytes);
+    }
+
+    #[test]
+    fn test_zero_sized_tensors() {
+        let empty = Tensor::empty(&[0, 3]);
+        assert!(empty.defined());
+        assert_eq!(empty.shape(), vec![0, 3]);
+        assert_eq!(empty.numel(), 0);
+        assert_eq!(empty.strides(), vec![3, 1]);
+        assert!(empty.to_list::<f32>().is_empty());
+        assert_eq!(Tensor::zeros(&[2, 0]).strides(), vec![1, 1]);
+        assert!(!Tensor::empty(&[-1, 3]).defined());
+
+        let scalar = Tensor::zeros(&[]);
+        assert_eq!(scalar.dim(), 0);
+        assert_eq!(scalar.numel(), 1);
+
+        let sum = (&empty + &Tensor::ones(&[0, 3])).sum();
+        assert_eq!(sum.item::<f32>(), 0.0);
+        assert_eq!(empty.prod().item::<f32>(), 1.0);
+        assert!(empty.mean().item::<f32>().is_nan());
+        assert!(!empty.max().defined());
+        assert!(!empty.min().defined());
+
+        assert_eq!(empty.reshape(&[3, 0]).shape(), vec![3, 0]);
+        assert_eq!(empty.transpose(0, 1).contiguous().shape(), vec![3, 0]);
+        assert_eq!(Tensor::arange(1.0, 1.0, 1.0).shape(), vec![0]);
+        assert_eq!(Tensor::full(&[0], 2.0).numel(), 0);
+        assert_eq!(Tensor::from_vec(Vec::<f32>::new(), &[4, 0]).shape(), vec![4, 0]);
+        assert_eq!(format!("{}", empty), "tensor([], size=[0, 3])");
+    }
+
+    #[test]
+    fn test_reductions_and_backward() {
+        let mut x = Tensor::from_slice(&[2.0f32, 5.0, -1.0, 5.0], &[2, 2]);
+        x.set_requires_grad(true);
+        assert_eq!(x.prod().item::<f32>(), -50.0);
+        assert_eq!(x.mean().item::<f32>(), 2.75);
+        assert_eq!(x.min().item::<f32>(), -1.0);
+
+        x.max().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![0.0, 0.5, 0.0, 0.5]);
+        x.zero_grad();
+        x.mean().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![0.25; 4]);
+        x.zero_grad();
+        x.prod().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![-25.0, -10.0, 50.0, -10.0]);
+
+        let mut z = Tensor::from_slice(&[0.0f32, 3.0, 4.0], &[3]);
+        z.set_requires_grad(true);
+        z.prod().backward();
+        assert_eq!(z.grad().to_list::<f32>(), vec![12.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_cat_stack_and_narrow() {
+        let a = Tensor::from_slice(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2]);
+        let b = Tensor::from_slice(&[5.0f32, 6.0], &[2, 1]);
+        let joined = Tensor::cat(&[Clone::clone(&a), Clone::clone(&b)], 1);
+        assert_eq!(joined.shape(), vec![2, 3]);
+        assert_eq!(joined.to_list::<f32>(), vec![1.0, 2.0, 5.0, 3.0, 4.0, 6.0]);
+        assert!(!Tensor::cat(&[Clone::clone(&a), Clone::clone(&b)], 0).defined());
+
+        let rows = Tensor::cat(&[Tensor::empty(&[0, 2]), Clone::clone(&a), Tensor::empty(&[0])], 0);
+        assert_eq!(rows.shape(), vec![2, 2]);
+        assert_eq!(Tensor::cat(&[Tensor::empty(&[0])], 0).shape(), vec![0]);
+
+        let stacked = Tensor::stack(&[Clone::clone(&a), Clone::clone(&a)], -1);
+        assert_eq!(stacked.shape(), vec![2, 2, 2]);
+        assert_eq!(stacked.to_list::<f32>(), vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0]);
+        assert_eq!(Tensor::stack(&[Tensor::empty(&[0, 3]), Tensor::empty(&[0, 3])], 0).shape(), vec![2, 0, 3]);
+
+        let slice = joined.narrow(1, 1, 2);
+        assert_eq!(slice.to_list::<f32>(), vec![2.0, 5.0, 4.0, 6.0]);
+        assert_eq!(joined.narrow(0, -1, 0).shape(), vec![0, 3]);
+        assert!(!joined.narrow(1, 2, 2).defined());
+
+        let mut left = Tensor::from_slice(&[1.0f32, 2.0], &[2]);
+        left.set_requires_grad(true);
+        let mut right = Tensor::from_slice(&[3.0f32], &[1]);
+        right.set_requires_grad(true);
+        let weights = Tensor::from_slice(&[1.0f32, 10.0, 100.0], &[3]);
+        let joined = Tensor::cat(&[Clone::clone(&left), Clone::clone(&right)], 0);
+        assert_eq!(format!("{:?}", joined.grad_fn().map(|node| node.name())), "Some(\"CatBackward0\")");
+        (&joined * &weights).narrow(0, 1, 2).sum().backward();
+        assert_eq!(left.grad().to_list::<f32>(), vec![0.0, 10.0]);
+        assert_eq!(right.grad().to_list::<f32>(), vec![100.0]);
+    }
+
+    #[test]
+    fn test_meta_device_shape_inference() {
+        let meta = Options::default().device(Device::meta());
+        let before = memory_stats().allocated_bytes;
+        let x = Tensor::empty_with_options(&[1024, 4096], meta.clone());
+        let w = Tensor::zeros_with_options(&[4096, 512], meta.clone());
+        assert_eq!(memory_stats().allocated_bytes, before);
+
+        let y = x.matmul(&w);
+        assert!(y.is_meta());
+        assert_eq!(y.shape(), vec![1024, 512]);
+        let z = (&y + &Tensor::ones_with_options(&[512], meta.clone())) * 2.0f32;
+        assert_eq!(z.shape(), vec![1024, 512]);
+        assert_eq!(z.unary_op(|v| v.max(0.0)).device(), Device::meta());
+        assert_eq!(z.transpose(0, 1).contiguous().reshape(&[-1]).shape(), vec![1024 * 512]);
+        assert_eq!(Tensor::cat(&[Clone::clone(&z), Clone::clone(&z)], 0).shape(), vec![2048, 512]);
+
+        let total = z.sum();
+        assert!(total.is_meta());
+        assert_eq!(total.dim(), 0);
+        assert!(total.to_list::<f32>().is_empty());
+        assert!(!x.matmul(&x).defined());
+        assert!(!(&y + &Tensor::ones(&[512])).defined());
+
+        let small = Tensor::ones(&[2, 3]).to(Device::meta());
+        assert_eq!(format!("{}", small), "tensor(..., size=[2, 3], device=meta)");
+        assert!(!small.to(Device::cpu()).defined());
+        assert!(!Tensor::ones(&[2]).to(Device::cuda(0)).defined());
+    }
+
+    #[test]
+    fn test_meta_kernels_for_comparison_sort_and_indexing() {
+        let meta = Options::default().device(Device::meta());
+        let x = Tensor::empty_with_options(&[4, 6], meta.clone());
+        let y = Tensor::empty_with_options(&[6], meta.clone());
+
+        let mask = x.lt(&y);
+        assert!(mask.is_meta());
+        assert_eq!((mask.shape(), mask.dtype()), (vec![4, 6], DType::Bool));
+        assert_eq!(x.isnan().dtype(), DType::Bool);
+        assert_eq!(x.masked_fill(&mask, 0.0).shape(), vec![4, 6]);
+        assert!(x.count_nonzero().is_meta());
+
+        let (values, indices) = x.sort(1, true);
+        assert_eq!((values.shape(), indices.dtype()), (vec![4, 6], DType::Int64));
+        let (values, indices) = x.topk(2, -1, true, true);
+        assert!(values.is_meta() && indices.is_meta());
+        assert_eq!(values.shape(), vec![4, 2]);
+        assert_eq!(x.kthvalue(3, 0, false).0.shape(), vec![6]);
+        assert_eq!(x.cumsum(1).shape(), vec![4, 6]);
+
+        let index = Tensor::empty_with_options(&[3], meta.dtype(DType::Int64));
+        assert_eq!(x.index_select(1, &index).shape(), vec![4, 3]);
+        assert_eq!(x.gather(0, &index.reshape(&[3, 1])).shape(), vec![3, 1]);
+        assert!(!x.gather(2, &index.reshape(&[3, 1])).defined());
+        assert!(!x.index_select(0, &Tensor::from_vec(vec![0i64], &[1])).defined());
+    }
+
+    struct HostBackedCuda;
+
+    impl Backend for HostBackedCuda {
+        fn device_type(&self) -> DeviceType {
+            DeviceType::CUDA
+        }
+
+        fn allocate(&self, size: usize, device: Device) -> Result<Storage, String> {
+            let buffer = Box::into_raw(vec![0u8; size].into_boxed_slice()) as *mut u8;
+            unsafe {
+                Storage::from_raw_parts(buffer, size, device, |ptr, size| {
+                    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, size)));
+                })
+            }
+        }
+
+        fn read(&self, src: &TensorImpl) -> Result<Vec<f64>, String> {
+            let ptr = src.storage().ok_or("No storage")?.data_ptr::<f32>();
+            let values = unsafe { std::slice::from_raw_parts(ptr, src.numel() as usize) };
+            Ok(values.iter().map(|&v| v as f64).collect())
+        }
+
+        fn write(&self, dst: &TensorImpl, data: &[f64]) -> Result<(), String> {
+            let ptr = dst.storage().ok_or("No storage")?.data_ptr::<f32>();
+            for (i, &v) in data.iter().enumerate() {
+                unsafe { *ptr.add(i) = v as f32 };
+            }
+            Ok(())
+        }
+
+        fn kernels(&self) -> KernelTable {
+            KernelTable {
+                unary: |input, op| {
+                    if op != UnaryOp::Neg {
+                        return Tensor::new();
+                    }
+                    let data: Vec<f64> = HostBackedCuda.read(input.impl_.as_ref().unwrap()).unwrap();
+                    let output = Tensor::empty_with_options(&input.shape(), Options::default().device(input.device()));
+                    let negated: Vec<f64> = data.iter().map(|&v| -v).collect();
+                    HostBackedCuda.write(output.impl_.as_ref().unwrap(), &negated).unwrap();
+                    output
+                },
+                binary: |_, _, _| Tensor::new(),
+                matmul: |_, _| Tensor::new(),
+                reduce: |_, _| Tensor::new(),
+                compare: |_, _, _| Tensor::new(),
+                predicate: |_, _| Tensor::new(),
+                sort: |_, _, _| Tensor::new(),
+                gather: |_, _, _| Tensor::new(),
+                index_select: |_, _, _| Tensor::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pluggable_backend_round_trip() {
+        assert!(backend(Device::cuda(0)).is_err());
+        assert!(register_backend(std::rc::Rc::new(HostBackedCuda)).is_none());
+
+        let mut x = Tensor::from_slice(&[1.0f32, -2.0, 3.0], &[3]);
+        x.set_requires_grad(true);
+        let on_device = x.to(Device::cuda(0));
+        assert_eq!(on_device.device(), Device::cuda(0));
+        assert!(on_device.to_list::<f32>().is_empty());
+        assert_eq!(on_device.grad_fn().unwrap().name(), "ToCopyBackward0");
+
+        let negated = -&on_device;
+        assert_eq!(negated.device(), Device::cuda(0));
+        assert_eq!(negated.cpu().to_list::<f32>(), vec![-1.0, 2.0, -3.0]);
+        assert!(!on_device.sqrt().defined());
+        assert!(!on_device.unary_op(|v| v * v).defined());
+        assert!(!(&on_device + &on_device).defined());
+
+        on_device.cpu().sum().backward();
+        assert_eq!(x.grad().to_list::<f32>(), vec![1.0, 1.0, 1.0]);
+
+        assert!(unregister_backend(DeviceType::CUDA).is_some());
+        assert!(!x.to(Device::cuda(0)).defined());
+    }
 }
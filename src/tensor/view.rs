@@ -0,0 +1,407 @@
+use crate::tensor::{Options, Tensor};
+use std::rc::Rc;
+
+impl Tensor {
+    pub fn view(&self, shape: &[i64]) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        let shape = match infer_shape(shape, impl_.numel()) {
+            Ok(shape) => shape,
+            Err(_) => return Self::new(),
+        };
+        let strides = match view_strides(impl_.shape(), impl_.strides(), &shape) {
+            Some(strides) => strides,
+            None => return Self::new(),
+        };
+
+        let input_shape = self.shape();
+        self.as_strided(&shape, &strides, impl_.storage_offset())
+            .with_grad_fn("ViewBackward0", &[self], move |grad| {
+                vec![grad.reshape(&input_shape)]
+            })
+    }
+
+    pub fn view_as(&self, other: &Self) -> Self {
+        if !other.defined() {
+            return Self::new();
+        }
+        self.view(&other.shape())
+    }
+
+    pub fn reshape(&self, shape: &[i64]) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        let shape = match infer_shape(shape, impl_.numel()) {
+            Ok(shape) => shape,
+            Err(_) => return Self::new(),
+        };
+
+        if view_strides(impl_.shape(), impl_.strides(), &shape).is_some() {
+            self.view(&shape)
+        } else {
+            self.contiguous().view(&shape)
+        }
+    }
+
+    pub fn contiguous(&self) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        if impl_.is_contiguous() {
+            return Clone::clone(self);
+        }
+
+        let copy = match impl_.contiguous_copy() {
+            Ok(mut copy) => {
+                copy.autograd_meta = None;
+                copy.options_mut().requires_grad = false;
+                Self {
+                    impl_: Some(Rc::new(copy)),
+                }
+            }
+            Err(_) => return Self::new(),
+        };
+        copy.with_grad_fn("CloneBackward0", &[self], |grad| vec![Clone::clone(grad)])
+    }
+
+    pub fn squeeze(&self, dim: Option<i64>) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+
+        let target = match dim {
+            Some(dim) => match normalize_dim(dim, impl_.dim()) {
+                Some(dim) => Some(dim),
+                None => return Self::new(),
+            },
+            None => None,
+        };
+
+        let mut shape = Vec::new();
+        let mut strides = Vec::new();
+        for (d, (&size, &stride)) in impl_.shape().iter().zip(impl_.strides().iter()).enumerate() {
+            let squeezed = size == 1 && target.is_none_or(|target| target == d);
+            if !squeezed {
+                shape.push(size);
+                strides.push(stride);
+            }
+        }
+
+        let input_shape = self.shape();
+        self.as_strided(&shape, &strides, impl_.storage_offset())
+            .with_grad_fn("SqueezeBackward0", &[self], move |grad| {
+                vec![grad.reshape(&input_shape)]
+            })
+    }
+
+    pub fn unsqueeze(&self, dim: i64) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        let dim = match normalize_dim(dim, impl_.dim() + 1) {
+            Some(dim) => dim,
+            None => return Self::new(),
+        };
+
+        let mut shape = impl_.shape().to_vec();
+        let mut strides = impl_.strides().to_vec();
+        let stride = if dim < shape.len() { shape[dim] * strides[dim] } else { 1 };
+        shape.insert(dim, 1);
+        strides.insert(dim, stride);
+
+        self.as_strided(&shape, &strides, impl_.storage_offset())
+            .with_grad_fn("UnsqueezeBackward0", &[self], move |grad| {
+                vec![grad.squeeze(Some(dim as i64))]
+            })
+    }
+
+    pub fn flatten(&self) -> Self {
+        self.flatten_range(0, -1)
+    }
+
+    pub fn flatten_range(&self, start_dim: i64, end_dim: i64) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+
+        let shape = self.shape();
+        if shape.is_empty() {
+            return self.reshape(&[1]);
+        }
+
+        let (start, end) = match (normalize_dim(start_dim, self.dim()), normalize_dim(end_dim, self.dim())) {
+            (Some(start), Some(end)) if start <= end => (start, end),
+            _ => return Self::new(),
+        };
+
+        let mut new_shape = shape[..start].to_vec();
+        new_shape.push(shape[start..=end].iter().product());
+        new_shape.extend_from_slice(&shape[end + 1..]);
+        self.reshape(&new_shape)
+    }
+
+    pub fn unflatten(&self, dim: i64, sizes: &[i64]) -> Self {
+        if !self.defined() {
+            return Self::new();
+        }
+
+        let shape = self.shape();
+        let dim = match normalize_dim(dim, self.dim()) {
+            Some(dim) => dim,
+            None => return Self::new(),
+        };
+        let sizes = match infer_shape(sizes, shape[dim]) {
+            Ok(sizes) => sizes,
+            Err(_) => return Self::new(),
+        };
+
+        let mut new_shape = shape[..dim].to_vec();
+        new_shape.extend_from_slice(&sizes);
+        new_shape.extend_from_slice(&shape[dim + 1..]);
+        self.reshape(&new_shape)
+    }
+
+    pub fn permute(&self, dims: &[i64]) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+
+        let ndim = impl_.dim();
+        if dims.len() != ndim as usize {
+            return Self::new();
+        }
+        let mut perm = Vec::with_capacity(dims.len());
+        for &dim in dims {
+            match normalize_dim(dim, ndim) {
+                Some(dim) if !perm.contains(&dim) => perm.push(dim),
+                _ => return Self::new(),
+            }
+        }
+
+        let shape: Vec<i64> = perm.iter().map(|&d| impl_.shape()[d]).collect();
+        let strides: Vec<i64> = perm.iter().map(|&d| impl_.strides()[d]).collect();
+
+        let mut inverse = vec![0i64; perm.len()];
+        for (i, &d) in perm.iter().enumerate() {
+            inverse[d] = i as i64;
+        }
+        self.as_strided(&shape, &strides, impl_.storage_offset())
+            .with_grad_fn("PermuteBackward0", &[self], move |grad| {
+                vec![grad.permute(&inverse)]
+            })
+    }
+
+    pub fn movedim(&self, source: i64, destination: i64) -> Self {
+        let ndim = self.dim();
+        let (source, destination) = match (normalize_dim(source, ndim), normalize_dim(destination, ndim)) {
+            (Some(source), Some(destination)) => (source as i64, destination as i64),
+            _ => return Self::new(),
+        };
+
+        let mut perm: Vec<i64> = (0..ndim).filter(|&d| d != source).collect();
+        perm.insert(destination as usize, source);
+        self.permute(&perm)
+    }
+
+    pub fn swapaxes(&self, axis0: i64, axis1: i64) -> Self {
+        let ndim = self.dim();
+        let (axis0, axis1) = match (normalize_dim(axis0, ndim), normalize_dim(axis1, ndim)) {
+            (Some(axis0), Some(axis1)) => (axis0, axis1),
+            _ => return Self::new(),
+        };
+
+        let mut perm: Vec<i64> = (0..ndim).collect();
+        perm.swap(axis0, axis1);
+        self.permute(&perm)
+    }
+
+    pub fn transpose(&self, dim0: i64, dim1: i64) -> Self {
+        self.swapaxes(dim0, dim1)
+    }
+
+    pub fn narrow(&self, dim: i64, start: i64, length: i64) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+        let dim = match normalize_dim(dim, impl_.dim()) {
+            Some(dim) if !impl_.shape().is_empty() => dim,
+            _ => return Self::new(),
+        };
+
+        let size = impl_.shape()[dim];
+        let start = if start < 0 { start + size } else { start };
+        if start < 0 || length < 0 || start + length > size {
+            return Self::new();
+        }
+
+        let mut shape = impl_.shape().to_vec();
+        shape[dim] = length;
+        let offset = impl_.storage_offset() + start * impl_.strides()[dim];
+
+        let input_shape = self.shape();
+        self.as_strided(&shape, impl_.strides(), offset)
+            .with_grad_fn("NarrowBackward0", &[self], move |grad| {
+                let mut sections = Vec::with_capacity(3);
+                let mut before = input_shape.clone();
+                before[dim] = start;
+                let mut after = input_shape.clone();
+                after[dim] = input_shape[dim] - start - length;
+                let options = Options::default().dtype(grad.dtype());
+                sections.push(Tensor::zeros_with_options(&before, options.clone()));
+                sections.push(Clone::clone(grad));
+                sections.push(Tensor::zeros_with_options(&after, options));
+                vec![Tensor::cat(&sections, dim as i64)]
+            })
+    }
+
+    pub fn expand(&self, shape: &[i64]) -> Self {
+        let impl_ = match self.impl_.as_ref() {
+            Some(impl_) => impl_,
+            None => return Self::new(),
+        };
+
+        let ndim = impl_.shape().len();
+        if shape.len() < ndim {
+            return Self::new();
+        }
+        let leading = shape.len() - ndim;
+
+        let mut new_shape = Vec::with_capacity(shape.len());
+        let mut new_strides = Vec::with_capacity(shape.len());
+        for (i, &size) in shape.iter().enumerate() {
+            if i < leading {
+                if size < 0 {
+                    return Self::new();
+                }
+                new_shape.push(size);
+                new_strides.push(0);
+                continue;
+            }
+
+            let old_size = impl_.shape()[i - leading];
+            let old_stride = impl_.strides()[i - leading];
+            if size == -1 || size == old_size {
+                new_shape.push(old_size);
+                new_strides.push(old_stride);
+            } else if old_size == 1 && size >= 0 {
+                new_shape.push(size);
+                new_strides.push(0);
+            } else {
+                return Self::new();
+            }
+        }
+
+        let input_shape = self.shape();
+        self.as_strided(&new_shape, &new_strides, impl_.storage_offset())
+            .with_grad_fn("ExpandBackward0", &[self], move |grad| {
+                vec![grad.sum_to_size(&input_shape)]
+            })
+    }
+
+    pub fn expand_as(&self, other: &Self) -> Self {
+        if !other.defined() {
+            return Self::new();
+        }
+        self.expand(&other.shape())
+    }
+
+    fn as_strided(&self, shape: &[i64], strides: &[i64], offset: i64) -> Self {
+        match self.impl_.as_ref().map(|impl_| impl_.as_strided(shape, strides, offset)) {
+            Some(Ok(impl_)) => Self {
+                impl_: Some(Rc::new(impl_)),
+            },
+            _ => Self::new(),
+        }
+    }
+}
+
+fn normalize_dim(dim: i64, ndim: i64) -> Option<usize> {
+    let dim = if dim < 0 { dim + ndim } else { dim };
+    if dim < 0 || dim >= ndim.max(1) {
+        return None;
+    }
+    Some(dim as usize)
+}
+
+fn infer_shape(shape: &[i64], numel: i64) -> Result<Vec<i64>, String> {
+    let mut inferred = None;
+    let mut known = 1i64;
+    for (i, &size) in shape.iter().enumerate() {
+        match size {
+            -1 if inferred.is_none() => inferred = Some(i),
+            -1 => return Err("Only one dimension can be inferred".to_string()),
+            size if size < 0 => return Err(format!("Invalid shape dimension {}", size)),
+            size => known *= size,
+        }
+    }
+
+    let mut shape = shape.to_vec();
+    if let Some(i) = inferred {
+        if known == 0 || numel % known != 0 {
+            return Err(format!("Shape {:?} is invalid for input of size {}", shape, numel));
+        }
+        shape[i] = numel / known;
+    } else if known != numel {
+        return Err(format!("Shape {:?} is invalid for input of size {}", shape, numel));
+    }
+    Ok(shape)
+}
+
+fn view_strides(old_shape: &[i64], old_strides: &[i64], new_shape: &[i64]) -> Option<Vec<i64>> {
+    if old_shape.is_empty() {
+        return Some(vec![1; new_shape.len()]);
+    }
+
+    let numel: i64 = old_shape.iter().product();
+    if numel == 0 {
+        let mut strides = vec![1i64; new_shape.len()];
+        for i in (0..new_shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * new_shape[i + 1].max(1);
+        }
+        return Some(strides);
+    }
+
+    let mut new_strides = vec![0i64; new_shape.len()];
+    let mut view_d = new_shape.len() as isize - 1;
+    let mut chunk_base_stride = old_strides[old_strides.len() - 1];
+    let mut tensor_numel = 1i64;
+    let mut view_numel = 1i64;
+
+    for tensor_d in (0..old_shape.len()).rev() {
+        tensor_numel *= old_shape[tensor_d];
+        let chunk_ends = tensor_d == 0
+            || (old_shape[tensor_d - 1] != 1
+                && old_strides[tensor_d - 1] != tensor_numel * chunk_base_stride);
+        if !chunk_ends {
+            continue;
+        }
+
+        while view_d >= 0 && (view_numel < tensor_numel || new_shape[view_d as usize] == 1) {
+            new_strides[view_d as usize] = view_numel * chunk_base_stride;
+            view_numel *= new_shape[view_d as usize];
+            view_d -= 1;
+        }
+        if view_numel != tensor_numel {
+            return None;
+        }
+        if tensor_d > 0 {
+            chunk_base_stride = old_strides[tensor_d - 1];
+            tensor_numel = 1;
+            view_numel = 1;
+        }
+    }
+
+    if view_d != -1 {
+        return None;
+    }
+    Some(new_strides)
+}